@@ -1,3 +1,5 @@
+use crate::level::map::LevelChunk;
+use crate::spell_registry::SpellRegistry;
 use bevy::asset::*;
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::Aseprite;
@@ -14,6 +16,12 @@ pub struct GameAssets {
     #[asset(path = "image/level.aseprite")]
     pub level: Handle<Aseprite>,
 
+    #[asset(path = "data/registry.spell.json")]
+    pub spells: Handle<SpellRegistry>,
+
+    #[asset(path = "data/multiplay_arena.level.json")]
+    pub multiplay_arena: Handle<LevelChunk>,
+
     #[asset(path = "image/title.aseprite")]
     pub title: Handle<Aseprite>,
 
@@ -99,9 +107,9 @@ pub struct GameAssets {
     #[asset(path = "audio/カーソル移動2.ogg")]
     pub cursor2: Handle<AudioSource>,
 
-    // #[asset(path = "audio/カーソル移動8.ogg")]
-    // pub cursor8: Handle<AudioSource>,
-    //
+    #[asset(path = "audio/カーソル移動8.ogg")]
+    pub cursor8: Handle<AudioSource>,
+
     #[asset(path = "audio/爆発3_drop.ogg")]
     pub drop: Handle<AudioSource>,
 
@@ -0,0 +1,131 @@
+// レベルを検証するコマンドラインツールです
+// ゲームを起動しなくても、壊れたレベルをコミット前に見つけられます
+//
+// cargo run --bin validate_level -- assets/image/level.aseprite assets/data/multiplay_arena.level.json
+//
+// aseprite ファイルはすべてのスライスをそれぞれひとつのレベルとして、
+// *.level.json ファイルはそのファイルをひとつのレベルとして、ゲームと同じ手順で読み込みます
+// そのうえで入口やエンティティの配置と、入口から到達できない床の区域を検査します
+// aseprite ファイルの知らない色のピクセルはすべて報告し、壁とみなしてほかの検査も続けます
+// Spell エンティティの spell プロパティは、ゲームに同梱される呪文定義ファイルの呪文と照合します
+// 誤りは標準エラー出力に出力し、ひとつでも誤りがあれば、終了コード 1 で終了します
+
+use aseprite_loader::loader::AsepriteFile;
+use magiaforge::level::format::{
+    find_placement_errors, find_unreachable_areas, LevelData, LevelError,
+};
+use magiaforge::level::map::rgba_to_level_data_with_errors;
+use magiaforge::spell_registry::SpellRegistry;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let paths: Vec<String> = std::env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: validate_level <level.aseprite | *.level.json>...");
+        return ExitCode::from(2);
+    }
+
+    if let Err(err) =
+        SpellRegistry::from_json(include_str!("../../assets/data/registry.spell.json"))
+    {
+        eprintln!("registry.spell.json: {}", err);
+        return ExitCode::from(2);
+    }
+
+    let mut error_count = 0;
+    for path in paths.iter() {
+        match load_levels(Path::new(path)) {
+            Ok(levels) => {
+                for (name, level) in levels {
+                    let errors = validate_level(level);
+                    for err in errors.iter() {
+                        eprintln!("{}: {}", name, err);
+                    }
+                    if errors.is_empty() {
+                        println!("{}: ok", name);
+                    }
+                    error_count += errors.len();
+                }
+            }
+            Err(message) => {
+                eprintln!("{}: {}", path, message);
+                error_count += 1;
+            }
+        }
+    }
+
+    if 0 < error_count {
+        eprintln!("{} error(s) found", error_count);
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 変換したレベルと、変換を続けられた誤りの一覧です
+type ConvertedLevel = Result<(LevelData, Vec<LevelError>), LevelError>;
+
+/// ファイルを読み込み、含まれるレベルを名前とともに返します
+/// aseprite ファイルの色の誤りなど、レベルごとの変換の誤りはレベルごとの結果として返します
+fn load_levels(path: &Path) -> Result<Vec<(String, ConvertedLevel)>, String> {
+    let bytes = std::fs::read(path).map_err(|err| format!("failed to read: {}", err))?;
+    let file_name = path.to_string_lossy().to_string();
+
+    if file_name.ends_with(".level.json") {
+        let json = String::from_utf8_lossy(&bytes);
+        let level = LevelData::from_json(&json).map(|data| (data, Vec::new()));
+        return Ok(vec![(file_name, level)]);
+    }
+
+    if file_name.ends_with(".aseprite") {
+        let aseprite = AsepriteFile::load(&bytes)
+            .map_err(|err| format!("failed to parse aseprite: {:?}", err))?;
+        let (width, height) = aseprite.size();
+        let mut pixels = vec![0; width as usize * height as usize * 4];
+        aseprite
+            .combined_frame_image(0, &mut pixels)
+            .map_err(|err| format!("failed to render aseprite: {:?}", err))?;
+
+        let mut levels = Vec::new();
+        for slice in aseprite.slices().iter() {
+            let Some(key) = slice.slice_keys.first() else {
+                continue;
+            };
+            levels.push((
+                format!("{}[{}]", file_name, slice.name),
+                rgba_to_level_data_with_errors(
+                    &pixels,
+                    width as u32,
+                    height as u32,
+                    key.x,
+                    key.x + key.width as i32,
+                    key.y,
+                    key.y + key.height as i32,
+                ),
+            ));
+        }
+        return Ok(levels);
+    }
+
+    Err("unsupported file type".to_string())
+}
+
+/// レベルの誤りをすべて返します
+/// 地形の誤りがある場合はレベルを組み立てられないため、それまでの誤りだけを返します
+fn validate_level(level: ConvertedLevel) -> Vec<LevelError> {
+    let (data, mut errors) = match level {
+        Ok(level) => level,
+        Err(err) => return vec![err],
+    };
+    let chunk = match data.build_chunk() {
+        Ok(chunk) => chunk,
+        Err(err) => {
+            errors.push(err);
+            return errors;
+        }
+    };
+    errors.extend(find_placement_errors(&chunk));
+    errors.extend(find_unreachable_areas(&chunk));
+    errors
+}
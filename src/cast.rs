@@ -6,20 +6,31 @@ use crate::{
     },
     controller::remote::{send_remote_message, RemoteMessage},
     entity::{
-        actor::{Actor, ActorGroup},
+        actor::{Actor, ActorGroup, CastEffects},
+        barrier::SpawnBarrier,
+        beam::SpawnBeam,
         bullet::{spawn_bullet, SpawnBullet, BULLET_SPAWNING_MARGIN},
         life::Life,
-        slime_seed::SpawnSlimeSeed,
+        lob::SpawnLob,
+        status_effect::StatusEffects,
+        summon::get_summon_group,
+        summon_seed::SpawnSummonSeed,
+        teleport::CastTeleport,
         witch::WITCH_COLLIDER_RADIUS,
     },
+    random::GameRng,
     se::{SEEvent, SE},
     spell::SpellType,
-    spell_props::SpellCast,
+    spell_props::{BulletProps, SpellCast},
+    spell_registry::SpellRegistry,
+    wand::Wand,
+    wand_props::WandStats,
 };
 use bevy::prelude::*;
-use bevy_rapier2d::prelude::ExternalImpulse;
+use bevy_rapier2d::prelude::{ExternalImpulse, Group};
 use bevy_simple_websocket::ClientMessage;
-use rand::random;
+use rand::rngs::StdRng;
+use rand::Rng;
 use uuid::Uuid;
 
 /// 現在のインデックスをもとに呪文を唱えます
@@ -28,147 +39,464 @@ use uuid::Uuid;
 pub fn cast_spell(
     commands: &mut Commands,
     assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
     actor_entity: Entity,
     actor: &mut Actor,
     actor_life: &mut Life,
     actor_transform: &Transform,
     actor_impulse: &mut ExternalImpulse,
+    mut actor_status: Option<&mut StatusEffects>,
     online: bool,
     writer: &mut EventWriter<ClientMessage>,
     se_writer: &mut EventWriter<SEEvent>,
-    slime_writer: &mut EventWriter<SpawnSlimeSeed>,
+    seed_writer: &mut EventWriter<SpawnSummonSeed>,
+    beam_writer: &mut EventWriter<SpawnBeam>,
+    barrier_writer: &mut EventWriter<SpawnBarrier>,
+    lob_writer: &mut EventWriter<SpawnLob>,
+    teleport_writer: &mut EventWriter<CastTeleport>,
     wand_index: usize,
 ) {
     if let Some(ref mut wand) = &mut actor.wands[wand_index] {
-        // 1フレームあたりの残りの呪文詠唱回数
-        // MultipleCast で増加することがあります
-        let mut multicast = 1;
-
         if 0 < wand.delay {
             return;
         }
 
-        while 0 < multicast && wand.index < MAX_SPELLS_IN_WAND {
-            if let Some(spell) = wand.slots[wand.index] {
-                let props = spell.spell_type.to_props();
-
-                wand.delay += props.cast_delay.max(1);
-                multicast -= 1;
-
-                match props.cast {
-                    SpellCast::Bullet {
-                        slice,
-                        collier_radius,
-                        speed,
-                        lifetime,
-                        damage,
-                        impulse,
-                        scattering,
-                        light_intensity,
-                        light_radius,
-                        light_color_hlsa,
-                    } => {
-                        let normalized = actor.pointer.normalize();
-                        let angle = actor.pointer.to_angle();
-                        let angle_with_random = angle + (random::<f32>() - 0.5) * scattering;
-                        let direction = Vec2::from_angle(angle_with_random);
-                        let range = WITCH_COLLIDER_RADIUS + BULLET_SPAWNING_MARGIN;
-                        let bullet_position =
-                            actor_transform.translation.truncate() + range * normalized;
-
-                        let spawn = SpawnBullet {
-                            uuid: Uuid::new_v4(),
-                            position: bullet_position,
-                            velocity: direction
-                                * speed
-                                * (1.0 + actor.effects.bullet_speed_buff_factor),
-                            bullet_lifetime: lifetime,
-                            sender: Some(actor.uuid),
-                            damage: damage + actor.effects.bullet_damage_buff_amount,
-                            impulse,
-                            slice: slice.to_string(),
-                            collier_radius,
-                            light_intensity,
-                            light_radius,
-                            light_color_hlsa,
-                            homing: actor.effects.homing,
-                            group: match actor.actor_group {
-                                ActorGroup::Player => WITCH_BULLET_GROUP,
-                                ActorGroup::Enemy => ENEMY_BULLET_GROUP,
-                            },
-                            filter: match actor.actor_group {
-                                ActorGroup::Player => ENEMY_GROUP,
-                                ActorGroup::Enemy => WITCH_GROUP,
-                            } | ENTITY_GROUP
-                                | WALL_GROUP,
-                        };
-
-                        spawn_bullet(commands, assets.atlas.clone(), se_writer, &spawn);
-                        actor.effects = default();
-
-                        send_remote_message(writer, online, &RemoteMessage::Fire(spawn));
-                    }
-                    SpellCast::BulletSpeedUpDown { delta } => {
-                        actor.effects.bullet_speed_buff_factor =
-                            (actor.effects.bullet_speed_buff_factor + delta)
-                                .max(-0.9)
-                                .min(3.0);
-                    }
-                    SpellCast::Heal => {
-                        if spell.spell_type == SpellType::Heal
-                            && actor_life.life == actor_life.max_life
-                        {
-                            wand.delay += 1;
-                        }
-
-                        actor_life.life = (actor_life.life + 2).min(actor_life.max_life);
-                        se_writer.send(SEEvent::pos(
-                            SE::Heal,
-                            actor_transform.translation.truncate(),
-                        ));
-                    }
-                    SpellCast::MultipleCast { amount } => {
-                        multicast += amount;
-                    }
-                    SpellCast::Homing => {
-                        actor.effects.homing = (actor.effects.homing + 0.01).max(-0.1).min(0.1);
-                    }
-                    SpellCast::HeavyShot => {
-                        actor.effects.bullet_damage_buff_amount += 5;
-                    }
-                    SpellCast::SummonSlime { friend } => {
-                        slime_writer.send(SpawnSlimeSeed {
-                            from: actor_transform.translation.truncate(),
-                            to: actor_transform.translation.truncate() + actor.pointer,
-                            owner: actor_entity,
-                            actor_group: match (actor.actor_group, friend) {
-                                (ActorGroup::Player, true) => ActorGroup::Player,
-                                (ActorGroup::Player, false) => ActorGroup::Enemy,
-                                (ActorGroup::Enemy, true) => ActorGroup::Enemy,
-                                (ActorGroup::Enemy, false) => ActorGroup::Player,
-                            },
-                        });
+        let stats = wand.stats;
+        let (group, filter) = get_bullet_groups(actor.actor_group);
+
+        // 常時詠唱の修飾は、この詠唱で発射されるすべての弾丸に適用されます
+        let mut always_effects = CastEffects::default();
+        for spell_type in wand.always_cast.iter() {
+            if let SpellCast::Modifier(modifiers) = &spell_type.to_props(registry).cast {
+                always_effects.modifiers.extend(modifiers.iter().copied());
+            }
+        }
+
+        // 常時詠唱の弾丸は、マナを消費せずに詠唱のたびに発射されます
+        // 修飾と弾丸以外の呪文は常時詠唱としては効果がありません
+        for spell_type in wand.always_cast.iter() {
+            if let SpellCast::Bullet(bullet) = &spell_type.to_props(registry).cast {
+                let spawn = bullet_to_spawn(
+                    bullet,
+                    &always_effects,
+                    &mut rng.combat,
+                    Some(actor.uuid),
+                    get_bullet_position(actor.pointer, actor_transform),
+                    actor.pointer.to_angle(),
+                    stats.spread,
+                    group,
+                    filter,
+                    Vec::new(),
+                );
+                spawn_bullet(commands, assets.atlas.clone(), se_writer, &spawn);
+                send_remote_message(writer, online, &RemoteMessage::Fire(spawn));
+            }
+        }
+
+        actor.effects = always_effects.clone();
+
+        // 消費マナの合計が杖の扱えるマナの上限を超えている場合は、詠唱全体が不発になり、
+        // 詠唱遅延だけが発生します
+        let mut drawn = draw_cast(registry, wand);
+        if exceeds_mana_capacity(&stats, &drawn) {
+            se_writer.send(SEEvent::pos(
+                SE::Fizzle,
+                actor_transform.translation.truncate(),
+            ));
+            drawn.clear();
+        }
+
+        for DrawnSpell {
+            spell_type,
+            tier,
+            payload,
+            mana_cost,
+        } in drawn
+        {
+            let props = spell_type.to_props(registry);
+
+            // マナが不足している場合はその呪文だけが不発になり、詠唱遅延だけが発生します
+            if actor.mana < mana_cost as i32 {
+                se_writer.send(SEEvent::pos(
+                    SE::Fizzle,
+                    actor_transform.translation.truncate(),
+                ));
+                continue;
+            }
+
+            actor.mana -= mana_cost as i32;
+
+            // ペイロードと常時詠唱の呪文は強化段階を持たないため、基本の性能のまま詠唱されます
+            match &props.cast.upgrade(tier) {
+                SpellCast::Bullet(bullet) => {
+                    let spawn = bullet_to_spawn(
+                        bullet,
+                        &actor.effects,
+                        &mut rng.combat,
+                        Some(actor.uuid),
+                        get_bullet_position(actor.pointer, actor_transform),
+                        actor.pointer.to_angle(),
+                        stats.spread,
+                        group,
+                        filter,
+                        payload,
+                    );
+
+                    spawn_bullet(commands, assets.atlas.clone(), se_writer, &spawn);
+                    actor.effects = always_effects.clone();
+
+                    send_remote_message(writer, online, &RemoteMessage::Fire(spawn));
+                }
+                SpellCast::Modifier(modifiers) => {
+                    actor.effects.modifiers.extend(modifiers.iter().copied());
+                }
+                SpellCast::Heal => {
+                    if spell_type == SpellType::Heal && actor_life.life == actor_life.max_life {
+                        wand.delay += 1;
                     }
-                    SpellCast::Dash => {
-                        actor_impulse.impulse += if 0.0 < actor.move_direction.length() {
-                            actor.move_direction
-                        } else {
-                            actor.pointer.normalize()
-                        } * 50000.0;
-                        se_writer.send(SEEvent::pos(
-                            SE::Shuriken,
-                            actor_transform.translation.truncate(),
-                        ));
+
+                    actor_life.life = (actor_life.life + 2).min(actor_life.max_life);
+                    se_writer.send(SEEvent::pos(
+                        SE::Heal,
+                        actor_transform.translation.truncate(),
+                    ));
+                }
+                SpellCast::MultipleCast { .. } => {
+                    // 詠唱回数の増加は draw_cast で処理済みです
+                }
+                SpellCast::Summon {
+                    summon_type,
+                    friend,
+                    duration,
+                } => {
+                    seed_writer.send(SpawnSummonSeed {
+                        from: actor_transform.translation.truncate(),
+                        to: actor_transform.translation.truncate() + actor.pointer,
+                        owner: actor_entity,
+                        actor_group: get_summon_group(actor.actor_group, *friend),
+                        summon_type: *summon_type,
+                        duration: Some(*duration),
+                    });
+                }
+                SpellCast::Dash => {
+                    actor_impulse.impulse += if 0.0 < actor.move_direction.length() {
+                        actor.move_direction
+                    } else {
+                        actor.pointer.normalize()
+                    } * 50000.0;
+                    se_writer.send(SEEvent::pos(
+                        SE::Shuriken,
+                        actor_transform.translation.truncate(),
+                    ));
+                }
+                SpellCast::Teleport { range } => {
+                    teleport_writer.send(CastTeleport {
+                        actor: actor_entity,
+                        range: *range,
+                    });
+                }
+                SpellCast::Beam(beam) => {
+                    let spawn = SpawnBeam {
+                        sender: actor.uuid,
+                        props: beam.clone(),
+                        group,
+                        filter,
+                    };
+                    beam_writer.send(spawn.clone());
+                    send_remote_message(writer, online, &RemoteMessage::Beam(spawn));
+                }
+                SpellCast::Lob(lob) => {
+                    let from = actor_transform.translation.truncate();
+                    let spawn = SpawnLob {
+                        sender: Some(actor.uuid),
+                        from,
+                        to: from + actor.pointer.clamp_length_max(lob.range),
+                        props: lob.clone(),
+                        payload,
+                        payload_seed: rng.combat.gen(),
+                        group,
+                        filter,
+                    };
+                    lob_writer.send(spawn.clone());
+                    send_remote_message(writer, online, &RemoteMessage::Lob(spawn));
+                }
+                SpellCast::Barrier(barrier) => {
+                    let (group, filter) = get_barrier_groups(actor.actor_group);
+                    let spawn = SpawnBarrier {
+                        position: actor_transform.translation.truncate(),
+                        angle: actor.pointer.to_angle(),
+                        props: barrier.clone(),
+                        group,
+                        filter,
+                    };
+                    barrier_writer.send(spawn.clone());
+                    send_remote_message(writer, online, &RemoteMessage::Barrier(spawn));
+                }
+                SpellCast::SelfEffect(effect) => {
+                    if let Some(ref mut status) = actor_status {
+                        status.apply(*effect);
                     }
+                    se_writer.send(SEEvent::pos(
+                        SE::Heal,
+                        actor_transform.translation.truncate(),
+                    ));
                 }
+            }
+        }
+
+        actor.effects = default();
+
+        // 最後の呪文まで唱え終えたら、杖を再装填します
+        if wand.is_cycle_finished() {
+            wand.recharge(&mut rng.combat);
+        }
+    }
+}
+
+/// アクターの発射する弾丸の衝突グループと、衝突対象のグループを返します
+fn get_bullet_groups(actor_group: ActorGroup) -> (Group, Group) {
+    match actor_group {
+        ActorGroup::Player => (WITCH_BULLET_GROUP, ENEMY_GROUP | ENTITY_GROUP | WALL_GROUP),
+        ActorGroup::Enemy => (ENEMY_BULLET_GROUP, WITCH_GROUP | ENTITY_GROUP | WALL_GROUP),
+    }
+}
+
+/// アクターの生成する障壁の衝突グループと、衝突対象のグループを返します
+/// 障壁は詠唱者と同じグループに属し、敵の弾丸とだけ衝突するため、味方の弾丸やアクターは通り抜けます
+fn get_barrier_groups(actor_group: ActorGroup) -> (Group, Group) {
+    match actor_group {
+        ActorGroup::Player => (WITCH_GROUP, ENEMY_BULLET_GROUP),
+        ActorGroup::Enemy => (ENEMY_GROUP, WITCH_BULLET_GROUP),
+    }
+}
+
+/// アクターが弾丸を発射する位置を返します
+/// 自分自身に衝突しないよう、アクターの向いている方向へ少し離れた位置になります
+fn get_bullet_position(pointer: Vec2, actor_transform: &Transform) -> Vec2 {
+    let range = WITCH_COLLIDER_RADIUS + BULLET_SPAWNING_MARGIN;
+    actor_transform.translation.truncate() + range * pointer.normalize()
+}
+
+/// トリガー付きの弾丸や投擲が保持していたペイロードを、弾丸の位置から詠唱します
+/// ペイロードはローカルとリモートの双方の弾丸でそれぞれ詠唱されるため、
+/// ここで生成された弾丸はリモートには送信しません
+/// rng は弾丸の拡散に使われ、双方で同じシードの乱数生成器を渡せば同じ弾丸が発射されます
+/// Heal や Dash、SelfEffect、Beam など詠唱者を必要とする呪文は、ペイロードとしては効果がありません
+/// 定義ファイルの異なる他のプレイヤーから受け取った未知の呪文も、同様に効果のない呪文として読み飛ばします
+pub fn cast_payload(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut StdRng,
+    se_writer: &mut EventWriter<SEEvent>,
+    payload: &[SpellType],
+    sender: Option<Uuid>,
+    position: Vec2,
+    angle: f32,
+    group: Group,
+    filter: Group,
+) {
+    let mut effects = CastEffects::default();
+    let mut index = 0;
+    while index < payload.len() {
+        let spell_type = payload[index];
+        index += 1;
+        let Some(props) = registry.get(spell_type) else {
+            continue;
+        };
+        match &props.cast {
+            SpellCast::Bullet(bullet) => {
+                // ペイロードの中にトリガー付きの弾丸がある場合は、さらにその後続をペイロードとして渡します
+                let length = match bullet.trigger {
+                    Some(_) => payload_length(registry, &payload[index..]),
+                    None => 0,
+                };
+                let nested = payload[index..index + length].to_vec();
+                index += length;
+
+                let spawn = bullet_to_spawn(
+                    bullet, &effects, rng, sender, position, angle, 0.0, group, filter, nested,
+                );
+                spawn_bullet(commands, assets.atlas.clone(), se_writer, &spawn);
+                effects = default();
+            }
+            SpellCast::Modifier(modifiers) => {
+                effects.modifiers.extend(modifiers.iter().copied());
+            }
+            cast => {
+                // ペイロードとして効果のない呪文が保持していたペイロードは、まとめて読み飛ばします
+                if cast.takes_payload() {
+                    index += payload_length(registry, &payload[index..]);
+                }
+            }
+        }
+    }
+}
+
+/// 弾丸系の呪文に詠唱中の修飾を順に適用し、発射する弾丸の情報を組み立てます
+/// spread は杖による拡散の補正です
+fn bullet_to_spawn(
+    bullet: &BulletProps,
+    effects: &CastEffects,
+    rng: &mut StdRng,
+    sender: Option<Uuid>,
+    position: Vec2,
+    angle: f32,
+    spread: f32,
+    group: Group,
+    filter: Group,
+    payload: Vec<SpellType>,
+) -> SpawnBullet {
+    let mut bullet = bullet.clone();
+    for modifier in effects.modifiers.iter() {
+        modifier.apply(&mut bullet);
+    }
+    bullet.scattering = (bullet.scattering + spread).max(0.0);
+
+    let angle_with_random = angle + (rng.gen::<f32>() - 0.5) * bullet.scattering;
+    let direction = Vec2::from_angle(angle_with_random);
+    SpawnBullet {
+        uuid: Uuid::new_v4(),
+        position,
+        velocity: direction * bullet.speed,
+        bullet_lifetime: bullet.lifetime,
+        sender,
+        damage: bullet.damage,
+        damage_type: bullet.damage_type,
+        impulse: bullet.impulse,
+        slice: bullet.slice,
+        collier_radius: bullet.collier_radius,
+        scale: bullet.scale,
+        light_intensity: bullet.light_intensity,
+        light_radius: bullet.light_radius,
+        light_color_hlsa: bullet.light_color_hlsa,
+        homing: bullet.homing,
+        bounces: bullet.bounces,
+        pierce: bullet.pierce,
+        status_effects: bullet.status_effects,
+        explosion_radius: bullet.explosion_radius,
+        terrain: bullet.terrain,
+        group,
+        filter,
+        trigger: bullet.trigger,
+        payload,
+    }
+}
+
+/// 1回の詠唱で杖から取り出された呪文ひとつぶんです
+#[derive(Clone, Debug)]
+pub struct DrawnSpell {
+    pub spell_type: SpellType,
+
+    /// 呪文の強化段階
+    pub tier: u32,
+
+    /// トリガー付きの弾丸が保持するペイロード
+    pub payload: Vec<SpellType>,
+
+    /// ペイロードを含めた消費マナ
+    pub mana_cost: u32,
+}
+
+/// 杖の現在のインデックスから、1回の詠唱で唱えられる呪文を順に取り出します
+/// MultipleCast による詠唱回数の増加とトリガーのペイロードの取り出しもここで処理します
+/// 杖のインデックスと詠唱遅延を進める以外の副作用はないため、詠唱のシミュレーションにも使われます
+/// 最後の呪文まで取り出しても再装填は行いません
+/// 定義ファイルにない呪文は空欄と同じように扱い、ペイロードに含まれる場合は遅延とマナを加算しません
+pub fn draw_cast(registry: &SpellRegistry, wand: &mut Wand) -> Vec<DrawnSpell> {
+    let cast_delay_multiplier = wand.stats.cast_delay_multiplier;
+
+    // 1フレームあたりの残りの呪文詠唱回数
+    // MultipleCast で増加することがあります
+    let mut multicast = 1;
+
+    let mut drawn = Vec::new();
+
+    while 0 < multicast && wand.index < MAX_SPELLS_IN_WAND {
+        let spell = wand
+            .spell_at(wand.index)
+            .and_then(|spell| registry.get(spell.spell_type).map(|props| (spell, props)));
+        if let Some((spell, props)) = spell {
+            // トリガー付きの弾丸や投擲の場合は、続く呪文をペイロードとして取り出します
+            // ペイロードの呪文もこの時点で詠唱されたものとして、詠唱遅延とマナを消費します
+            let payload = if props.cast.takes_payload() {
+                draw_payload(registry, wand)
             } else {
-                // 空欄の場合は残り詠唱回数は減りません
+                Vec::new()
+            };
+            let cast_delay = props.cast_delay
+                + payload
+                    .iter()
+                    .filter_map(|s| registry.get(*s))
+                    .map(|p| p.cast_delay)
+                    .sum::<u32>();
+            let mana_cost = props.mana_cost
+                + payload
+                    .iter()
+                    .filter_map(|s| registry.get(*s))
+                    .map(|p| p.mana_cost)
+                    .sum::<u32>();
+
+            wand.delay += ((cast_delay as f32 * cast_delay_multiplier).round() as u32).max(1);
+            multicast -= 1;
+
+            if let SpellCast::MultipleCast { amount } = &props.cast {
+                multicast += *amount;
             }
 
-            wand.index = wand.index + 1;
+            drawn.push(DrawnSpell {
+                spell_type: spell.spell_type,
+                tier: spell.tier,
+                payload,
+                mana_cost,
+            });
+        } else {
+            // 空欄や未知の呪文の場合は残り詠唱回数は減りません
         }
 
-        actor.effects = default();
-        wand.index %= MAX_SPELLS_IN_WAND;
+        wand.index = wand.index + 1;
+    }
+
+    drawn
+}
+
+/// 1回の詠唱で取り出された呪文の消費マナの合計が、杖の扱えるマナの上限を超えているかどうかを返します
+/// 実際の詠唱と詠唱のシミュレーションの双方がこの判定で不発を決めます
+pub fn exceeds_mana_capacity(stats: &WandStats, drawn: &[DrawnSpell]) -> bool {
+    stats.mana_capacity < drawn.iter().map(|d| d.mana_cost).sum::<u32>()
+}
+
+/// 杖の現在の呪文に続く呪文から、1回の詠唱で消費されるぶんをペイロードとして取り出します
+/// 取り出した呪文は詠唱済みとして扱われ、杖のインデックスはペイロードの最後の呪文まで進みます
+fn draw_payload(registry: &SpellRegistry, wand: &mut Wand) -> Vec<SpellType> {
+    let rest: Vec<(usize, SpellType)> = (wand.index + 1..MAX_SPELLS_IN_WAND)
+        .filter_map(|i| wand.spell_at(i).map(|spell| (i, spell.spell_type)))
+        .collect();
+    let spells: Vec<SpellType> = rest.iter().map(|(_, spell)| *spell).collect();
+    let length = payload_length(registry, &spells);
+    if 0 < length {
+        wand.index = rest[length - 1].0;
+    }
+    spells[..length].to_vec()
+}
+
+/// 呪文の列の先頭から、1回の詠唱で消費される呪文の数を返します
+/// MultipleCast は詠唱回数を増やし、トリガー付きの弾丸や投擲はさらに自身のペイロードを消費します
+/// 未知の呪文は効果のない呪文と同じく、1回ぶんの詠唱として数えます
+fn payload_length(registry: &SpellRegistry, spells: &[SpellType]) -> usize {
+    let mut multicast = 1;
+    let mut index = 0;
+    while 0 < multicast && index < spells.len() {
+        multicast -= 1;
+        if let Some(props) = registry.get(spells[index]) {
+            if let SpellCast::MultipleCast { amount } = &props.cast {
+                multicast += amount;
+            } else if props.cast.takes_payload() {
+                index += payload_length(registry, &spells[index + 1..]);
+            }
+        }
+        index += 1;
     }
+    index
 }
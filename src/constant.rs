@@ -20,6 +20,20 @@ pub const MAX_WANDS: usize = 4;
 
 pub const MAX_SPELLS_IN_WAND: usize = 8;
 
+// 杖の常時詠唱の呪文として表示できる最大数
+pub const MAX_ALWAYS_CAST_IN_WAND: usize = 2;
+
+// ひとりの詠唱者が同時に召喚しておける生き物の最大数
+// 超えた場合は古いものから消滅します
+pub const MAX_SUMMONS_PER_CASTER: usize = 3;
+
+// 呪文の強化段階の上限
+// 同じ段階の同じ呪文を重ねると1段階強化されます
+pub const MAX_SPELL_TIER: u32 = 3;
+
+// 強化段階1段階あたりの呪文の性能の増加率
+pub const SPELL_TIER_BONUS: f32 = 0.25;
+
 pub const MAX_ITEMS_IN_INVENTORY_ROW: usize = 8;
 
 pub const MAX_ITEMS_IN_INVENTORY_COLUMN: usize = 8;
@@ -48,8 +62,8 @@ pub const WALL_HEIGHT: f32 = 8.0;
 
 // レイヤー ///////////////////////////////////////////////////////////////////////
 
-/// スライムシードは空中にあるので一番上
-pub const SLIME_SEED_LAYER_Z: f32 = 21.0;
+/// 召喚の種や投擲された弾は空中にあるので一番上
+pub const SUMMON_SEED_LAYER_Z: f32 = 21.0;
 
 /// キャラクターやチェストなどのレイヤー
 pub const ENTITY_LAYER_Z: f32 = 20.0;
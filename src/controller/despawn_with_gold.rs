@@ -1,5 +1,6 @@
 use crate::entity::gold::spawn_gold;
 use crate::entity::life::Life;
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
 use crate::{asset::GameAssets, set::GameSet, states::GameState};
 use bevy::prelude::*;
@@ -19,6 +20,7 @@ fn dead_enemy(
     assets: Res<GameAssets>,
     mut query: Query<(Entity, &DespawnWithGold, &Life, &Transform)>,
     mut writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, enemy, enemy_life, transform) in query.iter_mut() {
         if enemy_life.life <= 0 {
@@ -29,6 +31,7 @@ fn dead_enemy(
                 spawn_gold(
                     &mut commands,
                     &assets,
+                    &mut rng,
                     transform.translation.x,
                     transform.translation.y,
                 );
@@ -1,10 +1,15 @@
 use crate::constant::*;
 use crate::controller::player::Player;
 use crate::entity::actor::ActorGroup;
+use crate::entity::barrier::SpawnBarrier;
+use crate::entity::beam::SpawnBeam;
 use crate::entity::bullet::SpawnBullet;
 use crate::entity::life::Life;
+use crate::entity::lob::SpawnLob;
+use crate::entity::teleport::TeleportActor;
 use crate::inventory::Inventory;
 use crate::level::{setup_level, CurrentLevel, GameLevel};
+use crate::random::GameRng;
 use crate::se::SE;
 use crate::{
     asset::GameAssets,
@@ -67,6 +72,14 @@ pub enum RemoteMessage {
     },
     // 弾を発射したことを通知します
     Fire(SpawnBullet),
+    // ビームを発射したこと、または発射し続けていることを通知します
+    Beam(SpawnBeam),
+    // 障壁を生成したことを通知します
+    Barrier(SpawnBarrier),
+    // 弾を投擲したことを通知します
+    Lob(SpawnLob),
+    // 瞬間移動したことを通知します
+    Teleport(TeleportActor),
     // ダメージを受けたことを通知します
     Hit {
         sender: Uuid,
@@ -183,6 +196,11 @@ fn receive_events(
     frame_count: Res<FrameCount>,
     life_bar_res: Res<LifeBarResource>,
     mut writer: EventWriter<SEEvent>,
+    mut beam_writer: EventWriter<SpawnBeam>,
+    mut barrier_writer: EventWriter<SpawnBarrier>,
+    mut lob_writer: EventWriter<SpawnLob>,
+    mut teleport_writer: EventWriter<TeleportActor>,
+    mut rng: ResMut<GameRng>,
 ) {
     // キャラクターを生成されたときに実際に反映させるのは次のフレームからですが、
     // 1フレームに複数のメッセージが届くことがあるため、
@@ -272,6 +290,18 @@ fn receive_events(
                                 &spawning,
                             );
                         }
+                        RemoteMessage::Beam(spawning) => {
+                            beam_writer.send(spawning);
+                        }
+                        RemoteMessage::Barrier(spawning) => {
+                            barrier_writer.send(spawning);
+                        }
+                        RemoteMessage::Lob(spawning) => {
+                            lob_writer.send(spawning);
+                        }
+                        RemoteMessage::Teleport(teleport) => {
+                            teleport_writer.send(teleport);
+                        }
                         RemoteMessage::Hit {
                             sender: _sender,
                             uuid,
@@ -304,6 +334,7 @@ fn receive_events(
                                     spawn_gold(
                                         &mut commands,
                                         &assets,
+                                        &mut rng,
                                         transform.translation.x,
                                         transform.translation.y,
                                     );
@@ -0,0 +1,104 @@
+use crate::language::Dict;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 弾丸や衝撃波が与えるダメージの属性です
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Ice,
+    Poison,
+    Arcane,
+}
+
+impl DamageType {
+    pub fn to_dict(&self) -> Dict {
+        match self {
+            DamageType::Physical => Dict {
+                ja: "物理",
+                en: "Physical",
+            },
+            DamageType::Fire => Dict {
+                ja: "炎",
+                en: "Fire",
+            },
+            DamageType::Ice => Dict {
+                ja: "氷",
+                en: "Ice",
+            },
+            DamageType::Poison => Dict {
+                ja: "毒",
+                en: "Poison",
+            },
+            DamageType::Arcane => Dict {
+                ja: "魔法",
+                en: "Arcane",
+            },
+        }
+    }
+
+    /// ダメージの数値を表示するときの色です
+    pub fn to_color(&self) -> Color {
+        match self {
+            DamageType::Physical => Color::WHITE,
+            DamageType::Fire => Color::hsl(25.0, 1.0, 0.6),
+            DamageType::Ice => Color::hsl(195.0, 1.0, 0.75),
+            DamageType::Poison => Color::hsl(110.0, 0.8, 0.55),
+            DamageType::Arcane => Color::hsl(275.0, 0.9, 0.7),
+        }
+    }
+}
+
+/// 属性ごとの被ダメージの倍率です
+/// 1.0 で通常、0.5 で半減、2.0 で弱点となります
+#[derive(Clone, Copy, Debug, Reflect)]
+pub struct Resistance {
+    pub physical: f32,
+    pub fire: f32,
+    pub ice: f32,
+    pub poison: f32,
+    pub arcane: f32,
+}
+
+impl Default for Resistance {
+    fn default() -> Self {
+        Resistance {
+            physical: 1.0,
+            fire: 1.0,
+            ice: 1.0,
+            poison: 1.0,
+            arcane: 1.0,
+        }
+    }
+}
+
+impl Resistance {
+    /// 指定した属性の倍率を変更した耐性を返します
+    pub fn with(mut self, damage_type: DamageType, factor: f32) -> Self {
+        match damage_type {
+            DamageType::Physical => self.physical = factor,
+            DamageType::Fire => self.fire = factor,
+            DamageType::Ice => self.ice = factor,
+            DamageType::Poison => self.poison = factor,
+            DamageType::Arcane => self.arcane = factor,
+        }
+        self
+    }
+
+    pub fn get(&self, damage_type: DamageType) -> f32 {
+        match damage_type {
+            DamageType::Physical => self.physical,
+            DamageType::Fire => self.fire,
+            DamageType::Ice => self.ice,
+            DamageType::Poison => self.poison,
+            DamageType::Arcane => self.arcane,
+        }
+    }
+
+    /// 耐性を考慮した実際のダメージを返します
+    pub fn apply(&self, damage: i32, damage_type: DamageType) -> i32 {
+        (damage as f32 * self.get(damage_type)).round() as i32
+    }
+}
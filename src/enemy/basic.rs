@@ -1,8 +1,11 @@
+use crate::asset::GameAssets;
 use crate::constant::*;
 use crate::controller::despawn_with_gold::DespawnWithGold;
+use crate::element::Resistance;
 use crate::entity::actor::{Actor, ActorFireState, ActorGroup, ActorState};
 use crate::entity::bullet::HomingTarget;
 use crate::entity::life::{Life, LifeBeingSprite};
+use crate::entity::status_effect::StatusEffects;
 use crate::entity::EntityDepth;
 use crate::hud::life_bar::{spawn_life_bar, LifeBarResource};
 use crate::inventory::Inventory;
@@ -16,6 +19,7 @@ use uuid::*;
 
 pub fn spawn_basic_enemy<T: Component>(
     commands: &mut Commands,
+    assets: &Res<GameAssets>,
     aseprite: Handle<Aseprite>,
     position: Vec2,
     life_bar_locals: &Res<LifeBarResource>,
@@ -25,11 +29,13 @@ pub fn spawn_basic_enemy<T: Component>(
     move_force: f32,
     gold: u32,
     actor_group: ActorGroup,
-) {
+    resistance: Resistance,
+) -> Entity {
     let mut slots = [None; MAX_SPELLS_IN_WAND];
     slots[0] = Some(WandSpell {
         spell_type: spell,
         price: 0,
+        tier: 0,
     });
 
     commands
@@ -50,6 +56,9 @@ pub fn spawn_basic_enemy<T: Component>(
                 effects: default(),
                 actor_group,
                 golds: gold as i32,
+                mana: 1000,
+                max_mana: 1000,
+                mana_regen: 10,
                 inventory: Inventory::new(),
                 equipments: [None; MAX_ITEMS_IN_EQUIPMENT],
                 wands: [
@@ -60,11 +69,13 @@ pub fn spawn_basic_enemy<T: Component>(
                 ],
             },
             ActorState::default(),
+            StatusEffects::default(),
             EntityDepth,
             Life {
                 life: 15,
                 max_life: 15,
                 amplitude: 0.0,
+                resistance,
             },
             HomingTarget,
             Transform::from_translation(position.extend(5.0)),
@@ -106,6 +117,7 @@ pub fn spawn_basic_enemy<T: Component>(
                 },
             ));
 
-            spawn_life_bar(&mut parent, &life_bar_locals);
-        });
+            spawn_life_bar(&mut parent, assets, &life_bar_locals);
+        })
+        .id()
 }
@@ -1,5 +1,6 @@
 use crate::asset::GameAssets;
 use crate::constant::*;
+use crate::element::{DamageType, Resistance};
 use crate::enemy::basic::spawn_basic_enemy;
 use crate::entity::actor::{Actor, ActorFireState, ActorGroup};
 use crate::hud::life_bar::LifeBarResource;
@@ -25,9 +26,12 @@ pub fn spawn_eyeball(
     aseprite: &Res<GameAssets>,
     position: Vec2,
     life_bar_locals: &Res<LifeBarResource>,
-) {
+    gold: u32,
+    group: ActorGroup,
+) -> Entity {
     spawn_basic_enemy(
         &mut commands,
+        aseprite,
         aseprite.eyeball.clone(),
         position,
         life_bar_locals,
@@ -35,9 +39,11 @@ pub fn spawn_eyeball(
         "eyeball",
         SpellType::PurpleBolt,
         ENEMY_MOVE_FORCE,
-        3,
-        ActorGroup::Enemy,
-    );
+        gold,
+        group,
+        // 目玉は炎に弱い
+        Resistance::default().with(DamageType::Fire, 2.0),
+    )
 }
 
 fn control_eyeball(
@@ -2,13 +2,17 @@ use crate::asset::GameAssets;
 use crate::audio::NextBGM;
 use crate::constant::*;
 use crate::controller::player::Player;
+use crate::element::{DamageType, Resistance};
 use crate::entity::actor::{Actor, ActorFireState, ActorGroup, ActorState};
 use crate::entity::bullet::HomingTarget;
 use crate::entity::impact::SpawnImpact;
 use crate::entity::life::Life;
-use crate::entity::slime_seed::SpawnSlimeSeed;
+use crate::entity::status_effect::StatusEffects;
+use crate::entity::summon::SummonType;
+use crate::entity::summon_seed::SpawnSummonSeed;
 use crate::entity::EntityDepth;
 use crate::inventory::Inventory;
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
 use crate::spell::SpellType;
 use crate::states::GameState;
@@ -16,6 +20,7 @@ use crate::wand::{Wand, WandSpell, WandType};
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 use uuid::*;
 
 const HUGE_SLIME_COLLIDER_RADIUS: f32 = 24.0;
@@ -49,6 +54,7 @@ pub fn spawn_huge_slime(commands: &mut Commands, assets: &Res<GameAssets>, posit
     slots[0] = Some(WandSpell {
         spell_type: SpellType::MagicBolt,
         price: 0,
+        tier: 0,
     });
 
     commands
@@ -61,6 +67,7 @@ pub fn spawn_huge_slime(commands: &mut Commands, assets: &Res<GameAssets>, posit
                 life: 1200,
                 max_life: 1200,
                 amplitude: 0.0,
+                resistance: Resistance::default().with(DamageType::Poison, 0.5),
             },
             HomingTarget,
             HugeSlime {
@@ -81,6 +88,9 @@ pub fn spawn_huge_slime(commands: &mut Commands, assets: &Res<GameAssets>, posit
                 effects: default(),
                 actor_group: ActorGroup::Enemy,
                 golds: 0,
+                mana: 1000,
+                max_mana: 1000,
+                mana_regen: 10,
                 inventory: Inventory::new(),
                 equipments: [None; MAX_ITEMS_IN_EQUIPMENT],
                 wands: [
@@ -91,6 +101,7 @@ pub fn spawn_huge_slime(commands: &mut Commands, assets: &Res<GameAssets>, posit
                 ],
             },
             ActorState::default(),
+            StatusEffects::default(),
             EntityDepth,
             AseSpriteAnimation {
                 aseprite: assets.huge_slime_shadow.clone(),
@@ -166,10 +177,15 @@ fn update_huge_slime(
         // 着地判定
         if 0.0 < offset.translation.y && next == 0.0 {
             impact_writer.send(SpawnImpact {
-                owner: parent.get(),
+                owner: Some(parent.get()),
                 position: transform.translation.truncate(),
                 radius: HUGE_SLIME_COLLIDER_RADIUS + IMPACT_MARGIN,
                 impulse: 30000.0,
+                damage: 10,
+                damage_type: DamageType::Physical,
+                group: ENEMY_GROUP,
+                filter: WITCH_GROUP | ENTITY_GROUP,
+                light: false,
             });
         }
 
@@ -238,7 +254,8 @@ fn update_huge_slime_summon(
     mut huge_slime_query: Query<(Entity, &mut HugeSlime, &Transform), Without<Player>>,
     mut sprite_query: Query<&Parent, (With<HugeSlimeSprite>, Without<HugeSlime>, Without<Player>)>,
     mut se_writer: EventWriter<SEEvent>,
-    mut seed_writer: EventWriter<SpawnSlimeSeed>,
+    mut seed_writer: EventWriter<SpawnSummonSeed>,
+    mut rng: ResMut<GameRng>,
 ) {
     for parent in sprite_query.iter_mut() {
         let (huge_slime_entity, mut huge_slime, transform) =
@@ -252,15 +269,17 @@ fn update_huge_slime_summon(
                     for n in 0..circles {
                         for i in 0..slimes {
                             let t = std::f32::consts::PI * 2.0 / slimes as f32; // 等間隔に配置した場合の角度
-                            let a = rand::random::<f32>() * 3.0; // 起点は適当にばらけさせる
-                            let angle = a + t * i as f32 + t * 0.5 * rand::random::<f32>(); // 少しランダムにずらす
+                            let a = rng.combat.gen::<f32>() * 3.0; // 起点は適当にばらけさせる
+                            let angle = a + t * i as f32 + t * 0.5 * rng.combat.gen::<f32>(); // 少しランダムにずらす
                             let offset = Vec2::from_angle(angle) * 100.0 * (1.0 + n as f32); // 100ピクセルの演習場にばらまく
                             let to = player.translation.truncate() + offset;
-                            seed_writer.send(SpawnSlimeSeed {
+                            seed_writer.send(SpawnSummonSeed {
                                 from: transform.translation.truncate(),
                                 to,
                                 actor_group: ActorGroup::Enemy,
                                 owner: huge_slime_entity,
+                                summon_type: SummonType::Slime,
+                                duration: None,
                             });
                         }
                     }
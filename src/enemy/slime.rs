@@ -1,5 +1,6 @@
 use crate::asset::GameAssets;
 use crate::constant::*;
+use crate::element::{DamageType, Resistance};
 use crate::enemy::basic::spawn_basic_enemy;
 use crate::entity::actor::{Actor, ActorFireState, ActorGroup};
 use crate::hud::life_bar::LifeBarResource;
@@ -32,9 +33,10 @@ pub fn spawn_slime(
     gold: u32,
     group: ActorGroup,
     owner: Option<Entity>,
-) {
+) -> Entity {
     spawn_basic_enemy(
         &mut commands,
+        aseprite,
         match group {
             ActorGroup::Player => aseprite.friend_slime.clone(),
             ActorGroup::Enemy => aseprite.slime.clone(),
@@ -50,7 +52,9 @@ pub fn spawn_slime(
         ENEMY_MOVE_FORCE,
         gold,
         group,
-    );
+        // スライムは毒に強い
+        Resistance::default().with(DamageType::Poison, 0.5),
+    )
 }
 
 /// 1マス以上5マス以内にプレイヤーがいたら追いかけます
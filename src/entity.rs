@@ -1,4 +1,6 @@
 pub mod actor;
+pub mod barrier;
+pub mod beam;
 pub mod book_shelf;
 pub mod broken_magic_circle;
 pub mod bullet;
@@ -9,11 +11,15 @@ pub mod dropped_item;
 pub mod gold;
 pub mod impact;
 pub mod life;
+pub mod lob;
 pub mod magic_circle;
 pub mod rabbit;
 pub mod shop;
-pub mod slime_seed;
+pub mod status_effect;
 pub mod stone_lantern;
+pub mod summon;
+pub mod summon_seed;
+pub mod teleport;
 pub mod witch;
 
 use crate::{
@@ -24,8 +30,9 @@ use bevy::{
     ecs::query::{QueryData, QueryFilter, ROQueryItem},
     prelude::*,
 };
+use serde::Deserialize;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Deserialize)]
 pub enum GameEntity {
     Chest,
     BookShelf,
@@ -1,11 +1,20 @@
 use crate::cast::cast_spell;
 use crate::constant::{MAX_ITEMS_IN_EQUIPMENT, MAX_WANDS};
 use crate::controller::player::Equipment;
+use crate::entity::barrier::SpawnBarrier;
+use crate::entity::beam::SpawnBeam;
 use crate::entity::life::Life;
 use crate::entity::life::LifeBeingSprite;
-use crate::entity::slime_seed::SpawnSlimeSeed;
+use crate::entity::lob::SpawnLob;
+use crate::entity::status_effect::StatusEffects;
+use crate::entity::summon_seed::SpawnSummonSeed;
+use crate::entity::teleport::CastTeleport;
 use crate::equipment::EquipmentType;
 use crate::inventory::Inventory;
+use crate::level::tile_effect::Footing;
+use crate::random::GameRng;
+use crate::spell_props::BulletModifier;
+use crate::spell_registry::SpellRegistry;
 use crate::ui::floating::FloatingContent;
 use crate::wand::{Wand, WandSpell};
 use crate::{asset::GameAssets, se::SEEvent, states::GameState};
@@ -17,15 +26,11 @@ use bevy_simple_websocket::{ClientMessage, ReadyState, WebSocketState};
 use std::f32::consts::PI;
 use uuid::Uuid;
 
-#[derive(Reflect, Clone, Copy, Default)]
+/// 詠唱中に蓄積された、次に発射される弾丸への修飾です
+/// 弾丸を発射するか、一回の詠唱が終わるとリセットされます
+#[derive(Reflect, Clone, Default)]
 pub struct CastEffects {
-    // pub queue: Vec,
-    pub bullet_speed_buff_factor: f32,
-    // マルチキャストで待機中の弾丸
-    // pub multicasts: Vec<SpawnBulletProps>,
-    pub homing: f32,
-
-    pub bullet_damage_buff_amount: i32,
+    pub modifiers: Vec<BulletModifier>,
 }
 
 #[derive(Component, Reflect, Default, Clone, Copy, Debug, PartialEq, Eq)]
@@ -78,24 +83,35 @@ pub struct Actor {
     pub actor_group: ActorGroup,
 
     pub golds: i32,
+
+    /// 現在のマナ
+    /// 呪文を詠唱すると消費され、毎フレーム mana_regen ずつ回復します
+    /// 小数点以下の回復量を表現するため、表示上の値の10倍で保持しています
+    pub mana: i32,
+
+    pub max_mana: i32,
+
+    /// 1フレームあたりのマナ回復量
+    pub mana_regen: i32,
 }
 
 impl Actor {
     #[allow(dead_code)]
-    pub fn get_item_icon(&self, index: FloatingContent) -> Option<&str> {
+    pub fn get_item_icon(&self, registry: &SpellRegistry, index: FloatingContent) -> Option<&str> {
         match index {
-            FloatingContent::Inventory(index) => {
-                self.inventory.get(index).map(|i| i.item_type.get_icon())
-            }
+            FloatingContent::Inventory(index) => self
+                .inventory
+                .get(index)
+                .map(|i| i.item_type.get_icon(registry)),
             FloatingContent::Equipment(index) => {
                 self.equipments[index].map(|i| i.equipment_type.to_props().icon)
             }
             FloatingContent::Wand(index) => self.wands[index]
                 .as_ref()
                 .map(|i| i.wand_type.to_props().icon),
-            FloatingContent::WandSpell(w, s) => self.wands[w]
-                .as_ref()
-                .and_then(|wand| wand.slots[s].map(|spell| spell.spell_type.to_props().icon)),
+            FloatingContent::WandSpell(w, s) => self.wands[w].as_ref().and_then(|wand| {
+                wand.slots[s].map(|spell| spell.spell_type.to_props(registry).icon)
+            }),
         }
     }
 
@@ -291,6 +307,7 @@ fn update_actor_light(
 fn fire_bullet(
     mut commands: Commands,
     assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
     mut actor_query: Query<
         (
             Entity,
@@ -298,50 +315,73 @@ fn fire_bullet(
             &mut Life,
             &mut Transform,
             &mut ExternalImpulse,
+            Option<&mut StatusEffects>,
         ),
         Without<Camera2d>,
     >,
     mut remote_writer: EventWriter<ClientMessage>,
     mut se_writer: EventWriter<SEEvent>,
-    mut slime_writer: EventWriter<SpawnSlimeSeed>,
+    mut seed_writer: EventWriter<SpawnSummonSeed>,
+    mut beam_writer: EventWriter<SpawnBeam>,
+    mut barrier_writer: EventWriter<SpawnBarrier>,
+    mut lob_writer: EventWriter<SpawnLob>,
+    mut teleport_writer: EventWriter<CastTeleport>,
     websocket: Res<WebSocketState>,
+    mut rng: ResMut<GameRng>,
 ) {
     let online = websocket.ready_state == ReadyState::OPEN;
 
-    for (actor_entity, mut actor, mut actor_life, actor_transform, mut actor_impulse) in
+    for (actor_entity, mut actor, mut actor_life, actor_transform, mut actor_impulse, mut status) in
         actor_query.iter_mut()
     {
-        if actor.fire_state == ActorFireState::Fire {
+        // 沈黙状態では詠唱できませんが、詠唱遅延とマナの回復は進みます
+        let can_cast = status.as_ref().map(|s| s.can_cast()).unwrap_or(true);
+
+        if can_cast && actor.fire_state == ActorFireState::Fire {
             let current_wand = actor.current_wand;
             cast_spell(
                 &mut commands,
                 &assets,
+                &registry,
+                &mut rng,
                 actor_entity,
                 &mut actor,
                 &mut actor_life,
                 &actor_transform,
                 &mut actor_impulse,
+                status.as_deref_mut(),
                 online,
                 &mut remote_writer,
                 &mut se_writer,
-                &mut slime_writer,
+                &mut seed_writer,
+                &mut beam_writer,
+                &mut barrier_writer,
+                &mut lob_writer,
+                &mut teleport_writer,
                 current_wand,
             );
         }
 
-        if actor.fire_state_secondary == ActorFireState::Fire {
+        if can_cast && actor.fire_state_secondary == ActorFireState::Fire {
             cast_spell(
                 &mut commands,
                 &assets,
+                &registry,
+                &mut rng,
                 actor_entity,
                 &mut actor,
                 &mut actor_life,
                 &actor_transform,
                 &mut actor_impulse,
+                status.as_deref_mut(),
                 online,
                 &mut remote_writer,
                 &mut se_writer,
-                &mut slime_writer,
+                &mut seed_writer,
+                &mut beam_writer,
+                &mut barrier_writer,
+                &mut lob_writer,
+                &mut teleport_writer,
                 MAX_WANDS - 1,
             );
         }
@@ -351,15 +391,27 @@ fn fire_bullet(
                 wand.delay = (wand.delay as i32 - 1).max(0) as u32;
             }
         }
+
+        actor.mana = (actor.mana + actor.mana_regen).min(actor.max_mana);
     }
 }
 
 /// actor.move_direction の値に従って、アクターに外力を適用します
 /// 魔法の発射中は移動速度が低下します
-fn apply_external_force(mut player_query: Query<(&Actor, &mut ExternalForce)>) {
-    for (actor, mut force) in player_query.iter_mut() {
+/// 凍結や加速などの状態異常や、水などの足元のタイルによる倍率も掛かります
+fn apply_external_force(
+    mut player_query: Query<(
+        &Actor,
+        &mut ExternalForce,
+        Option<&StatusEffects>,
+        Option<&Footing>,
+    )>,
+) {
+    for (actor, mut force, status, footing) in player_query.iter_mut() {
         force.force = actor.move_direction
             * actor.get_total_move_force()
+            * status.map(|s| s.move_force_factor()).unwrap_or(1.0)
+            * footing.map(|f| f.move_force_factor()).unwrap_or(1.0)
             * if actor.fire_state == ActorFireState::Fire
                 || actor.fire_state_secondary == ActorFireState::Fire
             {
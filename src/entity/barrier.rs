@@ -0,0 +1,231 @@
+use crate::entity::bullet_particle::{spawn_particle_system, BulletParticleResource};
+use crate::entity::life::{Life, LifeBeingSprite};
+use crate::entity::EntityDepth;
+use crate::random::GameRng;
+use crate::se::{SEEvent, SE};
+use crate::spell_props::BarrierProps;
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+// 障壁を構成する円の半径
+// 障壁の当たり判定は、円弧に沿って並べたこの大きさの円で構成されます
+const BARRIER_SEGMENT_RADIUS: f32 = 3.0;
+
+// 障壁を構成する円どうしの間隔
+// 弾丸がすり抜けないよう、円の直径より小さくしています
+const BARRIER_SEGMENT_INTERVAL: f32 = 4.0;
+
+// ひびの数
+// 耐久力が減るにつれて、ひとつずつ表示されていきます
+const BARRIER_CRACK_COUNT: usize = 6;
+
+const BARRIER_CRACK_LENGTH: f32 = 6.0;
+
+// 消える直前に点滅し始めるフレーム数
+const BARRIER_BLINK_FRAMES: u32 = 60;
+
+/// 敵の弾丸を受け止める障壁です
+/// 耐久力が尽きるか、持続時間が過ぎると消滅します
+#[derive(Component)]
+pub struct Barrier {
+    lifetime: u32,
+}
+
+/// 障壁のひびです
+/// 障壁の耐久力の割合が threshold を下回ると表示されます
+#[derive(Component)]
+struct BarrierCrack {
+    barrier: Entity,
+    threshold: f32,
+}
+
+/// 障壁を生成するイベントです
+/// ローカルで詠唱されたときに発行されるほか、
+/// リモートで送信される RemoteMessage::Barrier のデータとしても共通で使われます
+/// group と filter には、詠唱者の敵の弾丸だけに衝突する衝突グループを指定します
+#[derive(Event, Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnBarrier {
+    /// 障壁の円弧の中心
+    pub position: Vec2,
+    /// 障壁の円弧の中央が向いている角度
+    pub angle: f32,
+    pub props: BarrierProps,
+    pub group: Group,
+    pub filter: Group,
+}
+
+#[derive(Resource)]
+struct BarrierResource {
+    segment_shape: Handle<Mesh>,
+    segment_material: Handle<ColorMaterial>,
+    crack_shape: Handle<Mesh>,
+    crack_material: Handle<ColorMaterial>,
+}
+
+fn setup_barrier(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    commands.insert_resource(BarrierResource {
+        segment_shape: meshes.add(Circle::new(BARRIER_SEGMENT_RADIUS)),
+        segment_material: materials.add(Color::hsla(190.0, 1.0, 0.7, 0.5)),
+        crack_shape: meshes.add(Rectangle::new(BARRIER_CRACK_LENGTH, 1.0)),
+        crack_material: materials.add(Color::hsla(190.0, 0.3, 0.2, 0.9)),
+    });
+}
+
+/// 円弧に沿って、障壁を構成する円の中心の位置を並べます
+/// 位置は障壁の中心からの相対位置で、円弧の中央は x 軸の正の方向を向きます
+fn get_segment_positions(radius: f32, arc: f32) -> Vec<Vec2> {
+    let count = ((radius * arc / BARRIER_SEGMENT_INTERVAL).ceil() as usize).max(1);
+    // 円の場合は始点と終点が重なるため、終点の円を省きます
+    let points = if 2.0 * PI <= arc { count } else { count + 1 };
+    (0..points)
+        .map(|i| Vec2::from_angle(-arc * 0.5 + arc * i as f32 / count as f32) * radius)
+        .collect()
+}
+
+fn read_spawn_barrier_event(
+    mut commands: Commands,
+    mut reader: EventReader<SpawnBarrier>,
+    resource: Res<BarrierResource>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for spawn in reader.read() {
+        let positions = get_segment_positions(spawn.props.radius, spawn.props.arc);
+
+        let barrier = commands
+            .spawn((
+                Name::new("barrier"),
+                StateScoped(GameState::InGame),
+                Barrier {
+                    lifetime: spawn.props.duration,
+                },
+                Life {
+                    life: spawn.props.life,
+                    max_life: spawn.props.life,
+                    amplitude: 0.0,
+                    resistance: default(),
+                },
+                EntityDepth,
+                Transform::from_translation(spawn.position.extend(0.0))
+                    .with_rotation(Quat::from_rotation_z(spawn.angle)),
+                Visibility::default(),
+                (
+                    RigidBody::Fixed,
+                    Collider::compound(
+                        positions
+                            .iter()
+                            .map(|p| (*p, 0.0, Collider::ball(BARRIER_SEGMENT_RADIUS)))
+                            .collect(),
+                    ),
+                    CollisionGroups::new(spawn.group, spawn.filter),
+                ),
+            ))
+            .id();
+
+        commands.entity(barrier).with_children(|parent| {
+            // 振動はこの子エンティティに対して適用されます
+            parent
+                .spawn((LifeBeingSprite, Transform::default(), Visibility::default()))
+                .with_children(|parent| {
+                    for position in positions.iter() {
+                        parent.spawn((
+                            Mesh2d::from(resource.segment_shape.clone()),
+                            MeshMaterial2d::from(resource.segment_material.clone()),
+                            Transform::from_translation(position.extend(0.0)),
+                        ));
+                    }
+
+                    for i in 0..BARRIER_CRACK_COUNT {
+                        let position = positions[rng.cosmetic.gen_range(0..positions.len())];
+                        parent.spawn((
+                            BarrierCrack {
+                                barrier,
+                                threshold: 1.0 - (i + 1) as f32 / (BARRIER_CRACK_COUNT + 1) as f32,
+                            },
+                            Mesh2d::from(resource.crack_shape.clone()),
+                            MeshMaterial2d::from(resource.crack_material.clone()),
+                            Transform::from_translation(position.extend(0.1)).with_rotation(
+                                Quat::from_rotation_z(rng.cosmetic.gen::<f32>() * PI),
+                            ),
+                            Visibility::Hidden,
+                        ));
+                    }
+                });
+        });
+
+        se_writer.send(SEEvent::pos(SE::TurnOn, spawn.position));
+    }
+}
+
+/// 耐久力の減り具合に応じてひびを表示します
+fn update_barrier_cracks(
+    mut crack_query: Query<(&BarrierCrack, &mut Visibility)>,
+    barrier_query: Query<&Life, With<Barrier>>,
+) {
+    for (crack, mut visibility) in crack_query.iter_mut() {
+        if let Ok(life) = barrier_query.get(crack.barrier) {
+            let ratio = life.life as f32 / life.max_life as f32;
+            *visibility = if ratio < crack.threshold {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
+/// 持続時間の過ぎた障壁や、耐久力の尽きた障壁を消滅させます
+/// 消える直前には点滅して、持続時間が残りわずかであることを示します
+fn update_barrier(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Barrier, &Life, &Transform, &mut Visibility)>,
+    particle: Res<BulletParticleResource>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (entity, mut barrier, life, transform, mut visibility) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        if life.life <= 0 {
+            commands.entity(entity).despawn_recursive();
+            spawn_particle_system(&mut commands, position, &particle, &mut rng);
+            se_writer.send(SEEvent::pos(SE::Break, position));
+        } else if barrier.lifetime == 0 {
+            commands.entity(entity).despawn_recursive();
+        } else {
+            barrier.lifetime -= 1;
+            *visibility = if barrier.lifetime < BARRIER_BLINK_FRAMES && barrier.lifetime % 8 < 4 {
+                Visibility::Hidden
+            } else {
+                Visibility::Inherited
+            };
+        }
+    }
+}
+
+pub struct BarrierPlugin;
+
+impl Plugin for BarrierPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnBarrier>();
+        app.add_systems(Startup, setup_barrier);
+        app.add_systems(
+            FixedUpdate,
+            (
+                read_spawn_barrier_event,
+                update_barrier,
+                update_barrier_cracks,
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use crate::controller::remote::RemotePlayer;
+use crate::entity::actor::Actor;
+use crate::entity::damege::spawn_damage_number;
+use crate::entity::life::Life;
+use crate::entity::status_effect::StatusEffects;
+use crate::entity::witch::WITCH_COLLIDER_RADIUS;
+use crate::level::wall::WallCollider;
+use crate::se::{SEEvent, SE};
+use crate::spell_props::BeamProps;
+use crate::{asset::GameAssets, states::GameState};
+use bevy::prelude::*;
+use bevy_aseprite_ultra::prelude::AseSpriteSlice;
+use bevy_light_2d::light::PointLight2d;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+static BEAM_Z: f32 = 10.0;
+
+// ビームのスプライトの元の大きさ
+// ビームの長さに合わせて横方向に引き伸ばして表示します
+const BEAM_SPRITE_SIZE: f32 = 16.0;
+
+// ビームの太さの倍率
+const BEAM_SPRITE_THICKNESS: f32 = 0.5;
+
+// ビームの始点と詠唱者の中心との距離
+const BEAM_MARGIN: f32 = 4.0;
+
+/// 詠唱者の向いている方向へ伸びるビームです
+/// 詠唱者に追従し、最初の壁で止まるまでの間にいる相手へ一定間隔でダメージを与えます
+/// 詠唱されるたびに持続時間が更新されるため、発射ボタンを押し続けている間は途切れずに伸び続けます
+#[derive(Component)]
+pub struct Beam {
+    owner: Entity,
+    props: BeamProps,
+    group: Group,
+    filter: Group,
+    lifetime: u32,
+    /// 出現してからのフレーム数
+    /// ダメージの間隔の判定に使います
+    tick: u32,
+    /// 現在のビームの長さ
+    length: f32,
+}
+
+#[derive(Component)]
+struct BeamLight;
+
+/// ビームを発射するイベントです
+/// ローカルで詠唱されたときに発行されるほか、
+/// リモートで送信される RemoteMessage::Beam のデータとしても共通で使われます
+/// 詠唱者はエンティティではなくアクターの UUID で指定します
+#[derive(Event, Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnBeam {
+    pub sender: Uuid,
+    pub props: BeamProps,
+    pub group: Group,
+    pub filter: Group,
+}
+
+/// SpawnBeam イベントを読み取り、ビームを生成します
+/// 詠唱者がすでにビームを発射している場合は、新たに生成せずに持続時間を更新します
+fn read_spawn_beam_event(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut reader: EventReader<SpawnBeam>,
+    actor_query: Query<(Entity, &Actor, &Transform)>,
+    mut beam_query: Query<&mut Beam>,
+    mut se_writer: EventWriter<SEEvent>,
+) {
+    for spawn in reader.read() {
+        let Some((owner, _, owner_transform)) =
+            actor_query.iter().find(|(_, a, _)| a.uuid == spawn.sender)
+        else {
+            continue;
+        };
+
+        if let Some(mut beam) = beam_query.iter_mut().find(|b| b.owner == owner) {
+            beam.lifetime = spawn.props.duration;
+            beam.props = spawn.props.clone();
+            beam.group = spawn.group;
+            beam.filter = spawn.filter;
+            continue;
+        }
+
+        commands
+            .spawn((
+                Name::new("beam"),
+                StateScoped(GameState::InGame),
+                Beam {
+                    owner,
+                    props: spawn.props.clone(),
+                    group: spawn.group,
+                    filter: spawn.filter,
+                    lifetime: spawn.props.duration,
+                    tick: 0,
+                    length: 0.0,
+                },
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: spawn.props.slice.clone().into(),
+                },
+                Transform::from_translation(owner_transform.translation.truncate().extend(BEAM_Z)),
+                Visibility::default(),
+            ))
+            .with_children(|parent| {
+                if 0.0 < spawn.props.light_intensity {
+                    parent.spawn((
+                        BeamLight,
+                        Transform::default(),
+                        Visibility::default(),
+                        PointLight2d {
+                            radius: spawn.props.light_radius,
+                            intensity: spawn.props.light_intensity,
+                            falloff: 10.0,
+                            color: Color::hsla(
+                                spawn.props.light_color_hlsa[0],
+                                spawn.props.light_color_hlsa[1],
+                                spawn.props.light_color_hlsa[2],
+                                spawn.props.light_color_hlsa[3],
+                            ),
+                            ..default()
+                        },
+                    ));
+                }
+            });
+
+        se_writer.send(SEEvent::pos(
+            SE::Fire,
+            owner_transform.translation.truncate(),
+        ));
+    }
+}
+
+/// ビームを詠唱者の向きに合わせて伸ばし、ビーム上の相手にダメージを与えます
+fn update_beam(
+    mut commands: Commands,
+    rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
+    mut beam_query: Query<(Entity, &mut Beam, &mut Transform)>,
+    owner_query: Query<(&Actor, &Transform), Without<Beam>>,
+    wall_collider_query: Query<Entity, With<WallCollider>>,
+    mut actor_query: Query<
+        (&mut Life, Option<&StatusEffects>),
+        (With<Actor>, Without<RemotePlayer>),
+    >,
+    mut lifebeing_query: Query<&mut Life, Without<Actor>>,
+    mut se_writer: EventWriter<SEEvent>,
+) {
+    let context: &RapierContext = rapier_context.single();
+
+    for (beam_entity, mut beam, mut transform) in beam_query.iter_mut() {
+        let Ok((owner, owner_transform)) = owner_query.get(beam.owner) else {
+            // 詠唱者が倒れた場合はビームも消えます
+            commands.entity(beam_entity).despawn_recursive();
+            continue;
+        };
+
+        if beam.lifetime == 0 {
+            commands.entity(beam_entity).despawn_recursive();
+            continue;
+        }
+        beam.lifetime -= 1;
+        beam.tick += 1;
+
+        let direction = owner.pointer.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+        let origin = owner_transform.translation.truncate()
+            + direction * (WITCH_COLLIDER_RADIUS + BEAM_MARGIN);
+
+        // レイに交差するものを近い順に並べ、最初の壁より手前のものだけを対象にします
+        let mut hits: Vec<(Entity, f32)> = Vec::new();
+        context.intersections_with_ray(
+            origin,
+            direction,
+            beam.props.range,
+            true,
+            QueryFilter::default()
+                .exclude_sensors()
+                .exclude_rigid_body(beam.owner)
+                .groups(CollisionGroups::new(beam.group, beam.filter)),
+            |entity, intersection| {
+                hits.push((entity, intersection.point.distance(origin)));
+                true // 交差図形の検索を続ける
+            },
+        );
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut length = beam.props.range;
+        let mut targets: Vec<(Entity, f32)> = Vec::new();
+        for (entity, distance) in hits {
+            if wall_collider_query.contains(entity) {
+                length = distance;
+                break;
+            }
+            targets.push((entity, distance));
+        }
+        beam.length = length;
+
+        transform.translation = (origin + direction * length * 0.5).extend(BEAM_Z);
+        transform.rotation = Quat::from_rotation_z(direction.to_angle());
+        transform.scale = Vec3::new(length / BEAM_SPRITE_SIZE, BEAM_SPRITE_THICKNESS, 1.0);
+
+        if beam.tick % beam.props.interval.max(1) != 0 {
+            continue;
+        }
+
+        for (entity, distance) in targets {
+            let damage_type = beam.props.damage_type;
+            let position = origin + direction * distance;
+            if let Ok((mut life, status)) = actor_query.get_mut(entity) {
+                let damage = life.resistance.apply(beam.props.damage, damage_type);
+                let damage = status.map(|s| s.filter_damage(damage)).unwrap_or(damage);
+                life.life = (life.life - damage).max(0);
+                life.amplitude = 2.0;
+                spawn_damage_number(&mut commands, damage, damage_type, position);
+                se_writer.send(SEEvent::pos(SE::Damage, position));
+            } else if let Ok(mut life) = lifebeing_query.get_mut(entity) {
+                let damage = life.resistance.apply(beam.props.damage, damage_type);
+                life.life -= damage;
+                life.amplitude = 2.0;
+                spawn_damage_number(&mut commands, damage, damage_type, position);
+                se_writer.send(SEEvent::pos(SE::Damage, position));
+            }
+        }
+    }
+}
+
+/// ビームの光源をビームの長さに合わせて広げます
+fn update_beam_light(
+    mut light_query: Query<(&Parent, &mut PointLight2d), With<BeamLight>>,
+    beam_query: Query<&Beam>,
+) {
+    for (parent, mut light) in light_query.iter_mut() {
+        if let Ok(beam) = beam_query.get(parent.get()) {
+            light.radius = beam.length * 0.5 + beam.props.light_radius;
+        }
+    }
+}
+
+pub struct BeamPlugin;
+
+impl Plugin for BeamPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnBeam>();
+        app.add_systems(
+            FixedUpdate,
+            (read_spawn_beam_event, update_beam, update_beam_light)
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
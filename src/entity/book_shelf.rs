@@ -25,6 +25,7 @@ pub fn spawn_book_shelf(commands: &mut Commands, aseprite: Handle<Aseprite>, x:
             life: 25,
             max_life: 25,
             amplitude: 0.0,
+            resistance: default(),
         },
         Bookshelf,
         EntityDepth,
@@ -1,11 +1,23 @@
+use crate::asset::GameAssets;
+use crate::cast::cast_payload;
+use crate::constant::TILE_HALF;
 use crate::controller::remote::RemotePlayer;
+use crate::element::DamageType;
 use crate::entity::actor::Actor;
 use crate::entity::bullet_particle::BulletParticleResource;
 use crate::entity::damege::spawn_damage_number;
+use crate::entity::impact::SpawnImpact;
 use crate::entity::life::Life;
+use crate::entity::status_effect::{StatusEffect, StatusEffects};
 use crate::entity::EntityDepth;
+use crate::level::terrain::{DamageTerrain, ModifyTerrain};
+use crate::level::tile::Tile;
 use crate::level::wall::WallCollider;
+use crate::random::GameRng;
 use crate::se::SE;
+use crate::spell::SpellType;
+use crate::spell_props::{BulletTrigger, TerrainEffect};
+use crate::spell_registry::SpellRegistry;
 use crate::states::GameState;
 use crate::{entity::bullet_particle::spawn_particle_system, se::SEEvent};
 use bevy::prelude::*;
@@ -26,13 +38,40 @@ static BULLET_Z: f32 = 10.0;
 // 大きすぎるとキャラクターと弾丸の位置が離れすぎて不自然
 pub const BULLET_SPAWNING_MARGIN: f32 = 9.0;
 
+// 貫通した弾丸が次の相手に与えるダメージの倍率
+const PIERCE_DAMAGE_FALLOFF: f32 = 0.7;
+
 #[derive(Component, Reflect)]
 pub struct Bullet {
     life: u32,
     damage: i32,
+    damage_type: DamageType,
     impulse: f32,
     owner: Option<Uuid>,
     homing: f32,
+    bounces: u32,
+    pierce: u32,
+    /// 命中済みのエンティティ
+    /// 貫通する弾丸が同じ相手に何度も命中しないようにするために使います
+    hit_entities: Vec<Entity>,
+    trigger: Option<BulletTrigger>,
+    payload: Vec<SpellType>,
+    status_effects: Vec<StatusEffect>,
+    explosion_radius: f32,
+    terrain: Option<TerrainEffect>,
+}
+
+impl Bullet {
+    /// 直撃した相手に与えるダメージです
+    /// 爆発する弾丸がこの命中で消滅する場合は、爆発の衝撃波が同じ相手にもダメージを与えるため、
+    /// 二重にダメージを与えないよう直撃のダメージは0になります
+    fn direct_damage(&self) -> i32 {
+        if 0.0 < self.explosion_radius && self.pierce == 0 {
+            0
+        } else {
+            self.damage
+        }
+    }
 }
 
 #[derive(Bundle)]
@@ -58,15 +97,52 @@ pub struct SpawnBullet {
     pub velocity: Vec2,
     pub bullet_lifetime: u32,
     pub damage: i32,
+    pub damage_type: DamageType,
     pub impulse: f32,
     pub slice: String,
     pub collier_radius: f32,
+    pub scale: f32,
     pub light_intensity: f32,
     pub light_radius: f32,
     pub light_color_hlsa: [f32; 4],
     pub homing: f32,
+
+    /// 壁で反射できる残り回数
+    pub bounces: u32,
+
+    /// 命中しても消滅せずに貫通できる残り回数
+    pub pierce: u32,
+
     pub group: Group,
     pub filter: Group,
+
+    /// ペイロードを詠唱する条件
+    pub trigger: Option<BulletTrigger>,
+
+    /// トリガーの条件を満たしたときに弾丸の位置から詠唱される呪文の列です
+    /// リモートでも同じペイロードを詠唱できるように、弾丸と一緒に送信されます
+    pub payload: Vec<SpellType>,
+
+    /// 命中したアクターに付与する状態異常
+    pub status_effects: Vec<StatusEffect>,
+
+    /// 消滅時の爆発の半径
+    /// 0の場合は爆発しません
+    pub explosion_radius: f32,
+
+    /// 地形に与える効果
+    pub terrain: Option<TerrainEffect>,
+}
+
+/// トリガー付きの弾丸が条件を満たし、ペイロードを詠唱するときに発行されるイベントです
+#[derive(Event)]
+pub struct BulletTriggered {
+    sender: Option<Uuid>,
+    position: Vec2,
+    velocity: Vec2,
+    payload: Vec<SpellType>,
+    group: Group,
+    filter: Group,
 }
 
 /// 指定した種類の弾丸を発射します
@@ -90,13 +166,23 @@ pub fn spawn_bullet(
         Bullet {
             life: spawn.bullet_lifetime,
             damage: spawn.damage,
+            damage_type: spawn.damage_type,
             impulse: spawn.impulse,
             owner: spawn.sender,
             homing: spawn.homing,
+            bounces: spawn.bounces,
+            pierce: spawn.pierce,
+            hit_entities: Vec::new(),
+            trigger: spawn.trigger,
+            payload: spawn.payload.clone(),
+            status_effects: spawn.status_effects.clone(),
+            explosion_radius: spawn.explosion_radius,
+            terrain: spawn.terrain,
         },
         EntityDepth,
         Transform::from_xyz(spawn.position.x, spawn.position.y, BULLET_Z)
-            * Transform::from_rotation(Quat::from_rotation_z(spawn.velocity.to_angle())), // .looking_to(velocity.extend(BULLET_Z), Vec3::Z)
+            * Transform::from_rotation(Quat::from_rotation_z(spawn.velocity.to_angle())) // .looking_to(velocity.extend(BULLET_Z), Vec3::Z)
+            * Transform::from_scale(Vec3::splat(spawn.scale)),
         AseSpriteSlice {
             aseprite,
             name: spawn.slice.clone().into(),
@@ -129,6 +215,12 @@ pub fn spawn_bullet(
         ),
     ));
 
+    // 貫通する弾丸は命中した相手を押しのけないように、接触による力を発生させません
+    // 衝突イベントは SolverGroups の影響を受けないため、命中の判定はそのまま行われます
+    if 0 < spawn.pierce {
+        entity.insert(SolverGroups::new(Group::NONE, Group::NONE));
+    }
+
     if 0.0 < spawn.light_intensity {
         entity.insert(PointLight2d {
             radius: spawn.light_radius,
@@ -147,17 +239,107 @@ pub fn spawn_bullet(
 
 fn despawn_bullet_by_lifetime(
     mut commands: Commands,
-    mut bullet_query: Query<(Entity, &mut Bullet, &Transform, &Velocity)>,
+    mut bullet_query: Query<(Entity, &mut Bullet, &Transform, &Velocity, &CollisionGroups)>,
+    mut triggered_writer: EventWriter<BulletTriggered>,
+    mut impact_writer: EventWriter<SpawnImpact>,
+    mut terrain_writer: EventWriter<ModifyTerrain>,
 ) {
     // 弾丸のライフタイムを減らし、ライフタイムが尽きたら削除
-    for (entity, mut bullet, _, _) in bullet_query.iter_mut() {
+    for (entity, mut bullet, transform, velocity, groups) in bullet_query.iter_mut() {
         bullet.life -= 1;
         if bullet.life <= 0 {
             commands.entity(entity).despawn_recursive();
+
+            if bullet.trigger == Some(BulletTrigger::Expire) {
+                send_triggered(&mut triggered_writer, &bullet, transform, velocity, groups);
+            }
+
+            send_explosion(&mut impact_writer, &bullet, transform, groups);
+            send_raise_wall(&mut terrain_writer, &bullet, transform);
         }
     }
 }
 
+/// 爆発する弾丸が消滅したときに、その位置に衝撃波を発生させます
+/// 衝撃波は弾丸と同じ衝突グループを持つため、詠唱者の仲間には命中しません
+fn send_explosion(
+    writer: &mut EventWriter<SpawnImpact>,
+    bullet: &Bullet,
+    transform: &Transform,
+    groups: &CollisionGroups,
+) {
+    if 0.0 < bullet.explosion_radius {
+        writer.send(SpawnImpact {
+            owner: None,
+            position: transform.translation.truncate(),
+            radius: bullet.explosion_radius,
+            impulse: bullet.impulse,
+            damage: bullet.damage,
+            damage_type: bullet.damage_type,
+            group: groups.memberships,
+            filter: groups.filters,
+            light: true,
+        });
+    }
+}
+
+/// 壁を立てる弾丸が消滅したときに、その位置の床に壁を立てます
+fn send_raise_wall(
+    writer: &mut EventWriter<ModifyTerrain>,
+    bullet: &Bullet,
+    transform: &Transform,
+) {
+    if bullet.terrain == Some(TerrainEffect::Raise) {
+        writer.send(ModifyTerrain {
+            position: transform.translation.truncate(),
+            tile: Tile::Wall,
+        });
+    }
+}
+
+/// トリガーの条件を満たした弾丸のペイロードを詠唱するイベントを発行します
+fn send_triggered(
+    writer: &mut EventWriter<BulletTriggered>,
+    bullet: &Bullet,
+    transform: &Transform,
+    velocity: &Velocity,
+    groups: &CollisionGroups,
+) {
+    writer.send(BulletTriggered {
+        sender: bullet.owner,
+        position: transform.translation.truncate(),
+        velocity: velocity.linvel,
+        payload: bullet.payload.clone(),
+        group: groups.memberships,
+        filter: groups.filters,
+    });
+}
+
+fn cast_triggered_payload(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
+    mut reader: EventReader<BulletTriggered>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for event in reader.read() {
+        cast_payload(
+            &mut commands,
+            &assets,
+            &registry,
+            &mut rng.combat,
+            &mut se_writer,
+            &event.payload,
+            event.sender,
+            event.position,
+            event.velocity.to_angle(),
+            event.group,
+            event.filter,
+        );
+    }
+}
+
 fn bullet_homing(
     mut bullet_query: Query<(&mut Bullet, &mut Transform, &mut Velocity)>,
     enemy_query: Query<(Option<&Actor>, &Transform), (With<HomingTarget>, Without<Bullet>)>,
@@ -191,9 +373,20 @@ fn bullet_homing(
 
 fn bullet_collision(
     mut commands: Commands,
-    mut bullet_query: Query<(Entity, &mut Bullet, &Transform, &Velocity)>,
+    mut bullet_query: Query<(
+        Entity,
+        &mut Bullet,
+        &mut Transform,
+        &mut Velocity,
+        &CollisionGroups,
+    )>,
     mut actor_query: Query<
-        (&mut Actor, Option<&mut ExternalImpulse>, &mut Life),
+        (
+            &mut Actor,
+            Option<&mut ExternalImpulse>,
+            &mut Life,
+            Option<&mut StatusEffects>,
+        ),
         Without<RemotePlayer>,
     >,
     mut lifebeing_query: Query<(&mut Life, Option<&mut ExternalImpulse>), Without<Actor>>,
@@ -201,7 +394,15 @@ fn bullet_collision(
     wall_collider_query: Query<Entity, With<WallCollider>>,
     mut writer: EventWriter<SEEvent>,
     resource: Res<BulletParticleResource>,
+    mut triggered_writer: EventWriter<BulletTriggered>,
+    mut impact_writer: EventWriter<SpawnImpact>,
+    mut terrain_writer: EventWriter<ModifyTerrain>,
+    mut damage_terrain_writer: EventWriter<DamageTerrain>,
+    rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
+    mut rng: ResMut<GameRng>,
 ) {
+    let context: &RapierContext = rapier_context.single();
+
     // 弾丸が壁の角に当たった場合、衝突イベントが同時に複数回発生するため、
     // すでにdespawnしたentityに対して再びdespawnしてしまうことがあり、
     // 警告が出るのを避けるため、処理済みのentityを識別するセットを使っています
@@ -222,6 +423,12 @@ fn bullet_collision(
                     &wall_collider_query,
                     &mut writer,
                     &resource,
+                    &mut triggered_writer,
+                    &mut impact_writer,
+                    &mut terrain_writer,
+                    &mut damage_terrain_writer,
+                    context,
+                    &mut rng,
                 ) {
                     process_bullet_event(
                         &mut commands,
@@ -234,6 +441,12 @@ fn bullet_collision(
                         &wall_collider_query,
                         &mut writer,
                         &resource,
+                        &mut triggered_writer,
+                        &mut impact_writer,
+                        &mut terrain_writer,
+                        &mut damage_terrain_writer,
+                        context,
+                        &mut rng,
                     );
                 }
             }
@@ -244,9 +457,20 @@ fn bullet_collision(
 
 fn process_bullet_event(
     mut commands: &mut Commands,
-    query: &Query<(Entity, &mut Bullet, &Transform, &Velocity)>,
+    query: &mut Query<(
+        Entity,
+        &mut Bullet,
+        &mut Transform,
+        &mut Velocity,
+        &CollisionGroups,
+    )>,
     actors: &mut Query<
-        (&mut Actor, Option<&mut ExternalImpulse>, &mut Life),
+        (
+            &mut Actor,
+            Option<&mut ExternalImpulse>,
+            &mut Life,
+            Option<&mut StatusEffects>,
+        ),
         Without<RemotePlayer>,
     >,
     breakabke_query: &mut Query<(&mut Life, Option<&mut ExternalImpulse>), Without<Actor>>,
@@ -256,57 +480,153 @@ fn process_bullet_event(
     wall_collider_query: &Query<Entity, With<WallCollider>>,
     writer: &mut EventWriter<SEEvent>,
     resource: &Res<BulletParticleResource>,
+    triggered_writer: &mut EventWriter<BulletTriggered>,
+    impact_writer: &mut EventWriter<SpawnImpact>,
+    terrain_writer: &mut EventWriter<ModifyTerrain>,
+    damage_terrain_writer: &mut EventWriter<DamageTerrain>,
+    context: &RapierContext,
+    rng: &mut GameRng,
 ) -> bool {
-    if let Ok((bullet_entity, bullet, bullet_transform, bullet_velocity)) = query.get(*a) {
+    if let Ok((
+        bullet_entity,
+        mut bullet,
+        mut bullet_transform,
+        mut bullet_velocity,
+        bullet_groups,
+    )) = query.get_mut(*a)
+    {
         let bullet_position = bullet_transform.translation.truncate();
 
         if !despownings.contains(&bullet_entity) {
-            if let Ok((actor, impilse, mut lifebeing)) = actors.get_mut(*b) {
+            if let Ok((actor, impilse, mut lifebeing, status)) = actors.get_mut(*b) {
                 trace!("bullet hit actor: {:?}", actor.uuid);
 
                 // 弾丸がアクターに衝突したとき
                 // このクエリにはプレイヤーキャラクター自身、発射したキャラクター自身も含まれることに注意
                 // 弾丸の詠唱者自身に命中した場合はダメージやノックバックはなし
                 // リモートプレイヤーのダメージやノックバックはリモートで処理されるため、ここでは処理しない
-                if bullet.owner == None || Some(actor.uuid) != bullet.owner {
-                    lifebeing.life = (lifebeing.life - bullet.damage).max(0);
+                // 貫通中の弾丸がすでに命中した相手に再び衝突した場合も無視します
+                if (bullet.owner == None || Some(actor.uuid) != bullet.owner)
+                    && !bullet.hit_entities.contains(b)
+                {
+                    let mut damage = lifebeing
+                        .resistance
+                        .apply(bullet.direct_damage(), bullet.damage_type);
+                    if let Some(mut status) = status {
+                        damage = status.filter_damage(damage);
+                        for effect in bullet.status_effects.iter() {
+                            status.apply(*effect);
+                        }
+                    }
+                    lifebeing.life = (lifebeing.life - damage).max(0);
                     lifebeing.amplitude = 6.0;
                     if let Some(mut impilse) = impilse {
                         impilse.impulse +=
                             bullet_velocity.linvel.normalize_or_zero() * bullet.impulse;
                     }
-                    despownings.insert(bullet_entity.clone());
-                    commands.entity(bullet_entity).despawn_recursive();
-                    spawn_particle_system(&mut commands, bullet_position, resource);
-                    spawn_damage_number(&mut commands, bullet.damage, bullet_position);
+                    spawn_particle_system(&mut commands, bullet_position, resource, rng);
+                    // 爆発でダメージを与える場合は、ダメージの数値は衝撃波の側で表示されます
+                    if 0 < bullet.direct_damage() {
+                        spawn_damage_number(
+                            &mut commands,
+                            damage,
+                            bullet.damage_type,
+                            bullet_position,
+                        );
+                    }
                     writer.send(SEEvent::pos(SE::Damage, bullet_position));
+                    pierce_or_despawn(&mut commands, &mut bullet, bullet_entity, *b, despownings);
                 }
             } else if let Ok((mut breakabke, impulse_optional)) = breakabke_query.get_mut(*b) {
-                trace!("bullet hit: {:?}", b);
-                breakabke.life -= bullet.damage;
-                breakabke.amplitude = 2.0;
-                despownings.insert(bullet_entity.clone());
-                commands.entity(bullet_entity).despawn_recursive();
-                spawn_particle_system(&mut commands, bullet_position, resource);
-                spawn_damage_number(&mut commands, bullet.damage, bullet_position);
-                writer.send(SEEvent::pos(SE::Damage, bullet_position));
+                if !bullet.hit_entities.contains(b) {
+                    trace!("bullet hit: {:?}", b);
+                    let damage = breakabke
+                        .resistance
+                        .apply(bullet.direct_damage(), bullet.damage_type);
+                    breakabke.life -= damage;
+                    breakabke.amplitude = 2.0;
+                    spawn_particle_system(&mut commands, bullet_position, resource, rng);
+                    // 爆発でダメージを与える場合は、ダメージの数値は衝撃波の側で表示されます
+                    if 0 < bullet.direct_damage() {
+                        spawn_damage_number(
+                            &mut commands,
+                            damage,
+                            bullet.damage_type,
+                            bullet_position,
+                        );
+                    }
+                    writer.send(SEEvent::pos(SE::Damage, bullet_position));
 
-                if let Some(mut impilse) = impulse_optional {
-                    impilse.impulse += bullet_velocity.linvel.normalize_or_zero() * bullet.impulse;
+                    if let Some(mut impilse) = impulse_optional {
+                        impilse.impulse +=
+                            bullet_velocity.linvel.normalize_or_zero() * bullet.impulse;
+                    }
+                    pierce_or_despawn(&mut commands, &mut bullet, bullet_entity, *b, despownings);
                 }
             } else if let Ok(_) = wall_collider_query.get(*b) {
                 trace!("bullet hit wall: {:?}", b);
+
+                // 反射回数が残っている場合は、接触点の法線で速度を反射して消滅しません
+                if 0 < bullet.bounces {
+                    if let Some(normal) = get_contact_normal(context, bullet_entity, *b) {
+                        // 壁の角では衝突イベントが同時に複数回発生するため、
+                        // すでに反射して壁から離れつつある場合は何もしません
+                        let dot = bullet_velocity.linvel.dot(normal);
+                        if 0.0 < dot {
+                            bullet.bounces -= 1;
+                            bullet_velocity.linvel -= 2.0 * dot * normal;
+                            bullet_transform.rotation =
+                                Quat::from_rotation_z(bullet_velocity.linvel.to_angle());
+                            writer.send(SEEvent::pos(SE::NoDamage, bullet_position));
+                        }
+                        return true;
+                    }
+                }
+
+                // 壁を掘る弾丸は、接触点から壁の内側へ半タイル進んだ位置のタイルを掘ります
+                // ダメージのある弾丸は、同じ位置のひび割れた壁を崩します
+                let direction = get_contact_normal(context, bullet_entity, *b)
+                    .unwrap_or(bullet_velocity.linvel.normalize_or_zero());
+                if bullet.terrain == Some(TerrainEffect::Dig) {
+                    terrain_writer.send(ModifyTerrain {
+                        position: bullet_position + direction * TILE_HALF,
+                        tile: Tile::StoneTile,
+                    });
+                }
+                if 0 < bullet.damage {
+                    damage_terrain_writer.send(DamageTerrain {
+                        position: bullet_position + direction * TILE_HALF,
+                        radius: 0.0,
+                    });
+                }
+
                 despownings.insert(bullet_entity.clone());
                 commands.entity(bullet_entity).despawn_recursive();
-                spawn_particle_system(&mut commands, bullet_position, resource);
-                writer.send(SEEvent::pos(SE::Steps, bullet_position));
+                spawn_particle_system(&mut commands, bullet_position, resource, rng);
+                writer.send(SEEvent::pos(SE::NoDamage, bullet_position));
             } else {
                 trace!("bullet hit unknown entity: {:?}", b);
                 despownings.insert(bullet_entity.clone());
                 commands.entity(bullet_entity).despawn_recursive();
-                spawn_particle_system(&mut commands, bullet_position, resource);
+                spawn_particle_system(&mut commands, bullet_position, resource, rng);
                 writer.send(SEEvent::pos(SE::NoDamage, bullet_position));
             }
+
+            // 衝突によって弾丸が消滅した場合は、トリガーの条件を満たしていればペイロードを詠唱します
+            if despownings.contains(&bullet_entity) && bullet.trigger == Some(BulletTrigger::Hit) {
+                send_triggered(
+                    triggered_writer,
+                    &bullet,
+                    &bullet_transform,
+                    &bullet_velocity,
+                    bullet_groups,
+                );
+            }
+
+            if despownings.contains(&bullet_entity) {
+                send_explosion(impact_writer, &bullet, &bullet_transform, bullet_groups);
+                send_raise_wall(terrain_writer, &bullet, &bullet_transform);
+            }
             true
         } else {
             false
@@ -316,16 +636,94 @@ fn process_bullet_event(
     }
 }
 
+/// 弾丸が相手に命中したあと、貫通回数が残っていればダメージを減衰させて飛び続け、
+/// 残っていなければ弾丸を消滅させます
+fn pierce_or_despawn(
+    commands: &mut Commands,
+    bullet: &mut Bullet,
+    bullet_entity: Entity,
+    target: Entity,
+    despownings: &mut HashSet<Entity>,
+) {
+    bullet.hit_entities.push(target);
+    if 0 < bullet.pierce {
+        bullet.pierce -= 1;
+        bullet.damage = ((bullet.damage as f32 * PIERCE_DAMAGE_FALLOFF).round() as i32).max(1);
+    } else {
+        despownings.insert(bullet_entity);
+        commands.entity(bullet_entity).despawn_recursive();
+    }
+}
+
+/// 弾丸と壁の接触点の法線を、弾丸から壁へ向かう向きで返します
+fn get_contact_normal(context: &RapierContext, bullet: Entity, wall: Entity) -> Option<Vec2> {
+    let pair = context.contact_pair(bullet, wall)?;
+    let manifold = pair.manifolds().find(|m| 0 < m.num_points())?;
+    let normal = manifold.normal().normalize_or_zero();
+    if normal == Vec2::ZERO {
+        return None;
+    }
+    // 法線は collider1 から collider2 へ向かう向きです
+    Some(if pair.collider1() == bullet {
+        normal
+    } else {
+        -normal
+    })
+}
+
 pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (despawn_bullet_by_lifetime, bullet_collision, bullet_homing)
+            (
+                // 寿命が尽きたフレームに衝突した弾丸がペイロードや爆発を二重に発生させないよう、
+                // 寿命による削除を反映してから衝突を処理します
+                (despawn_bullet_by_lifetime, bullet_collision).chain(),
+                bullet_homing,
+                cast_triggered_payload,
+            )
                 .run_if(in_state(GameState::InGame))
                 .before(PhysicsSet::SyncBackend),
         );
+        app.add_event::<BulletTriggered>();
         app.register_type::<Bullet>();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bullet(damage: i32, explosion_radius: f32, pierce: u32) -> Bullet {
+        Bullet {
+            life: 60,
+            damage,
+            damage_type: DamageType::Physical,
+            impulse: 0.0,
+            owner: None,
+            homing: 0.0,
+            bounces: 0,
+            pierce,
+            hit_entities: Vec::new(),
+            trigger: None,
+            payload: Vec::new(),
+            status_effects: Vec::new(),
+            explosion_radius,
+            terrain: None,
+        }
+    }
+
+    #[test]
+    fn explosive_bullet_damages_only_by_explosion() {
+        // 爆発しない弾丸は直撃でダメージを与えます
+        assert_eq!(bullet(10, 0.0, 0).direct_damage(), 10);
+
+        // 命中して消滅する爆発する弾丸は、衝撃波だけがダメージを与えます
+        assert_eq!(bullet(10, 24.0, 0).direct_damage(), 0);
+
+        // 貫通して飛び続ける間は爆発しないため、直撃でダメージを与えます
+        assert_eq!(bullet(10, 24.0, 1).direct_damage(), 10);
+    }
+}
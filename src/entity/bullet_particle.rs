@@ -1,6 +1,8 @@
 use bevy::prelude::*;
+use rand::Rng;
 use std::f32::consts::PI;
 
+use crate::random::GameRng;
 use crate::states::GameState;
 
 #[derive(Resource)]
@@ -29,11 +31,12 @@ pub fn spawn_particle_system(
     commands: &mut Commands,
     position: Vec2,
     resource: &Res<BulletParticleResource>,
+    rng: &mut GameRng,
 ) {
     for _ in 0..10 {
-        let angle = rand::random::<f32>() * PI * 2.0;
-        let velocity = Vec2::from_angle(angle) * (0.4 + rand::random::<f32>() * 0.2);
-        let lifetime = 15 + rand::random::<usize>() % 10;
+        let angle = rng.cosmetic.gen::<f32>() * PI * 2.0;
+        let velocity = Vec2::from_angle(angle) * (0.4 + rng.cosmetic.gen::<f32>() * 0.2);
+        let lifetime = rng.cosmetic.gen_range(15..25);
         commands.spawn((
             Name::new("bullet_particle"),
             BulletParticle { velocity, lifetime },
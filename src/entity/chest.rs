@@ -1,21 +1,29 @@
 use crate::{asset::GameAssets, constant::*, se::SEEvent, states::GameState};
 use crate::{
     entity::{
+        dropped_item::spawn_dropped_wand,
         gold::spawn_gold,
         life::{Life, LifeBeingSprite},
         EntityDepth,
     },
+    level::CurrentLevel,
+    random::GameRng,
     se::SE,
+    spell_registry::SpellRegistry,
+    wand_generator::generate_wand,
 };
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::random;
+use rand::Rng;
 
 const ENTITY_WIDTH: f32 = 8.0;
 
 const ENTITY_HEIGHT: f32 = 8.0;
 
+/// 宝箱から杖が出る確率です
+const CHEST_WAND_CHANCE: f32 = 0.2;
+
 #[derive(Clone, Copy, PartialEq, Eq, Reflect, Default)]
 pub enum ChestType {
     #[default]
@@ -48,6 +56,7 @@ pub fn spawn_chest(
                 life: 30,
                 max_life: 30,
                 amplitude: 0.0,
+                resistance: default(),
             },
             Chest {
                 chest_type,
@@ -99,7 +108,10 @@ fn break_chest(
     mut commands: Commands,
     query: Query<(Entity, &Life, &Transform, &Chest)>,
     assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
+    current: Res<CurrentLevel>,
     mut writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, breakabke, transform, chest) in query.iter() {
         if breakabke.life <= 0 {
@@ -107,14 +119,29 @@ fn break_chest(
             writer.send(SEEvent::pos(SE::Break, transform.translation.truncate()));
 
             if chest.chest_type == ChestType::Chest {
-                for _ in 0..(3 + random::<i32>().abs() % 10) {
+                for _ in 0..rng.loot.gen_range(3..13) {
                     spawn_gold(
                         &mut commands,
                         &assets,
+                        &mut rng,
                         transform.translation.x,
                         transform.translation.y,
                     );
                 }
+
+                if rng.loot.gen::<f32>() < CHEST_WAND_CHANCE {
+                    let depth = current.level.map(|l| l.depth()).unwrap_or(0);
+                    let mut wand = generate_wand(&registry, depth, &mut rng.loot);
+                    wand.clear_price();
+                    spawn_dropped_wand(
+                        &mut commands,
+                        &assets,
+                        &registry,
+                        &mut rng,
+                        transform.translation.truncate(),
+                        wand,
+                    );
+                }
             }
         }
     }
@@ -1,4 +1,4 @@
-use crate::states::GameState;
+use crate::{element::DamageType, states::GameState};
 use bevy::{prelude::*, text::FontSmoothing};
 
 #[derive(Component)]
@@ -6,13 +6,19 @@ struct DamageParticle {
     lifetime: usize,
 }
 
-pub fn spawn_damage_number(commands: &mut Commands, damage: i32, position: Vec2) {
+/// ダメージの数値を、属性に応じた色で表示します
+pub fn spawn_damage_number(
+    commands: &mut Commands,
+    damage: i32,
+    damage_type: DamageType,
+    position: Vec2,
+) {
     commands.spawn((
         Name::new("Damage Number"),
         StateScoped(GameState::InGame),
         DamageParticle { lifetime: 40 },
         Text2d(format!("{}", damage).to_string()),
-        TextColor(Color::WHITE),
+        TextColor(damage_type.to_color()),
         TextFont {
             font_size: 8.0,
             font_smoothing: FontSmoothing::None,
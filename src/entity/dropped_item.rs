@@ -2,20 +2,31 @@ use crate::controller::player::Player;
 use crate::entity::EntityDepth;
 use crate::inventory::InventoryItem;
 use crate::inventory_item::InventoryItemType;
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
+use crate::spell_registry::SpellRegistry;
+use crate::wand::Wand;
 use crate::{asset::GameAssets, constant::*, states::GameState};
 use bevy::core::FrameCount;
 use bevy::prelude::*;
 use bevy::text::FontSmoothing;
 use bevy_aseprite_ultra::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 use super::actor::Actor;
 use super::life::Life;
 
+/// 床に落ちているアイテムの中身です
+/// 杖は性能と装填された呪文ごと床に落ちるため、インベントリのアイテムとは別に杖そのものを保持します
+pub enum DroppedContent {
+    Item(InventoryItem),
+    Wand(Wand),
+}
+
 #[derive(Component)]
 pub struct DroppedItemEntity {
-    item: InventoryItem,
+    content: DroppedContent,
 }
 
 #[derive(Component)]
@@ -27,40 +38,59 @@ struct SpellSprites {
 pub fn spawn_dropped_item(
     commands: &mut Commands,
     assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
     position: Vec2,
     item: InventoryItem,
 ) {
-    let item_type = item.item_type;
-    let icon = match item_type {
-        InventoryItemType::Spell(spell) => spell.to_props().icon,
-        InventoryItemType::Wand(wand) => wand.to_props().icon,
-        InventoryItemType::Equipment(equipment) => equipment.to_props().icon,
-    };
-    let name = match item_type {
-        InventoryItemType::Spell(spell) => spell.to_props().name.en,
-        InventoryItemType::Wand(wand) => wand.to_props().name.en,
-        InventoryItemType::Equipment(equipment) => equipment.to_props().name.en,
-    };
-    let frame_slice = match item_type {
-        InventoryItemType::Wand(_) => "empty", //"wand_frame",
-        InventoryItemType::Spell(_) if 0 < item.price => "spell_frame_yellow",
-        InventoryItemType::Spell(_) => "spell_frame",
-        InventoryItemType::Equipment(_) => "empty",
-    };
+    spawn_dropped(
+        commands,
+        assets,
+        registry,
+        rng,
+        position,
+        DroppedContent::Item(item),
+    );
+}
+
+/// 杖を床に落とします
+/// 拾うと空いている杖の枠に、性能と呪文を保ったまま装備されます
+/// 杖の枠に空きがない場合は、手に持っている杖と持ち替えます
+pub fn spawn_dropped_wand(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
+    position: Vec2,
+    wand: Wand,
+) {
+    spawn_dropped(
+        commands,
+        assets,
+        registry,
+        rng,
+        position,
+        DroppedContent::Wand(wand),
+    );
+}
+
+fn spawn_dropped(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
+    position: Vec2,
+    content: DroppedContent,
+) {
+    let item_type = content.item_type();
     let collider_width = match item_type {
         InventoryItemType::Wand(_) => 16.0,
         _ => 8.0,
     };
-    let swing = match item_type {
-        InventoryItemType::Spell(_) => 2.0,
-        InventoryItemType::Wand(_) => 0.0,
-        InventoryItemType::Equipment(_) => 0.0,
-    };
     commands
         .spawn((
-            Name::new(format!("dropped item {}", name)),
+            content.name(registry),
             StateScoped(GameState::InGame),
-            DroppedItemEntity { item },
             EntityDepth,
             InheritedVisibility::default(),
             Transform::from_translation(Vec3::new(position.x, position.y, 0.0)),
@@ -69,6 +99,7 @@ pub fn spawn_dropped_item(
                 life: 300,
                 max_life: 300,
                 amplitude: 0.0,
+                resistance: default(),
             },
             (
                 RigidBody::Dynamic,
@@ -93,47 +124,102 @@ pub fn spawn_dropped_item(
             ),
         ))
         .with_children(|parent| {
-            parent
-                .spawn((
-                    SpellSprites {
-                        swing,
-                        frame_count_offset: rand::random::<u32>() % 360,
+            spawn_dropped_sprites(parent, assets, registry, rng, &content);
+        })
+        .insert(DroppedItemEntity { content });
+}
+
+impl DroppedContent {
+    fn item_type(&self) -> InventoryItemType {
+        match self {
+            DroppedContent::Item(item) => item.item_type,
+            DroppedContent::Wand(wand) => InventoryItemType::Wand(wand.wand_type),
+        }
+    }
+
+    fn price(&self) -> u32 {
+        match self {
+            DroppedContent::Item(item) => item.price,
+            DroppedContent::Wand(wand) => wand.price,
+        }
+    }
+
+    fn name(&self, registry: &SpellRegistry) -> Name {
+        let name = match self.item_type() {
+            InventoryItemType::Spell(spell) => spell.to_props(registry).name.en,
+            InventoryItemType::Wand(wand) => wand.to_props().name.en,
+            InventoryItemType::Equipment(equipment) => equipment.to_props().name.en,
+        };
+        Name::new(format!("dropped item {}", name))
+    }
+}
+
+/// 床に落ちているアイテムのアイコンや値札を生成します
+/// 杖を持ち替えたときは中身が入れ替わるため、作り直せるように分けています
+fn spawn_dropped_sprites(
+    parent: &mut ChildBuilder,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
+    content: &DroppedContent,
+) {
+    let item_type = content.item_type();
+    let price = content.price();
+    let icon = match item_type {
+        InventoryItemType::Spell(spell) => spell.to_props(registry).icon,
+        InventoryItemType::Wand(wand) => wand.to_props().icon,
+        InventoryItemType::Equipment(equipment) => equipment.to_props().icon,
+    };
+    let frame_slice = match item_type {
+        InventoryItemType::Wand(_) => "empty", //"wand_frame",
+        InventoryItemType::Spell(_) if 0 < price => "spell_frame_yellow",
+        InventoryItemType::Spell(_) => "spell_frame",
+        InventoryItemType::Equipment(_) => "empty",
+    };
+    let swing = match item_type {
+        InventoryItemType::Spell(_) => 2.0,
+        InventoryItemType::Wand(_) => 0.0,
+        InventoryItemType::Equipment(_) => 0.0,
+    };
+    parent
+        .spawn((
+            SpellSprites {
+                swing,
+                frame_count_offset: rng.cosmetic.gen_range(0..360),
+            },
+            Transform::from_xyz(0.0, 0.0, 0.0),
+            GlobalTransform::default(),
+            InheritedVisibility::default(),
+        ))
+        .with_children(|parent| {
+            if 0 < price {
+                parent.spawn((
+                    Text2d(format!("{}", price)),
+                    TextFont {
+                        font: assets.dotgothic.clone(),
+                        font_size: 24.0,
+                        font_smoothing: FontSmoothing::None,
                     },
-                    Transform::from_xyz(0.0, 0.0, 0.0),
-                    GlobalTransform::default(),
-                    InheritedVisibility::default(),
-                ))
-                .with_children(|parent| {
-                    if 0 < item.price {
-                        parent.spawn((
-                            Text2d(format!("{}", item.price)),
-                            TextFont {
-                                font: assets.dotgothic.clone(),
-                                font_size: 24.0,
-                                font_smoothing: FontSmoothing::None,
-                            },
-                            TextColor(Color::WHITE),
-                            Transform::from_xyz(0.0, 14.0, 1.0)
-                                .with_scale(Vec3::new(0.3, 0.3, 1.0)),
-                        ));
-                    }
+                    TextColor(Color::WHITE),
+                    Transform::from_xyz(0.0, 14.0, 1.0).with_scale(Vec3::new(0.3, 0.3, 1.0)),
+                ));
+            }
+
+            parent.spawn((
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: frame_slice.into(),
+                },
+                Transform::from_xyz(0.0, 0.0, 0.0),
+            ));
 
-                    parent.spawn((
-                        AseSpriteSlice {
-                            aseprite: assets.atlas.clone(),
-                            name: frame_slice.into(),
-                        },
-                        Transform::from_xyz(0.0, 0.0, 0.0),
-                    ));
-
-                    parent.spawn((
-                        AseSpriteSlice {
-                            aseprite: assets.atlas.clone(),
-                            name: icon.into(),
-                        },
-                        Transform::from_xyz(0.0, 0.0, 0.0001),
-                    ));
-                });
+            parent.spawn((
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: icon.into(),
+                },
+                Transform::from_xyz(0.0, 0.0, 0.0001),
+            ));
         });
 }
 
@@ -150,6 +236,9 @@ fn collision(
     item_query: Query<&DroppedItemEntity>,
     mut player_query: Query<&mut Actor, With<Player>>,
     mut global: EventWriter<SEEvent>,
+    assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
+    mut rng: ResMut<GameRng>,
 ) {
     for collision_event in collision_events.read() {
         match collision_event {
@@ -161,6 +250,9 @@ fn collision(
                     &item_query,
                     &mut player_query,
                     &mut global,
+                    &assets,
+                    &registry,
+                    &mut rng,
                 ) || chat_start(
                     &mut commands,
                     b,
@@ -168,6 +260,9 @@ fn collision(
                     &item_query,
                     &mut player_query,
                     &mut global,
+                    &assets,
+                    &registry,
+                    &mut rng,
                 );
             }
             CollisionEvent::Stopped(..) => {}
@@ -182,10 +277,41 @@ fn chat_start(
     item_query: &Query<&DroppedItemEntity>,
     player_query: &mut Query<&mut Actor, With<Player>>,
     global: &mut EventWriter<SEEvent>,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    rng: &mut GameRng,
 ) -> bool {
     match (item_query.get(*a), player_query.get_mut(*b)) {
         (Ok(item), Ok(mut actor)) => {
-            if actor.inventory.insert(item.item) {
+            let picked = match &item.content {
+                DroppedContent::Item(item) => actor.inventory.insert(*item),
+                DroppedContent::Wand(wand) => {
+                    // 拾った杖は呪文を装填したまま、空いている杖の枠に直接装備します
+                    if let Some(index) = actor.wands.iter().position(|w| w.is_none()) {
+                        actor.wands[index] = Some(wand.clone());
+                        true
+                    } else {
+                        // 杖の枠に空きがない場合は、いま持っている杖と持ち替えます
+                        // 床の杖はその場で中身だけを入れ替えるため、続けて拾い直すことはありません
+                        let current = actor.current_wand;
+                        let Some(previous) = actor.wands[current].take() else {
+                            return false;
+                        };
+                        actor.wands[current] = Some(wand.clone());
+                        let content = DroppedContent::Wand(previous);
+                        commands
+                            .entity(*a)
+                            .despawn_descendants()
+                            .with_children(|parent| {
+                                spawn_dropped_sprites(parent, assets, registry, rng, &content);
+                            })
+                            .insert((content.name(registry), DroppedItemEntity { content }));
+                        global.send(SEEvent::new(SE::PickUp));
+                        return true;
+                    }
+                }
+            };
+            if picked {
                 commands.entity(*a).despawn_recursive();
                 global.send(SEEvent::new(SE::PickUp));
                 return true;
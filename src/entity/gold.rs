@@ -1,9 +1,10 @@
 use crate::entity::EntityDepth;
+use crate::random::GameRng;
 use crate::{asset::GameAssets, constant::*, states::GameState};
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
 use bevy_rapier2d::prelude::*;
-use rand::random;
+use rand::Rng;
 use std::f32::consts::PI;
 
 #[derive(Default, Component)]
@@ -12,7 +13,13 @@ pub struct Gold;
 /// チェストを生成します
 /// 指定する位置はスプライトの左上ではなく、重心のピクセル座標です
 /// 大量に生成したときに重なりが減るように、この関数内でランダムな位置にずらしています
-pub fn spawn_gold(commands: &mut Commands, assets: &Res<GameAssets>, x: f32, y: f32) {
+pub fn spawn_gold(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    rng: &mut GameRng,
+    x: f32,
+    y: f32,
+) {
     let tx = x;
     let ty = y;
     commands.spawn((
@@ -21,8 +28,8 @@ pub fn spawn_gold(commands: &mut Commands, assets: &Res<GameAssets>, x: f32, y:
         Gold,
         EntityDepth,
         Transform::from_translation(Vec3::new(
-            tx + (random::<f32>() - 0.5) * 16.0,
-            ty + (random::<f32>() - 0.5) * 16.0,
+            tx + (rng.cosmetic.gen::<f32>() - 0.5) * 16.0,
+            ty + (rng.cosmetic.gen::<f32>() - 0.5) * 16.0,
             0.0,
         )),
         AseSpriteSlice {
@@ -30,7 +37,7 @@ pub fn spawn_gold(commands: &mut Commands, assets: &Res<GameAssets>, x: f32, y:
             name: "gold".into(),
         },
         LockedAxes::ROTATION_LOCKED,
-        Velocity::linear(Vec2::from_angle(2.0 * PI * random::<f32>()) * 20.0),
+        Velocity::linear(Vec2::from_angle(2.0 * PI * rng.cosmetic.gen::<f32>()) * 20.0),
         RigidBody::Dynamic,
         // Restitution::coefficient(0.2),
         // Friction::coefficient(0.2),
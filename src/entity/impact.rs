@@ -1,17 +1,33 @@
+use crate::controller::remote::RemotePlayer;
+use crate::element::DamageType;
 use crate::entity::life::Life;
+use crate::entity::status_effect::StatusEffects;
+use crate::level::terrain::DamageTerrain;
 use crate::se::SE;
 use crate::{
-    asset::GameAssets,
-    camera::GameCamera,
-    constant::{ENEMY_GROUP, ENTITY_GROUP, PAINT_LAYER_Z, WITCH_GROUP},
-    entity::damege::spawn_damage_number,
-    se::SEEvent,
-    states::GameState,
+    asset::GameAssets, camera::GameCamera, constant::PAINT_LAYER_Z,
+    entity::damege::spawn_damage_number, se::SEEvent, states::GameState,
 };
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::AseSpriteAnimation;
+use bevy_light_2d::light::PointLight2d;
 use bevy_rapier2d::prelude::*;
 
+// 衝撃波のスプライトの半径
+// 衝撃波の半径に合わせてスプライトを拡大縮小します
+const IMPACT_SPRITE_RADIUS: f32 = 40.0;
+
+// 衝撃波の端でのダメージの減衰率
+// 中心では damage の値そのまま、端ではその半分になります
+const IMPACT_DAMAGE_FALLOFF: f32 = 0.5;
+
+const IMPACT_LIFETIME: u32 = 60;
+
+// 閃光が消えるまでのフレーム数
+const IMPACT_FLASH_FRAMES: u32 = 20;
+
+const IMPACT_FLASH_INTENSITY: f32 = 4.0;
+
 #[derive(Component)]
 struct Impact {
     lifetime: u32,
@@ -19,12 +35,23 @@ struct Impact {
 
 /// 一定の範囲にダメージと吹き飛ばし効果を与える衝撃波です
 /// 1フレームだけ当たり判定があり、すぐに消えます
+/// 当たり判定は group と filter で絞り込まれるため、発生させた側の仲間には命中しません
 #[derive(Event)]
 pub struct SpawnImpact {
-    pub owner: Entity,
+    /// 衝撃波を発生させたエンティティ
+    /// このエンティティ自身には命中しません
+    pub owner: Option<Entity>,
     pub position: Vec2,
     pub radius: f32,
     pub impulse: f32,
+    /// 中心でのダメージ
+    /// 中心から離れるほど減衰します
+    pub damage: i32,
+    pub damage_type: DamageType,
+    pub group: Group,
+    pub filter: Group,
+    /// 爆発の閃光を表示するかどうか
+    pub light: bool,
 }
 
 fn read_impact_event(
@@ -33,8 +60,17 @@ fn read_impact_event(
     rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
     mut writer: EventWriter<SEEvent>,
     mut reader: EventReader<SpawnImpact>,
-    mut life_query: Query<(&mut Life, &Transform, Option<&mut ExternalImpulse>)>,
+    mut life_query: Query<
+        (
+            &mut Life,
+            &Transform,
+            Option<&mut ExternalImpulse>,
+            Option<&StatusEffects>,
+        ),
+        Without<RemotePlayer>,
+    >,
     mut camera_query: Query<(&mut GameCamera, &Transform), Without<Life>>,
+    mut terrain_writer: EventWriter<DamageTerrain>,
 ) {
     let context: &RapierContext = rapier_context.single();
 
@@ -43,6 +79,11 @@ fn read_impact_event(
         position,
         radius,
         impulse,
+        damage,
+        damage_type,
+        group,
+        filter,
+        light,
     } in reader.read()
     {
         writer.send(SEEvent::pos(SE::Drop, *position));
@@ -51,6 +92,14 @@ fn read_impact_event(
         let max_range = 320.0; // 振動が起きる最大距離
         camera.vibration = (20.0 * (max_range - distance).max(0.0) / max_range).min(10.0);
 
+        // ダメージのある衝撃波は、範囲内のひび割れた壁を崩します
+        if 0 < *damage {
+            terrain_writer.send(DamageTerrain {
+                position: *position,
+                radius: *radius,
+            });
+        }
+
         let mut entities: Vec<Entity> = Vec::new();
 
         context.intersections_with_shape(
@@ -58,14 +107,11 @@ fn read_impact_event(
             0.0,
             &Collider::ball(*radius),
             QueryFilter {
-                groups: Some(CollisionGroups::new(
-                    ENEMY_GROUP,
-                    WITCH_GROUP | ENEMY_GROUP | ENTITY_GROUP,
-                )),
+                groups: Some(CollisionGroups::new(*group, *filter)),
                 ..default()
             },
             |entity| {
-                if entity != *owner {
+                if Some(entity) != *owner {
                     entities.push(entity);
                 }
                 true // 交差図形の検索を続ける
@@ -73,12 +119,18 @@ fn read_impact_event(
         );
 
         for entity in entities {
-            if let Ok((mut life, life_transform, mut external_impulse)) = life_query.get_mut(entity)
+            if let Ok((mut life, life_transform, mut external_impulse, status)) =
+                life_query.get_mut(entity)
             {
-                let damage = 10;
                 let p = life_transform.translation.truncate();
+                let ratio = (p.distance(*position) / radius).min(1.0);
+                let damage = ((*damage as f32 * (1.0 - ratio * IMPACT_DAMAGE_FALLOFF)).round()
+                    as i32)
+                    .max(1);
+                let damage = life.resistance.apply(damage, *damage_type);
+                let damage = status.map(|s| s.filter_damage(damage)).unwrap_or(damage);
                 life.life = (life.life - damage).max(0);
-                spawn_damage_number(&mut commands, 10, p);
+                spawn_damage_number(&mut commands, damage, *damage_type, p);
                 writer.send(SEEvent::pos(SE::Damage, p));
                 if let Some(ref mut ex) = external_impulse {
                     ex.impulse = (p - position).normalize_or_zero() * impulse;
@@ -86,24 +138,48 @@ fn read_impact_event(
             }
         }
 
-        commands.spawn((
+        let mut entity = commands.spawn((
             Name::new("impact"),
-            Impact { lifetime: 60 },
+            Impact {
+                lifetime: IMPACT_LIFETIME,
+            },
             AseSpriteAnimation {
                 aseprite: assets.impact.clone(),
                 animation: "idle".into(),
             },
-            Transform::from_translation(position.extend(PAINT_LAYER_Z)),
+            Transform::from_translation(position.extend(PAINT_LAYER_Z))
+                .with_scale(Vec3::splat(radius / IMPACT_SPRITE_RADIUS)),
         ));
+
+        if *light {
+            entity.insert(PointLight2d {
+                radius: radius * 3.0,
+                intensity: IMPACT_FLASH_INTENSITY,
+                falloff: 10.0,
+                color: Color::hsl(30.0, 1.0, 0.6),
+                ..default()
+            });
+        }
     }
 }
 
-fn update_impact(mut commands: Commands, mut query: Query<(Entity, &mut Impact)>) {
-    for (entity, mut impact) in query.iter_mut() {
+fn update_impact(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Impact, Option<&mut PointLight2d>)>,
+) {
+    for (entity, mut impact, light) in query.iter_mut() {
         impact.lifetime -= 1;
         if impact.lifetime <= 0 {
             commands.entity(entity).despawn_recursive();
         }
+
+        // 閃光は最初の数フレームで消えていきます
+        if let Some(mut light) = light {
+            let elapsed = IMPACT_LIFETIME - impact.lifetime;
+            light.intensity = IMPACT_FLASH_INTENSITY
+                * (IMPACT_FLASH_FRAMES.saturating_sub(elapsed)) as f32
+                / IMPACT_FLASH_FRAMES as f32;
+        }
     }
 }
 
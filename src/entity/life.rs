@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::states::GameState;
+use crate::{element::Resistance, states::GameState};
 
 /// 木箱やトーチなどの破壊可能なオブジェクトを表すコンポーネントです
 /// 弾丸は Breakable コンポーネントを持つエンティティに対してダメージを与えます
@@ -14,6 +14,9 @@ pub struct Life {
 
     /// ダメージを受けた時の振動の幅
     pub amplitude: f32,
+
+    /// 属性ごとの被ダメージの倍率
+    pub resistance: Resistance,
 }
 
 /// ダメージを受けた時に振動するスプライト
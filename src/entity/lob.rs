@@ -0,0 +1,230 @@
+use crate::asset::GameAssets;
+use crate::cast::cast_payload;
+use crate::constant::*;
+use crate::controller::remote::RemotePlayer;
+use crate::curve::jump_curve;
+use crate::entity::actor::Actor;
+use crate::entity::impact::SpawnImpact;
+use crate::entity::summon::{get_summon_group, spawn_summon};
+use crate::hud::life_bar::LifeBarResource;
+use crate::level::CurrentLevel;
+use crate::random::GameRng;
+use crate::se::{SEEvent, SE};
+use crate::spell::SpellType;
+use crate::spell_props::{LobProps, SpellCast};
+use crate::spell_registry::SpellRegistry;
+use crate::states::GameState;
+use bevy::core::FrameCount;
+use bevy::prelude::*;
+use bevy_aseprite_ultra::prelude::*;
+use bevy_rapier2d::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 投擲された弾です
+/// この弾自身は衝突判定を持たず、影の位置を地面に沿って動かしながら、子のスプライトを放物線に沿って浮かせます
+#[derive(Component)]
+pub struct Lob {
+    sender: Option<Uuid>,
+    from: Vec2,
+    to: Vec2,
+    props: LobProps,
+    payload: Vec<SpellType>,
+    payload_seed: u64,
+    group: Group,
+    filter: Group,
+    elapsed: u32,
+}
+
+#[derive(Component)]
+struct LobSprite;
+
+/// 弾を投擲するイベントです
+/// ローカルで詠唱されたときに発行されるほか、
+/// リモートで送信される RemoteMessage::Lob のデータとしても共通で使われます
+/// 着地時の衝撃波とペイロードの弾丸はローカルとリモートの双方で発生しますが、
+/// 召喚はリモートには送信されないため、詠唱者のいるクライアントでだけ行われます
+#[derive(Event, Clone, Debug, Serialize, Deserialize)]
+pub struct SpawnLob {
+    pub sender: Option<Uuid>,
+    pub from: Vec2,
+    pub to: Vec2,
+    pub props: LobProps,
+    pub payload: Vec<SpellType>,
+
+    /// ペイロードの弾丸の拡散に使う乱数のシード
+    /// 詠唱者が決めたシードをリモートにも送信するため、双方で同じ弾丸が発射されます
+    pub payload_seed: u64,
+
+    pub group: Group,
+    pub filter: Group,
+}
+
+fn read_spawn_lob_event(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut reader: EventReader<SpawnLob>,
+    mut se_writer: EventWriter<SEEvent>,
+) {
+    for spawn in reader.read() {
+        commands
+            .spawn((
+                Name::new("lob"),
+                StateScoped(GameState::InGame),
+                Lob {
+                    sender: spawn.sender,
+                    from: spawn.from,
+                    to: spawn.to,
+                    props: spawn.props.clone(),
+                    payload: spawn.payload.clone(),
+                    payload_seed: spawn.payload_seed,
+                    group: spawn.group,
+                    filter: spawn.filter,
+                    elapsed: 0,
+                },
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: "entity_shadow".into(),
+                },
+                Transform::from_translation(spawn.from.extend(SUMMON_SEED_LAYER_Z)),
+            ))
+            .with_child((
+                LobSprite,
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: spawn.props.slice.clone().into(),
+                },
+            ));
+
+        se_writer.send(SEEvent::pos(SE::Shuriken, spawn.from));
+    }
+}
+
+/// 投擲された弾を動かし、着地したら衝撃波を発生させてペイロードを詠唱します
+fn update_lob(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
+    life_bar_res: Res<LifeBarResource>,
+    frame_count: Res<FrameCount>,
+    current: Res<CurrentLevel>,
+    mut query: Query<(Entity, &mut Lob, &mut Transform)>,
+    actor_query: Query<(Entity, &Actor), Without<RemotePlayer>>,
+    mut impact_writer: EventWriter<SpawnImpact>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for (entity, mut lob, mut transform) in query.iter_mut() {
+        lob.elapsed += 1;
+        transform.translation = lob
+            .from
+            .lerp(lob.to, lob.elapsed as f32 / lob.props.flight_time as f32)
+            .extend(SUMMON_SEED_LAYER_Z);
+
+        if lob.elapsed < lob.props.flight_time {
+            continue;
+        }
+
+        commands.entity(entity).despawn_recursive();
+        se_writer.send(SEEvent::pos(SE::Bicha, lob.to));
+
+        if 0.0 < lob.props.explosion_radius {
+            impact_writer.send(SpawnImpact {
+                owner: None,
+                position: lob.to,
+                radius: lob.props.explosion_radius,
+                impulse: lob.props.impulse,
+                damage: lob.props.damage,
+                damage_type: lob.props.damage_type,
+                group: lob.group,
+                filter: lob.filter,
+                light: true,
+            });
+        }
+
+        cast_payload(
+            &mut commands,
+            &assets,
+            &registry,
+            &mut StdRng::seed_from_u64(lob.payload_seed),
+            &mut se_writer,
+            &lob.payload,
+            lob.sender,
+            lob.to,
+            (lob.to - lob.from).to_angle(),
+            lob.group,
+            lob.filter,
+        );
+
+        // 召喚はペイロードの弾丸とは異なりリモートにも送信されないため、詠唱者のいるクライアントでだけ行います
+        let Some((owner, owner_actor)) =
+            actor_query.iter().find(|(_, a)| Some(a.uuid) == lob.sender)
+        else {
+            continue;
+        };
+        let on_floor = current
+            .chunk
+            .as_ref()
+            .map(|chunk| chunk.get_tile_by_coords(lob.to).is_floor())
+            .unwrap_or(false);
+        if !on_floor {
+            continue;
+        }
+        for spell_type in lob.payload.iter() {
+            let Some(props) = registry.get(*spell_type) else {
+                continue;
+            };
+            if let SpellCast::Summon {
+                summon_type,
+                friend,
+                duration,
+            } = &props.cast
+            {
+                spawn_summon(
+                    &mut commands,
+                    &assets,
+                    &life_bar_res,
+                    &frame_count,
+                    &mut rng,
+                    *summon_type,
+                    lob.to,
+                    get_summon_group(owner_actor.actor_group, *friend),
+                    owner,
+                    Some(*duration),
+                );
+            }
+        }
+    }
+}
+
+fn update_lob_sprite(
+    parent_query: Query<&Lob>,
+    mut query: Query<(&Parent, &mut Transform), With<LobSprite>>,
+) {
+    for (parent, mut transform) in query.iter_mut() {
+        if let Ok(lob) = parent_query.get(parent.get()) {
+            transform.translation.y = jump_curve(
+                lob.props.flight_time as f32,
+                lob.props.height,
+                lob.elapsed as f32,
+            );
+        }
+    }
+}
+
+pub struct LobPlugin;
+
+impl Plugin for LobPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnLob>();
+        app.add_systems(
+            FixedUpdate,
+            (read_spawn_lob_event, update_lob, update_lob_sprite)
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
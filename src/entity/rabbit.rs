@@ -5,6 +5,7 @@ use crate::controller::player::Player;
 use crate::entity::actor::{Actor, ActorFireState};
 use crate::entity::actor::{ActorGroup, ActorState};
 use crate::entity::life::Life;
+use crate::entity::status_effect::StatusEffects;
 use crate::entity::EntityChildrenAutoDepth;
 use crate::inventory::Inventory;
 use crate::language::Dict;
@@ -42,15 +43,20 @@ pub fn spawn_rabbit(commands: &mut Commands, assets: &Res<GameAssets>, position:
                 effects: default(),
                 actor_group: ActorGroup::Player,
                 golds: 0,
+                mana: 1000,
+                max_mana: 1000,
+                mana_regen: 10,
                 inventory: Inventory::new(),
                 equipments: [None; MAX_ITEMS_IN_EQUIPMENT],
                 wands: [None, None, None, None],
             },
             ActorState::default(),
+            StatusEffects::default(),
             Life {
                 life: 100000,
                 max_life: 100000,
                 amplitude: 0.0,
+                resistance: default(),
             },
             Transform::from_translation(position.extend(0.0)),
             GlobalTransform::default(),
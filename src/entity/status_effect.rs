@@ -0,0 +1,264 @@
+use crate::element::DamageType;
+use crate::entity::damege::spawn_damage_number;
+use crate::entity::life::Life;
+use crate::language::Dict;
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy_rapier2d::plugin::PhysicsSet;
+use serde::{Deserialize, Serialize};
+
+/// アクターに一定時間作用する状態異常や強化の種類です
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum StatusEffectType {
+    /// 一定間隔で炎のダメージを受けます
+    Burning,
+    /// 一定間隔で毒のダメージを受けます。重ねがけでダメージが増加します
+    Poisoned,
+    /// 移動速度が大きく低下します
+    Frozen,
+    /// 移動速度が上昇します
+    Hasted,
+    /// ダメージを受けません
+    Invulnerable,
+    /// 呪文を詠唱できません
+    Silenced,
+}
+
+/// ライフバーに表示するアイコンの並び順です
+pub const STATUS_EFFECT_TYPES: [StatusEffectType; 6] = [
+    StatusEffectType::Burning,
+    StatusEffectType::Poisoned,
+    StatusEffectType::Frozen,
+    StatusEffectType::Hasted,
+    StatusEffectType::Invulnerable,
+    StatusEffectType::Silenced,
+];
+
+impl StatusEffectType {
+    pub fn to_dict(&self) -> Dict {
+        match self {
+            StatusEffectType::Burning => Dict {
+                ja: "炎上",
+                en: "Burning",
+            },
+            StatusEffectType::Poisoned => Dict {
+                ja: "毒",
+                en: "Poisoned",
+            },
+            StatusEffectType::Frozen => Dict {
+                ja: "凍結",
+                en: "Frozen",
+            },
+            StatusEffectType::Hasted => Dict {
+                ja: "加速",
+                en: "Hasted",
+            },
+            StatusEffectType::Invulnerable => Dict {
+                ja: "無敵",
+                en: "Invulnerable",
+            },
+            StatusEffectType::Silenced => Dict {
+                ja: "沈黙",
+                en: "Silenced",
+            },
+        }
+    }
+
+    /// ライフバーの上に表示するアイコンの、atlas.aseprite のスライス名です
+    pub fn icon(&self) -> &'static str {
+        match self {
+            StatusEffectType::Burning => "status_burning",
+            StatusEffectType::Poisoned => "status_poisoned",
+            StatusEffectType::Frozen => "status_frozen",
+            StatusEffectType::Hasted => "status_hasted",
+            StatusEffectType::Invulnerable => "status_invulnerable",
+            StatusEffectType::Silenced => "status_silenced",
+        }
+    }
+
+    /// 重ねがけできる最大の数です
+    /// 1の場合は重ねがけせず、持続時間だけが更新されます
+    fn max_stacks(&self) -> u32 {
+        match self {
+            StatusEffectType::Poisoned => 5,
+            _ => 1,
+        }
+    }
+
+    /// 継続ダメージの (間隔のフレーム数, 1スタックあたりのダメージ, 属性) です
+    fn tick_damage(&self) -> Option<(u32, i32, DamageType)> {
+        match self {
+            StatusEffectType::Burning => Some((30, 1, DamageType::Fire)),
+            StatusEffectType::Poisoned => Some((60, 1, DamageType::Poison)),
+            _ => None,
+        }
+    }
+
+    /// 移動力に掛かる倍率です
+    fn move_force_factor(&self) -> f32 {
+        match self {
+            StatusEffectType::Frozen => 0.3,
+            StatusEffectType::Hasted => 1.5,
+            _ => 1.0,
+        }
+    }
+}
+
+/// 付与される状態異常ひとつぶんの内容です
+/// 呪文定義ファイルでも弾丸が命中時に付与する効果として記述されます
+#[derive(Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+pub struct StatusEffect {
+    pub effect_type: StatusEffectType,
+
+    /// 持続時間のフレーム数
+    pub duration: u32,
+
+    #[serde(default = "default_stacks")]
+    pub stacks: u32,
+}
+
+fn default_stacks() -> u32 {
+    1
+}
+
+/// アクターに現在作用している状態異常の一覧です
+#[derive(Component, Reflect, Default, Clone)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// 状態異常を付与します
+    /// すでに同じ種類の効果がある場合は持続時間の長いほうを残し、
+    /// 重ねがけできる効果であればスタック数を加算します
+    pub fn apply(&mut self, effect: StatusEffect) {
+        if effect.duration == 0 {
+            return;
+        }
+        let max_stacks = effect.effect_type.max_stacks();
+        if let Some(current) = self
+            .effects
+            .iter_mut()
+            .find(|e| e.effect_type == effect.effect_type)
+        {
+            current.duration = current.duration.max(effect.duration);
+            current.stacks = (current.stacks + effect.stacks).min(max_stacks);
+        } else {
+            self.effects.push(StatusEffect {
+                stacks: effect.stacks.min(max_stacks).max(1),
+                ..effect
+            });
+        }
+    }
+
+    /// 指定した種類の効果を取り除きます
+    pub fn remove(&mut self, effect_type: StatusEffectType) {
+        self.effects.retain(|e| e.effect_type != effect_type);
+    }
+
+    pub fn has(&self, effect_type: StatusEffectType) -> bool {
+        self.effects.iter().any(|e| e.effect_type == effect_type)
+    }
+
+    pub fn can_cast(&self) -> bool {
+        !self.has(StatusEffectType::Silenced)
+    }
+
+    pub fn move_force_factor(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|e| e.effect_type.move_force_factor())
+            .product()
+    }
+
+    /// 無敵状態を考慮した実際のダメージを返します
+    pub fn filter_damage(&self, damage: i32) -> i32 {
+        if self.has(StatusEffectType::Invulnerable) {
+            0
+        } else {
+            damage
+        }
+    }
+}
+
+/// 状態異常の持続時間を減らし、継続ダメージを与え、期限切れの効果を取り除きます
+fn update_status_effects(
+    mut commands: Commands,
+    mut query: Query<(&mut StatusEffects, &mut Life, &Transform)>,
+) {
+    for (mut status, mut life, transform) in query.iter_mut() {
+        if status.effects.is_empty() {
+            continue;
+        }
+
+        let invulnerable = status.has(StatusEffectType::Invulnerable);
+
+        for effect in status.effects.iter_mut() {
+            effect.duration -= 1;
+
+            if let Some((interval, damage, damage_type)) = effect.effect_type.tick_damage() {
+                if effect.duration % interval == 0 && !invulnerable {
+                    let damage = life
+                        .resistance
+                        .apply(damage * effect.stacks as i32, damage_type);
+                    life.life = (life.life - damage).max(0);
+                    life.amplitude = 2.0;
+                    spawn_damage_number(
+                        &mut commands,
+                        damage,
+                        damage_type,
+                        transform.translation.truncate(),
+                    );
+                }
+            }
+        }
+
+        status.effects.retain(|e| 0 < e.duration);
+    }
+}
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<StatusEffects>();
+        app.add_systems(
+            FixedUpdate,
+            update_status_effects
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spell::SpellType;
+    use crate::spell_props::SpellCast;
+    use crate::spell_registry::SpellRegistry;
+
+    #[test]
+    fn silence_bolt_prevents_casting() {
+        let registry =
+            SpellRegistry::from_json(include_str!("../../assets/data/registry.spell.json"))
+                .unwrap();
+        let SpellCast::Bullet(ref bullet) = SpellType::find("SilenceBolt")
+            .unwrap()
+            .to_props(&registry)
+            .cast
+        else {
+            panic!("SilenceBolt must be a bullet");
+        };
+
+        let mut status = StatusEffects::default();
+        assert!(status.can_cast());
+        for effect in bullet.status_effects.iter() {
+            status.apply(*effect);
+        }
+        assert!(!status.can_cast());
+
+        status.remove(StatusEffectType::Silenced);
+        assert!(status.can_cast());
+    }
+}
@@ -34,6 +34,7 @@ pub fn spawn_stone_lantern(commands: &mut Commands, assets: &Res<GameAssets>, x:
                 life: 50,
                 max_life: 50,
                 amplitude: 0.0,
+                resistance: default(),
             },
             StoneLantern,
             EntityDepth,
@@ -0,0 +1,202 @@
+use crate::asset::GameAssets;
+use crate::constant::MAX_SUMMONS_PER_CASTER;
+use crate::enemy::eyeball::spawn_eyeball;
+use crate::enemy::slime::spawn_slime;
+use crate::entity::actor::ActorGroup;
+use crate::entity::witch::spawn_enemy_witch;
+use crate::hud::life_bar::LifeBarResource;
+use crate::language::Dict;
+use crate::random::GameRng;
+use crate::se::{SEEvent, SE};
+use crate::states::GameState;
+use bevy::core::FrameCount;
+use bevy::prelude::*;
+use bevy_aseprite_ultra::prelude::*;
+use bevy_rapier2d::plugin::PhysicsSet;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 召喚呪文で呼び出せる生き物の種類です
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum SummonType {
+    Slime,
+    Eyeball,
+    Witch,
+}
+
+impl SummonType {
+    pub fn to_dict(&self) -> Dict {
+        match self {
+            SummonType::Slime => Dict {
+                ja: "スライム",
+                en: "Slime",
+            },
+            SummonType::Eyeball => Dict {
+                ja: "アイボール",
+                en: "Eyeball",
+            },
+            SummonType::Witch => Dict {
+                ja: "魔法使い",
+                en: "Witch",
+            },
+        }
+    }
+
+    /// 召喚の種として飛んでいく間に表示するスプライトを追加します
+    /// スライムとアイボールは本体のアニメーションを、魔法使いは種の画像を表示します
+    pub fn insert_seed_sprite(
+        &self,
+        entity: &mut EntityCommands,
+        assets: &GameAssets,
+        actor_group: ActorGroup,
+    ) {
+        match self {
+            SummonType::Slime => entity.insert(AseSpriteAnimation {
+                aseprite: match actor_group {
+                    ActorGroup::Player => assets.friend_slime.clone(),
+                    ActorGroup::Enemy => assets.slime.clone(),
+                },
+                animation: Animation::default().with_tag("idle"),
+            }),
+            SummonType::Eyeball => entity.insert(AseSpriteAnimation {
+                aseprite: assets.eyeball.clone(),
+                animation: Animation::default().with_tag("idle"),
+            }),
+            SummonType::Witch => entity.insert(AseSpriteSlice {
+                aseprite: assets.atlas.clone(),
+                name: "witch_seed".into(),
+            }),
+        };
+    }
+}
+
+/// 召喚された生き物の属するグループを返します
+/// friend が有効な場合は詠唱者の味方、そうでなければ詠唱者の敵になります
+pub fn get_summon_group(caster: ActorGroup, friend: bool) -> ActorGroup {
+    match (caster, friend) {
+        (ActorGroup::Player, true) => ActorGroup::Player,
+        (ActorGroup::Player, false) => ActorGroup::Enemy,
+        (ActorGroup::Enemy, true) => ActorGroup::Enemy,
+        (ActorGroup::Enemy, false) => ActorGroup::Player,
+    }
+}
+
+/// 召喚呪文で呼び出された生き物です
+/// 持続時間が過ぎると消滅し、同じ詠唱者の召喚数が上限を超えると古いものから消滅します
+#[derive(Component, Debug)]
+pub struct Summoned {
+    pub owner: Entity,
+
+    /// 消滅するまでの残りフレーム数
+    pub lifetime: u32,
+
+    /// 召喚されたフレーム
+    /// 上限を超えたときに古いものから消すために使います
+    pub spawned_at: u32,
+}
+
+/// 指定した種類の生き物を生成します
+/// duration を指定した場合は召喚として記録され、持続時間と詠唱者ごとの上限の対象になります
+/// 召喚された生き物は倒してもゴールドを落としません
+pub fn spawn_summon(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    life_bar_res: &Res<LifeBarResource>,
+    frame_count: &FrameCount,
+    rng: &mut GameRng,
+    summon_type: SummonType,
+    position: Vec2,
+    actor_group: ActorGroup,
+    owner: Entity,
+    duration: Option<u32>,
+) -> Entity {
+    let entity = match summon_type {
+        SummonType::Slime => spawn_slime(
+            commands,
+            assets,
+            position,
+            life_bar_res,
+            rng.combat.gen_range(30..60),
+            0,
+            actor_group,
+            Some(owner),
+        ),
+        SummonType::Eyeball => {
+            spawn_eyeball(commands, assets, position, life_bar_res, 0, actor_group)
+        }
+        SummonType::Witch => {
+            spawn_enemy_witch(commands, assets, life_bar_res, position, actor_group)
+        }
+    };
+
+    if let Some(duration) = duration {
+        commands.entity(entity).insert(Summoned {
+            owner,
+            lifetime: duration,
+            spawned_at: frame_count.0,
+        });
+    }
+
+    entity
+}
+
+/// 持続時間の過ぎた召喚を消滅させます
+fn expire_summons(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Summoned, &Transform)>,
+    mut se_writer: EventWriter<SEEvent>,
+) {
+    for (entity, mut summoned, transform) in query.iter_mut() {
+        if summoned.lifetime == 0 {
+            commands.entity(entity).despawn_recursive();
+            se_writer.send(SEEvent::pos(SE::Cry, transform.translation.truncate()));
+        } else {
+            summoned.lifetime -= 1;
+        }
+    }
+}
+
+/// 詠唱者ごとの召喚数が上限を超えている場合は、古いものから消滅させます
+/// 詠唱者がすでに倒れている場合も上限は変わりません
+fn limit_summons(
+    mut commands: Commands,
+    query: Query<(Entity, &Summoned)>,
+    mut se_writer: EventWriter<SEEvent>,
+    transform_query: Query<&Transform>,
+) {
+    let mut summons: HashMap<Entity, Vec<(Entity, u32)>> = HashMap::new();
+    for (entity, summoned) in query.iter() {
+        summons
+            .entry(summoned.owner)
+            .or_default()
+            .push((entity, summoned.spawned_at));
+    }
+
+    for (_, mut list) in summons {
+        if list.len() <= MAX_SUMMONS_PER_CASTER {
+            continue;
+        }
+        list.sort_by_key(|(_, spawned_at)| *spawned_at);
+        for (entity, _) in list.iter().take(list.len() - MAX_SUMMONS_PER_CASTER) {
+            commands.entity(*entity).despawn_recursive();
+            if let Ok(transform) = transform_query.get(*entity) {
+                se_writer.send(SEEvent::pos(SE::Cry, transform.translation.truncate()));
+            }
+        }
+    }
+}
+
+pub struct SummonPlugin;
+
+impl Plugin for SummonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (expire_summons, limit_summons)
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
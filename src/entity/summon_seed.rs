@@ -1,62 +1,76 @@
 use crate::asset::GameAssets;
 use crate::constant::*;
 use crate::curve::jump_curve;
-use crate::enemy::slime::spawn_slime;
+use crate::entity::summon::{spawn_summon, SummonType};
 use crate::hud::life_bar::LifeBarResource;
-use crate::level::tile::Tile;
 use crate::level::CurrentLevel;
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
 use crate::states::GameState;
+use bevy::core::FrameCount;
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
 use bevy_rapier2d::prelude::*;
+use rand::Rng;
 
 use super::actor::ActorGroup;
 
 #[derive(Component)]
-pub struct SlimeSeed {
+pub struct SummonSeed {
     animation: u32,
     from: Vec2,
     to: Vec2,
     speed: u32,
     actor_group: ActorGroup,
     owner: Entity,
+    summon_type: SummonType,
+    duration: Option<u32>,
 }
 
 #[derive(Component)]
-pub struct SlimeSeedSprite;
+pub struct SummonSeedSprite;
 
+/// 召喚の種を投げるイベントです
+/// 種が着地した位置に summon_type の生き物が召喚されます
+/// duration を指定した場合は召喚として扱われ、持続時間と詠唱者ごとの召喚数の上限の対象になります
 #[derive(Event)]
-pub struct SpawnSlimeSeed {
+pub struct SpawnSummonSeed {
     pub from: Vec2,
     pub to: Vec2,
     pub actor_group: ActorGroup,
     pub owner: Entity,
+    pub summon_type: SummonType,
+    pub duration: Option<u32>,
 }
 
-pub fn spawn_slime_seed(
+pub fn spawn_summon_seed(
     mut commands: Commands,
     assets: Res<GameAssets>,
-    mut reader: EventReader<SpawnSlimeSeed>,
+    mut reader: EventReader<SpawnSummonSeed>,
+    mut rng: ResMut<GameRng>,
 ) {
-    for SpawnSlimeSeed {
+    for SpawnSummonSeed {
         from,
         to,
         actor_group,
         owner,
+        summon_type,
+        duration,
     } in reader.read()
     {
         commands
             .spawn((
-                Name::new("slime_seed"),
+                Name::new("summon_seed"),
                 StateScoped(GameState::InGame),
-                SlimeSeed {
+                SummonSeed {
                     animation: 0,
                     from: *from,
                     to: *to,
-                    speed: 60 + rand::random::<u32>() % 30,
+                    speed: rng.combat.gen_range(60..90),
                     actor_group: *actor_group,
                     owner: *owner,
+                    summon_type: *summon_type,
+                    duration: *duration,
                 },
                 AseSpriteSlice {
                     aseprite: assets.atlas.clone(),
@@ -64,52 +78,50 @@ pub fn spawn_slime_seed(
                 },
                 Transform::from_translation(from.extend(SHADOW_LAYER_Z)),
             ))
-            .with_child((
-                SlimeSeedSprite,
-                AseSpriteAnimation {
-                    aseprite: match actor_group {
-                        ActorGroup::Player => assets.friend_slime.clone(),
-                        ActorGroup::Enemy => assets.slime.clone(),
-                    },
-                    animation: Animation::default().with_tag("idle"),
-                },
-            ));
+            .with_children(|parent| {
+                let mut sprite = parent.spawn(SummonSeedSprite);
+                summon_type.insert_seed_sprite(&mut sprite, &assets, *actor_group);
+            });
     }
 }
 
-fn update_slime_seed(
+fn update_summon_seed(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut SlimeSeed, &mut Transform)>,
+    mut query: Query<(Entity, &mut SummonSeed, &mut Transform)>,
     assets: Res<GameAssets>,
     life_bar_locals: Res<LifeBarResource>,
     mut se_writer: EventWriter<SEEvent>,
     current: Res<CurrentLevel>,
+    frame_count: Res<FrameCount>,
+    mut rng: ResMut<GameRng>,
 ) {
     for (entity, mut seed, mut transform) in query.iter_mut() {
         seed.animation += 1;
         transform.translation = seed
             .from
             .lerp(seed.to, seed.animation as f32 / seed.speed as f32)
-            .extend(SLIME_SEED_LAYER_Z);
+            .extend(SUMMON_SEED_LAYER_Z);
         if seed.animation == seed.speed {
             commands.entity(entity).despawn_recursive();
             if let Some(ref chunk) = current.chunk {
                 match chunk.get_tile_by_coords(seed.to) {
-                    Tile::StoneTile => {
-                        spawn_slime(
+                    tile if tile.is_floor() => {
+                        spawn_summon(
                             &mut commands,
                             &assets,
-                            seed.to,
                             &life_bar_locals,
-                            30 + rand::random::<u32>() % 30,
-                            0,
+                            &frame_count,
+                            &mut rng,
+                            seed.summon_type,
+                            seed.to,
                             seed.actor_group,
-                            Some(seed.owner),
+                            seed.owner,
+                            seed.duration,
                         );
                         se_writer.send(SEEvent::pos(SE::Bicha, seed.to));
                     }
                     tile => {
-                        warn!("SlimeSeed: Hit non-stone tile: {:?}", tile);
+                        warn!("SummonSeed: Hit non-floor tile: {:?}", tile);
                     }
                 }
             }
@@ -117,9 +129,9 @@ fn update_slime_seed(
     }
 }
 
-fn update_slime_seed_sprite(
-    parent_query: Query<&SlimeSeed>,
-    mut query: Query<(&Parent, &mut Transform), With<SlimeSeedSprite>>,
+fn update_summon_seed_sprite(
+    parent_query: Query<&SummonSeed>,
+    mut query: Query<(&Parent, &mut Transform), With<SummonSeedSprite>>,
 ) {
     for (parent, mut transform) in query.iter_mut() {
         if let Ok(seed) = parent_query.get(parent.get()) {
@@ -128,17 +140,17 @@ fn update_slime_seed_sprite(
     }
 }
 
-pub struct SlimeSeedPlugin;
+pub struct SummonSeedPlugin;
 
-impl Plugin for SlimeSeedPlugin {
+impl Plugin for SummonSeedPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnSlimeSeed>();
+        app.add_event::<SpawnSummonSeed>();
         app.add_systems(
             FixedUpdate,
             (
-                spawn_slime_seed,
-                update_slime_seed,
-                update_slime_seed_sprite,
+                spawn_summon_seed,
+                update_summon_seed,
+                update_summon_seed_sprite,
             )
                 .run_if(in_state(GameState::InGame))
                 .before(PhysicsSet::SyncBackend),
@@ -0,0 +1,174 @@
+use crate::constant::{TILE_SIZE, WALL_GROUP, WITCH_GROUP};
+use crate::controller::remote::{send_remote_message, RemoteMessage};
+use crate::entity::actor::Actor;
+use crate::entity::bullet_particle::{spawn_particle_system, BulletParticleResource};
+use crate::entity::witch::WITCH_COLLIDER_RADIUS;
+use crate::level::map::LevelChunk;
+use crate::level::CurrentLevel;
+use crate::random::GameRng;
+use crate::se::{SEEvent, SE};
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use bevy_simple_websocket::{ClientMessage, ReadyState, WebSocketState};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// 移動先を探すときに経路を調べる間隔
+const TELEPORT_STEP: f32 = TILE_SIZE / 4.0;
+
+/// 詠唱者を、ポインターの方向へ瞬間移動させるイベントです
+/// 移動先はこのイベントを処理するときにレベルの地形から決まります
+#[derive(Event)]
+pub struct CastTeleport {
+    pub actor: Entity,
+    pub range: f32,
+}
+
+/// アクターの瞬間移動を反映するイベントです
+/// ローカルで移動先が決まったときに発行されるほか、
+/// リモートで送信される RemoteMessage::Teleport のデータとしても共通で使われます
+/// リモートのプレイヤーは速度をもとに位置を補間しているため、瞬間移動の際には速度を打ち消します
+#[derive(Event, Clone, Debug, Serialize, Deserialize)]
+pub struct TeleportActor {
+    pub uuid: Uuid,
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+/// origin から direction の方向へ最大 range だけ進んだ位置のうち、壁にめり込まない最も遠い位置を返します
+/// まず詠唱者の体と同じ大きさの円を経路に沿って動かし、壁に触れるまでの距離に射程を縮めます
+/// そのうえで経路上のタイルを順に調べ、床以外のタイルに当たった場合はその手前の床の位置で止まります
+pub fn find_teleport_destination(
+    context: &RapierContext,
+    chunk: &LevelChunk,
+    origin: Vec2,
+    direction: Vec2,
+    range: f32,
+) -> Vec2 {
+    let direction = direction.normalize_or_zero();
+
+    // 経路の中心線だけでなく、体の幅のぶん斜めにかすめる壁の角も検出します
+    // 壁に接した状態から離れる方向へ移動する場合は妨げられないよう、最初から重なっている壁は無視します
+    let range = context
+        .cast_shape(
+            origin,
+            0.0,
+            direction,
+            &Collider::ball(WITCH_COLLIDER_RADIUS),
+            ShapeCastOptions {
+                max_time_of_impact: range,
+                target_distance: 0.0,
+                stop_at_penetration: false,
+                compute_impact_geometry_on_penetration: false,
+            },
+            QueryFilter::default()
+                .exclude_sensors()
+                .groups(CollisionGroups::new(WITCH_GROUP, WALL_GROUP)),
+        )
+        .map(|(_, hit)| hit.time_of_impact.min(range))
+        .unwrap_or(range);
+
+    let mut destination = origin;
+    let mut distance = TELEPORT_STEP;
+    while distance <= range {
+        let position = origin + direction * distance;
+        let front = position + direction * WITCH_COLLIDER_RADIUS;
+        if !chunk.get_tile_by_coords(position).is_floor()
+            || !chunk.get_tile_by_coords(front).is_floor()
+        {
+            break;
+        }
+        destination = position;
+        distance += TELEPORT_STEP;
+    }
+    destination
+}
+
+/// 瞬間移動の移動先を決め、ローカルに反映してリモートにも通知します
+fn read_cast_teleport_event(
+    mut reader: EventReader<CastTeleport>,
+    actor_query: Query<(&Actor, &Transform)>,
+    current: Res<CurrentLevel>,
+    rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
+    mut teleport_writer: EventWriter<TeleportActor>,
+    mut remote_writer: EventWriter<ClientMessage>,
+    websocket: Res<WebSocketState>,
+) {
+    let online = websocket.ready_state == ReadyState::OPEN;
+
+    let Some(ref chunk) = current.chunk else {
+        return;
+    };
+    let context: &RapierContext = rapier_context.single();
+
+    for CastTeleport { actor, range } in reader.read() {
+        let Ok((actor, transform)) = actor_query.get(*actor) else {
+            continue;
+        };
+        // ポインターが射程より近い場合は、ポインターの位置までの移動になります
+        let from = transform.translation.truncate();
+        let range = range.min(actor.pointer.length());
+        let to = find_teleport_destination(context, chunk, from, actor.pointer, range);
+        if from == to {
+            continue;
+        }
+
+        let teleport = TeleportActor {
+            uuid: actor.uuid,
+            from,
+            to,
+        };
+        teleport_writer.send(teleport.clone());
+        send_remote_message(
+            &mut remote_writer,
+            online,
+            &RemoteMessage::Teleport(teleport),
+        );
+    }
+}
+
+/// アクターを移動先へ移動させ、両端に効果を表示します
+fn read_teleport_actor_event(
+    mut commands: Commands,
+    mut reader: EventReader<TeleportActor>,
+    mut actor_query: Query<(&Actor, &mut Transform, Option<&mut Velocity>)>,
+    particle: Res<BulletParticleResource>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    for TeleportActor { uuid, from, to } in reader.read() {
+        let Some((_, mut transform, velocity)) =
+            actor_query.iter_mut().find(|(a, _, _)| a.uuid == *uuid)
+        else {
+            continue;
+        };
+
+        transform.translation.x = to.x;
+        transform.translation.y = to.y;
+        if let Some(mut velocity) = velocity {
+            velocity.linvel = Vec2::ZERO;
+        }
+
+        for position in [*from, *to] {
+            spawn_particle_system(&mut commands, position, &particle, &mut rng);
+            se_writer.send(SEEvent::pos(SE::Warp, position));
+        }
+    }
+}
+
+pub struct TeleportPlugin;
+
+impl Plugin for TeleportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CastTeleport>();
+        app.add_event::<TeleportActor>();
+        app.add_systems(
+            FixedUpdate,
+            (read_cast_teleport_event, read_teleport_actor_event)
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
@@ -3,11 +3,13 @@ use super::EntityChildrenAutoDepth;
 use crate::asset::GameAssets;
 use crate::config::GameConfig;
 use crate::constant::*;
-use crate::controller::player::{Equipment, Player};
+use crate::controller::player::Equipment;
 use crate::entity::actor::{Actor, ActorFireState};
 use crate::entity::life::{Life, LifeBeingSprite};
+use crate::entity::status_effect::StatusEffects;
 use crate::hud::life_bar::{spawn_life_bar, LifeBarResource};
 use crate::inventory::Inventory;
+use crate::physics::compare_distance;
 use crate::player_state::PlayerState;
 use crate::states::GameState;
 use crate::wand::Wand;
@@ -21,6 +23,12 @@ pub const WITCH_COLLIDER_RADIUS: f32 = 5.0;
 
 pub const PLAYER_MOVE_FORCE: f32 = 40000.0;
 
+/// 魔法使いのマナの最大値
+/// Actor の mana と同様に、表示上の値の10倍です
+pub const WITCH_MAX_MANA: i32 = 1000;
+
+pub const WITCH_MANA_REGEN: i32 = 2;
+
 #[derive(Default, Component, Reflect)]
 pub struct WitchWandSprite;
 
@@ -64,17 +72,22 @@ pub fn spawn_witch<T: Component>(
             effects: default(),
             actor_group,
             golds,
+            mana: WITCH_MAX_MANA,
+            max_mana: WITCH_MAX_MANA,
+            mana_regen: WITCH_MANA_REGEN,
             wands,
             inventory,
             equipments,
         },
         ActorState::default(),
+        StatusEffects::default(),
         Witch,
         controller,
         Life {
             life,
             max_life,
             amplitude: 0.0,
+            resistance: default(),
         },
         // 足音
         // footsteps.rsで音量を調整
@@ -168,22 +181,28 @@ pub fn spawn_witch<T: Component>(
         }
 
         if life_bar {
-            spawn_life_bar(spawn_children, &res);
+            spawn_life_bar(spawn_children, &assets, &res);
         }
     });
 
     return entity.id();
 }
 
+/// 操作するプレイヤーのいない魔法使いです
+/// 敵として配置されるほか、召喚呪文で味方として呼び出されることもあります
 #[derive(Component)]
 struct EnemyWitchController;
 
+/// 魔法使いが相手を見つけて呪文を唱え始める距離
+const ENEMY_WITCH_ATTACK_RANGE: f32 = 128.0;
+
 pub fn spawn_enemy_witch(
     commands: &mut Commands,
     assets: &Res<GameAssets>,
     life_bar_res: &Res<LifeBarResource>,
     position: Vec2,
-) {
+    actor_group: ActorGroup,
+) -> Entity {
     let player = PlayerState::from_config(&GameConfig::default());
 
     spawn_witch(
@@ -203,26 +222,45 @@ pub fn spawn_enemy_witch(
         player.inventory,
         player.equipments,
         EnemyWitchController,
-        ActorGroup::Enemy,
-    );
+        actor_group,
+    )
 }
 
+/// 範囲内で最も近い、別のグループに所属するアクターを狙って呪文を唱えます
 fn update_enemy_witch_controller(
-    mut query: Query<(&mut Actor, &Transform), With<EnemyWitchController>>,
-    player_query: Query<&Transform, With<Player>>,
+    mut query: Query<(
+        Entity,
+        &mut Actor,
+        &Transform,
+        Option<&EnemyWitchController>,
+    )>,
 ) {
-    for (mut actor, witch_transform) in query.iter_mut() {
-        if let Ok(player_transform) = player_query.get_single() {
-            if player_transform
-                .translation
-                .truncate()
-                .distance(witch_transform.translation.truncate())
-                < 128.0
-            {
-                actor.fire_state = ActorFireState::Fire;
-            } else {
-                actor.fire_state = ActorFireState::Idle;
-            }
+    // 多対多の参照になるので、先に位置を集めておく
+    let actors: Vec<(Entity, ActorGroup, Vec2)> = query
+        .iter()
+        .map(|(e, a, t, _)| (e, a.actor_group, t.translation.truncate()))
+        .collect();
+
+    for (entity, mut actor, witch_transform, controller) in query.iter_mut() {
+        if controller.is_none() {
+            continue;
+        }
+
+        let origin = witch_transform.translation.truncate();
+        let mut targets: Vec<Vec2> = actors
+            .iter()
+            .filter(|(e, g, p)| {
+                *e != entity
+                    && *g != actor.actor_group
+                    && p.distance(origin) < ENEMY_WITCH_ATTACK_RANGE
+            })
+            .map(|(_, _, p)| *p)
+            .collect();
+        targets.sort_by(compare_distance(origin));
+
+        if let Some(nearest) = targets.first() {
+            actor.pointer = *nearest - origin;
+            actor.fire_state = ActorFireState::Fire;
         } else {
             actor.fire_state = ActorFireState::Idle;
         }
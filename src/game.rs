@@ -11,6 +11,8 @@ use crate::enemy::eyeball::EyeballControlPlugin;
 use crate::enemy::huge_slime::HugeSlimePlugin;
 use crate::enemy::slime::SlimeControlPlugin;
 use crate::entity::actor::ActorPlugin;
+use crate::entity::barrier::BarrierPlugin;
+use crate::entity::beam::BeamPlugin;
 use crate::entity::book_shelf::BookshelfPlugin;
 use crate::entity::bullet::BulletPlugin;
 use crate::entity::bullet_particle::BulletParticlePlugin;
@@ -20,11 +22,15 @@ use crate::entity::dropped_item::SpellEntityPlugin;
 use crate::entity::gold::GoldPlugin;
 use crate::entity::impact::ImpactPlugin;
 use crate::entity::life::LifePlugin;
+use crate::entity::lob::LobPlugin;
 use crate::entity::magic_circle::MagicCirclePlugin;
 use crate::entity::rabbit::RabbitPlugin;
 use crate::entity::shop::ShopPlugin;
-use crate::entity::slime_seed::SlimeSeedPlugin;
+use crate::entity::status_effect::StatusEffectPlugin;
 use crate::entity::stone_lantern::StoneLanternPlugin;
+use crate::entity::summon::SummonPlugin;
+use crate::entity::summon_seed::SummonSeedPlugin;
+use crate::entity::teleport::TeleportPlugin;
 use crate::entity::witch::WitchPlugin;
 use crate::entity::EntityPlugin;
 use crate::footsteps::FootStepsPlugin;
@@ -40,8 +46,10 @@ use crate::page::name_input::NameInputPagePlugin;
 use crate::page::setup::SetupPlugin;
 use crate::page::warp::WarpPagePlugin;
 use crate::physics::GamePhysicsPlugin;
+use crate::random::RandomPlugin;
 use crate::se::SECommandPlugin;
 use crate::speech_bubble::SpeechBubblePlugin;
+use crate::spell_registry::SpellRegistryPlugin;
 use crate::states::*;
 use crate::ui::bar::StatusBarPlugin;
 use crate::ui::boss_hitpoint_bar::BossHitpointBarPlugin;
@@ -72,7 +80,10 @@ use bevy_light_2d::plugin::Light2dPlugin;
 use bevy_rapier2d::prelude::*;
 use bevy_simple_text_input::TextInputPlugin;
 use bevy_simple_websocket::WebSocketPlugin;
+use format::LevelFormatPlugin;
 use gameover::GameoverPlugin;
+use terrain::TerrainPlugin;
+use tile_effect::TileEffectPlugin;
 use wall::WallPlugin;
 
 #[cfg(all(not(debug_assertions), not(target_arch = "wasm32")))]
@@ -156,6 +167,9 @@ pub fn run_game() {
         .add_plugins(BossHitpointBarPlugin)
         .add_plugins(LifePlugin)
         .add_plugins(BulletPlugin)
+        .add_plugins(BeamPlugin)
+        .add_plugins(BarrierPlugin)
+        .add_plugins(LobPlugin)
         .add_plugins(BulletParticlePlugin)
         .add_plugins(CameraPlugin)
         .add_plugins(ChestPlugin)
@@ -184,6 +198,7 @@ pub fn run_game() {
         .add_plugins(InventoryPlugin)
         .add_plugins(ItemPanelPlugin)
         .add_plugins(LabelPlugin)
+        .add_plugins(LevelFormatPlugin)
         .add_plugins(LifeBarPlugin)
         .add_plugins(MagicCirclePlugin)
         .add_plugins(MainMenuPlugin)
@@ -196,18 +211,25 @@ pub fn run_game() {
         .add_plugins(PlayerPlugin)
         .add_plugins(PointerPlugin)
         .add_plugins(RabbitPlugin)
+        .add_plugins(RandomPlugin)
         .add_plugins(RemotePlayerPlugin)
         .add_plugins(SetupPlugin)
         .add_plugins(ShopPlugin)
         .add_plugins(SlimeControlPlugin)
-        .add_plugins(SlimeSeedPlugin)
+        .add_plugins(SummonSeedPlugin)
+        .add_plugins(SummonPlugin)
+        .add_plugins(TeleportPlugin)
         .add_plugins(SpeechBubblePlugin)
         .add_plugins(SpellInformationPlugin)
         .add_plugins(SpellEntityPlugin)
         .add_plugins(SpellInWandPlugin)
+        .add_plugins(SpellRegistryPlugin)
         .add_plugins(StatusBarPlugin)
+        .add_plugins(StatusEffectPlugin)
         .add_plugins(StoneLanternPlugin)
         .add_plugins(WallPlugin)
+        .add_plugins(TerrainPlugin)
+        .add_plugins(TileEffectPlugin)
         .add_plugins(WandEditorPlugin)
         .add_plugins(WandListPlugin)
         .add_plugins(WandSpritePlugin)
@@ -224,6 +246,7 @@ pub fn run_game() {
         .add_loading_state(
             LoadingState::new(GameState::Setup)
                 .continue_to_state(INITIAL_STATE)
+                .on_failure_continue_to_state(GameState::LoadError)
                 .load_collection::<GameAssets>(),
         )
         // State Scoped Entities をオンにすることで、
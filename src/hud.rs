@@ -27,6 +27,9 @@ pub struct HUD;
 #[derive(Component)]
 pub struct PlayerLifeBar;
 
+#[derive(Component)]
+pub struct PlayerManaBar;
+
 #[derive(Component)]
 pub struct PlayerGold;
 
@@ -199,6 +202,14 @@ fn spawn_status_bars(parent: &mut ChildBuilder, assets: &Res<GameAssets>) {
                 Color::hsla(110., 0.7, 0.7, 0.9),
             );
 
+            spawn_status_bar(
+                &mut parent,
+                PlayerManaBar,
+                0,
+                0,
+                Color::hsla(220., 0.7, 0.7, 0.9),
+            );
+
             parent
                 .spawn((Node {
                     display: Display::Flex,
@@ -234,16 +245,21 @@ fn spawn_status_bars(parent: &mut ChildBuilder, assets: &Res<GameAssets>) {
 
 fn update_hud(
     player_query: Query<(&Actor, &Life), (With<Player>, Without<Camera2d>)>,
-    mut player_life_query: Query<&mut StatusBar, With<PlayerLifeBar>>,
+    mut player_life_query: Query<&mut StatusBar, (With<PlayerLifeBar>, Without<PlayerManaBar>)>,
+    mut player_mana_query: Query<&mut StatusBar, (With<PlayerManaBar>, Without<PlayerLifeBar>)>,
     mut player_gold_query: Query<&mut Text, (With<PlayerGold>,)>,
 ) {
     if let Ok((actor, actor_life)) = player_query.get_single() {
         let mut player_life = player_life_query.single_mut();
+        let mut player_mana = player_mana_query.single_mut();
         let mut player_gold = player_gold_query.single_mut();
 
         player_life.value = actor_life.life;
         player_life.max_value = actor_life.max_life;
 
+        player_mana.value = actor.mana / 10;
+        player_mana.max_value = actor.max_mana / 10;
+
         player_gold.0 = format!("{}", actor.golds);
     }
 }
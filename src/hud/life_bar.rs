@@ -1,6 +1,9 @@
+use crate::asset::GameAssets;
 use crate::entity::life::Life;
+use crate::entity::status_effect::{StatusEffectType, StatusEffects, STATUS_EFFECT_TYPES};
 use crate::{set::GameSet, states::GameState};
 use bevy::prelude::*;
+use bevy_aseprite_ultra::prelude::*;
 
 const LIFE_BAR_WIDTH: f32 = 16.0;
 
@@ -10,12 +13,25 @@ const LIFE_BAR_Y: f32 = -8.0;
 
 const LIFE_BAR_Z: f32 = 100.0;
 
+/// 状態異常のアイコンのスライスの大きさです
+const STATUS_ICON_SIZE: f32 = 7.0;
+
+/// アイコンは縁取りどうしが重なるように並べます
+const STATUS_ICON_MARGIN: f32 = -1.0;
+
+const STATUS_ICON_Y: f32 = LIFE_BAR_Y + LIFE_BAR_HEIGHT + STATUS_ICON_SIZE * 0.5;
+
 #[derive(Component)]
 pub struct LifeBar;
 
 #[derive(Component)]
 pub struct LifeBarBackground;
 
+/// ライフバーの上に並ぶ、状態異常を表す小さなアイコンです
+/// 状態異常の種類ごとにひとつずつ生成され、作用しているものだけが左から詰めて表示されます
+#[derive(Component)]
+pub struct StatusEffectIcon(StatusEffectType);
+
 #[derive(Resource, Reflect, Clone)]
 pub struct LifeBarResource {
     material_life: Handle<ColorMaterial>,
@@ -38,7 +54,11 @@ fn setup_life_bar(
     });
 }
 
-pub fn spawn_life_bar(child_builder: &mut ChildBuilder, res: &Res<LifeBarResource>) {
+pub fn spawn_life_bar(
+    child_builder: &mut ChildBuilder,
+    assets: &Res<GameAssets>,
+    res: &Res<LifeBarResource>,
+) {
     child_builder.spawn((
         LifeBar,
         Mesh2d::from(res.shape.clone()),
@@ -52,6 +72,17 @@ pub fn spawn_life_bar(child_builder: &mut ChildBuilder, res: &Res<LifeBarResourc
         MeshMaterial2d::from(res.material_background.clone()),
         Transform::from_xyz(0.0, LIFE_BAR_Y, LIFE_BAR_Z),
     ));
+    for effect_type in STATUS_EFFECT_TYPES {
+        child_builder.spawn((
+            StatusEffectIcon(effect_type),
+            AseSpriteSlice {
+                aseprite: assets.atlas.clone(),
+                name: effect_type.icon().into(),
+            },
+            Transform::from_xyz(0.0, STATUS_ICON_Y, LIFE_BAR_Z),
+            Visibility::Hidden,
+        ));
+    }
 }
 
 pub fn update_life_bar(
@@ -89,6 +120,32 @@ pub fn update_life_bar(
     }
 }
 
+fn update_status_effect_icons(
+    mut query: Query<(&Parent, &StatusEffectIcon, &mut Transform, &mut Visibility)>,
+    status_query: Query<&StatusEffects>,
+) {
+    for (parent, icon, mut transform, mut visibility) in query.iter_mut() {
+        let Ok(status) = status_query.get(parent.get()) else {
+            *visibility = Visibility::Hidden;
+            continue;
+        };
+
+        // 作用している状態異常だけを左から順に並べます
+        let active: Vec<&StatusEffectType> = STATUS_EFFECT_TYPES
+            .iter()
+            .filter(|t| status.has(**t))
+            .collect();
+        if let Some(index) = active.iter().position(|t| **t == icon.0) {
+            let step = STATUS_ICON_SIZE + STATUS_ICON_MARGIN;
+            let left = (LIFE_BAR_WIDTH - STATUS_ICON_SIZE) * -0.5;
+            transform.translation.x = left + index as f32 * step;
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
 pub struct LifeBarPlugin;
 
 impl Plugin for LifeBarPlugin {
@@ -97,7 +154,7 @@ impl Plugin for LifeBarPlugin {
         app.add_systems(Startup, setup_life_bar);
         app.add_systems(
             Update,
-            update_life_bar
+            (update_life_bar, update_status_effect_icons)
                 .run_if(in_state(GameState::InGame))
                 .in_set(GameSet),
         );
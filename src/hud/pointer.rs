@@ -71,7 +71,10 @@ pub struct PointerPlugin;
 
 impl Plugin for PointerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(GameState::Setup), setup_pointer);
+        app.add_systems(
+            OnExit(GameState::Setup),
+            setup_pointer.run_if(resource_exists::<GameAssets>),
+        );
 
         app.add_systems(
             Update,
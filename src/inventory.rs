@@ -1,17 +1,51 @@
 use bevy::reflect::Reflect;
 
 use crate::{
-    constant::{MAX_ITEMS_IN_INVENTORY, MAX_ITEMS_IN_INVENTORY_COLUMN, MAX_ITEMS_IN_INVENTORY_ROW},
+    constant::{
+        MAX_ITEMS_IN_INVENTORY, MAX_ITEMS_IN_INVENTORY_COLUMN, MAX_ITEMS_IN_INVENTORY_ROW,
+        MAX_SPELL_TIER,
+    },
     inventory_item::InventoryItemType,
+    wand_props::WandStats,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Reflect)]
 pub struct InventoryItem {
     pub item_type: InventoryItemType,
     pub price: u32,
+
+    /// 呪文の強化段階
+    /// 呪文以外のアイテムでは常に0です
+    pub tier: u32,
+
+    /// 杖の性能
+    /// 生成された杖は性能が個別に異なるため、インベントリに移しても失われないように保持します
+    /// 杖以外のアイテムでは None で、None の杖は種類の基本の性能になります
+    pub wand_stats: Option<WandStats>,
+}
+
+impl InventoryItem {
+    /// 同じ段階の同じ呪文どうしをひとつにまとめ、1段階強化した呪文を返します
+    /// 未払いの呪文の代金が失われないよう、まとめた呪文の価格は両方の合計になります
+    /// 呪文以外のアイテムや、段階が異なるか上限に達している呪文はまとめられません
+    pub fn merge(&self, other: &InventoryItem) -> Option<InventoryItem> {
+        match (self.item_type, other.item_type) {
+            (InventoryItemType::Spell(a), InventoryItemType::Spell(b))
+                if a == b && self.tier == other.tier && self.tier < MAX_SPELL_TIER =>
+            {
+                Some(InventoryItem {
+                    item_type: self.item_type,
+                    price: self.price + other.price,
+                    tier: self.tier + 1,
+                    wand_stats: None,
+                })
+            }
+            _ => None,
+        }
+    }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Reflect)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Reflect)]
 pub struct Inventory(pub [Option<InventoryItem>; MAX_ITEMS_IN_INVENTORY]);
 
 impl Inventory {
@@ -74,6 +108,8 @@ impl Inventory {
         self.insert(InventoryItem {
             item_type,
             price: 0,
+            tier: 0,
+            wand_stats: None,
         })
     }
 
@@ -1,9 +1,12 @@
 use crate::{
     equipment::EquipmentType,
+    inventory::InventoryItem,
     language::{Dict, Languages},
     spell::SpellType,
     spell_props::get_spell_appendix,
+    spell_registry::SpellRegistry,
     wand::WandType,
+    wand_props::get_wand_appendix,
 };
 use bevy::reflect::Reflect;
 
@@ -23,9 +26,9 @@ impl InventoryItemType {
         }
     }
 
-    pub fn get_icon(&self) -> &'static str {
+    pub fn get_icon(&self, registry: &SpellRegistry) -> &'static str {
         match self {
-            InventoryItemType::Spell(spell) => spell.to_props().icon,
+            InventoryItemType::Spell(spell) => spell.to_props(registry).icon,
             InventoryItemType::Wand(wand) => wand.to_props().icon,
             InventoryItemType::Equipment(equipment) => equipment.to_props().icon,
         }
@@ -46,10 +49,13 @@ pub struct InventoryItemProps {
     pub description: Dict,
 }
 
-pub fn inventory_item_to_props(item: InventoryItemType) -> InventoryItemProps {
+pub fn inventory_item_to_props(
+    registry: &SpellRegistry,
+    item: InventoryItemType,
+) -> InventoryItemProps {
     match item {
         InventoryItemType::Spell(spell) => {
-            let props = spell.to_props();
+            let props = spell.to_props(registry);
             InventoryItemProps {
                 icon: props.icon,
                 name: props.name,
@@ -75,10 +81,33 @@ pub fn inventory_item_to_props(item: InventoryItemType) -> InventoryItemProps {
     }
 }
 
-pub fn get_inventory_item_description(item: InventoryItemType, language: Languages) -> String {
-    match item {
+/// アイテムの名前を返します
+/// 強化された呪文の場合は、名前の後ろに強化段階を付け加えます
+pub fn get_inventory_item_name(
+    registry: &SpellRegistry,
+    item: InventoryItem,
+    language: Languages,
+) -> String {
+    let name = inventory_item_to_props(registry, item.item_type)
+        .name
+        .get(language);
+    if 0 < item.tier {
+        format!("{} +{}", name, item.tier)
+    } else {
+        name.to_string()
+    }
+}
+
+/// アイテムの説明を返します
+/// 呪文の場合は強化段階を反映した性能を、杖の場合はその杖の性能を表示します
+pub fn get_inventory_item_description(
+    registry: &SpellRegistry,
+    item: InventoryItem,
+    language: Languages,
+) -> String {
+    match item.item_type {
         InventoryItemType::Spell(spell) => {
-            let props = spell.to_props();
+            let props = spell.to_props(registry);
             let cast = format!(
                 "{}:{}",
                 Dict {
@@ -88,15 +117,34 @@ pub fn get_inventory_item_description(item: InventoryItemType, language: Languag
                 .get(language),
                 props.cast_delay
             );
-            let appendix = get_spell_appendix(props.cast, language);
+            let mana = format!(
+                "{}:{}",
+                Dict {
+                    ja: "マナ消費",
+                    en: "Mana Cost"
+                }
+                .get(language),
+                props.mana_cost as f32 / 10.0
+            );
+            let appendix = get_spell_appendix(&props.cast.upgrade(item.tier), language);
             return format!(
-                "{}\n{}\n{}",
+                "{}\n{}  {}\n{}",
                 props.description.get(language),
                 cast,
+                mana,
                 appendix
             );
         }
-        other => inventory_item_to_props(other)
+        InventoryItemType::Wand(wand) => {
+            let props = wand.to_props();
+            let stats = item.wand_stats.unwrap_or(props.stats);
+            format!(
+                "{}\n{}",
+                props.description.get(language),
+                get_wand_appendix(registry, &stats, props.always_cast, language)
+            )
+        }
+        other => inventory_item_to_props(registry, other)
             .description
             .get(language)
             .to_string(),
@@ -1,4 +1,6 @@
 use serde::*;
+use std::collections::BTreeSet;
+use std::sync::Mutex;
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
 pub enum Languages {
@@ -15,7 +17,7 @@ impl Languages {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Dict {
     pub ja: &'static str,
     pub en: &'static str,
@@ -29,3 +31,17 @@ impl Dict {
         }
     }
 }
+
+/// 定義ファイルから読み込んだ文字列を、&'static str として返します
+/// Dict や呪文の識別子は &'static str を保持するため、読み込んだ文字列はリークさせて使いますが、
+/// 同じ内容の文字列は一度だけリークさせて使い回すため、定義ファイルを何度読み込み直しても増え続けることはありません
+pub fn intern(text: &str) -> &'static str {
+    static INTERNED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut interned = INTERNED.lock().unwrap_or_else(|err| err.into_inner());
+    if let Some(found) = interned.get(text) {
+        return found;
+    }
+    let leaked: &'static str = text.to_string().leak();
+    interned.insert(leaked);
+    leaked
+}
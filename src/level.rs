@@ -1,6 +1,10 @@
 pub mod ceil;
+pub mod format;
+pub mod generator;
 pub mod map;
+pub mod terrain;
 pub mod tile;
+pub mod tile_effect;
 pub mod wall;
 
 use crate::asset::GameAssets;
@@ -18,6 +22,7 @@ use crate::entity::broken_magic_circle::spawn_broken_magic_circle;
 use crate::entity::chest::spawn_chest;
 use crate::entity::chest::ChestType;
 use crate::entity::dropped_item::spawn_dropped_item;
+use crate::entity::dropped_item::spawn_dropped_wand;
 use crate::entity::magic_circle::spawn_magic_circle;
 use crate::entity::magic_circle::MagicCircleDestination;
 use crate::entity::rabbit::spawn_rabbit;
@@ -32,20 +37,29 @@ use crate::inventory::InventoryItem;
 use crate::inventory_item::InventoryItemType;
 use crate::language::Dict;
 use crate::level::ceil::spawn_roof_tiles;
-use crate::level::map::image_to_tilemap;
+use crate::level::format::parse_spell_property;
+use crate::level::format::LevelError;
+use crate::level::generator::{fallback_level, generate_level};
+use crate::level::map::image_to_level_data;
 use crate::level::map::LevelChunk;
+use crate::level::map::LevelEntity;
 use crate::level::tile::*;
 use crate::player_state::PlayerState;
 use crate::random::random_select;
 use crate::random::random_select_mut;
-use crate::spell::SPELL_TYPES;
+use crate::random::GameRng;
+use crate::spell_registry::SpellRegistry;
 use crate::states::GameState;
+use crate::wand_generator::generate_wand;
 use bevy::asset::*;
 use bevy::core::FrameCount;
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
 use map::image_to_spawn_tiles;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::Rng;
+use rand::SeedableRng;
 use uuid::Uuid;
 use wall::spawn_wall_collisions;
 use wall::WallCollider;
@@ -56,6 +70,28 @@ pub enum GameLevel {
     MultiPlayArena,
 }
 
+impl GameLevel {
+    /// 生成されるアイテムの強さの基準になる深さです
+    pub fn depth(&self) -> u32 {
+        match self {
+            GameLevel::Level(level) => (*level).max(0) as u32,
+            GameLevel::MultiPlayArena => 0,
+        }
+    }
+
+    /// 自動生成されるレベルかどうかを返します
+    /// 拠点とボスのレベル、マルチプレイのアリーナは手作業で作られたレベルを使います
+    pub fn is_generated(&self) -> bool {
+        match self {
+            GameLevel::Level(level) => 0 < level % LEVELS && level % LEVELS < LEVELS - 1,
+            GameLevel::MultiPlayArena => false,
+        }
+    }
+}
+
+/// ショップの商品が杖になる確率です
+const SHOP_WAND_CHANCE: f32 = 0.15;
+
 #[derive(Resource, Debug, Clone)]
 pub struct CurrentLevel {
     pub level: Option<GameLevel>,
@@ -64,6 +100,11 @@ pub struct CurrentLevel {
     pub next_state: PlayerState,
 }
 
+/// 手作業で作られたレベルの読み込みに失敗したときのエラーメッセージです
+/// 壊れたレベルのまま遊ばせないようタイトル画面に戻り、そこでこのメッセージを表示します
+#[derive(Resource, Debug, Default)]
+pub struct LevelLoadError(pub Option<String>);
+
 impl Default for CurrentLevel {
     fn default() -> Self {
         CurrentLevel {
@@ -80,10 +121,15 @@ pub fn setup_level(
     mut commands: Commands,
     level_aseprites: Res<Assets<Aseprite>>,
     images: Res<Assets<Image>>,
+    level_chunks: Res<Assets<LevelChunk>>,
     assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
     life_bar_res: Res<LifeBarResource>,
     mut camera: Query<(&mut GameCamera, &mut Transform), With<Camera2d>>,
     mut current: ResMut<CurrentLevel>,
+    mut rng: ResMut<GameRng>,
+    mut load_error: ResMut<LevelLoadError>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
     let level = match current.next_level {
         GameLevel::Level(level) => GameLevel::Level(level % LEVELS),
@@ -92,16 +138,28 @@ pub fn setup_level(
 
     let player = current.next_state.clone();
 
-    let mut chunk = spawn_level(
+    let mut chunk = match spawn_level(
         &mut commands,
         &level_aseprites,
         &images,
+        &level_chunks,
         &assets,
+        &registry,
         &life_bar_res,
+        &mut rng,
         level,
-    );
+    ) {
+        Ok(chunk) => chunk,
+        Err(err) => {
+            error!("failed to load level {:?}: {}", level, err);
+            load_error.0 = Some(format!("Failed to load level {:?}\n\n{}", level, err));
+            next_state.set(GameState::MainMenu);
+            return;
+        }
+    };
 
-    let entry_point = random_select_mut(&mut chunk.entry_points);
+    let entry_point = random_select_mut(&mut rng.level, &mut chunk.entry_points);
+    info!("entry point: {}", entry_point.name);
 
     let player_x = TILE_SIZE * entry_point.x as f32 + TILE_HALF;
     let player_y = -TILE_SIZE * entry_point.y as f32 - TILE_HALF;
@@ -151,13 +209,13 @@ fn select_level_bgm(
     next_level: Res<CurrentLevel>,
     mut next_bgm: ResMut<NextBGM>,
     assets: Res<GameAssets>,
+    mut rng: ResMut<GameRng>,
 ) {
     if next_level.is_changed() {
         info!("select_level_bgm {:?}", next_level.next_level);
         *next_bgm = NextBGM(Some(match next_level.next_level {
             GameLevel::Level(0) => assets.dokutsu.clone(),
             GameLevel::Level(3) => {
-                let mut rng = rand::thread_rng();
                 let mut bgms = vec![
                     assets.deamon.clone(),
                     assets.action.clone(),
@@ -169,11 +227,10 @@ fn select_level_bgm(
                     assets.final_battle.clone(),
                     assets.human_vs_machine.clone(),
                 ];
-                bgms.shuffle(&mut rng);
+                bgms.shuffle(&mut rng.cosmetic);
                 bgms.pop().unwrap()
             }
             _ => {
-                let mut rng = rand::thread_rng();
                 let mut bgms = vec![
                     assets.arechi.clone(),
                     assets.touha.clone(),
@@ -182,7 +239,7 @@ fn select_level_bgm(
                     assets.shiden.clone(),
                     assets.midnight_forest.clone(),
                 ];
-                bgms.shuffle(&mut rng);
+                bgms.shuffle(&mut rng.cosmetic);
                 bgms.pop().unwrap()
             }
         }));
@@ -205,35 +262,78 @@ fn despawn_level(
     }
 }
 
-fn spawn_level(
-    mut commands: &mut Commands,
+/// レベルの地形とエンティティの配置を読み込みます
+/// マルチプレイのアリーナはレベル定義ファイルから、自動生成されないそのほかのレベルは aseprite のスライスから読み込みます
+/// 自動生成に失敗したレベルは固定の配置で代用しますが、手作業で作られたレベルの誤りはそのままエラーになります
+fn load_level_chunk(
     level_aseprites: &Res<Assets<Aseprite>>,
     images: &Res<Assets<Image>>,
+    level_chunks: &Res<Assets<LevelChunk>>,
     assets: &Res<GameAssets>,
-    life_bar_res: &Res<LifeBarResource>,
+    rng: &mut GameRng,
     level: GameLevel,
-) -> LevelChunk {
+) -> Result<LevelChunk, LevelError> {
+    if level.is_generated() {
+        // レベルごとのシードは冒険のシードから決まるため、同じシードで始めれば同じレベルが生成されます
+        let seed = rng.level.gen::<u64>();
+        info!("generating level {:?} with seed {}", level, seed);
+        return Ok(match generate_level(&mut StdRng::seed_from_u64(seed)) {
+            Some(chunk) => chunk,
+            None => {
+                error!("failed to generate level {:?} with seed {}", level, seed);
+                fallback_level()
+            }
+        });
+    }
+
     let level_slice = match level {
         GameLevel::Level(level) => &format!("level{}", level % LEVELS),
-        GameLevel::MultiPlayArena => "multiplay_arena",
+        // レベル定義ファイルは読み込み時に検証済みです
+        GameLevel::MultiPlayArena => {
+            return level_chunks
+                .get(&assets.multiplay_arena)
+                .cloned()
+                .ok_or_else(|| LevelError::MissingAsset("multiplay_arena".to_string()));
+        }
     };
 
-    let level_aseprite = level_aseprites.get(assets.level.id()).unwrap();
-    let level_image = images.get(level_aseprite.atlas_image.id()).unwrap();
-    let slice = level_aseprite.slices.get(level_slice).unwrap();
+    let Some(level_aseprite) = level_aseprites.get(assets.level.id()) else {
+        return Err(LevelError::MissingAsset("level".to_string()));
+    };
+    let Some(level_image) = images.get(level_aseprite.atlas_image.id()) else {
+        return Err(LevelError::MissingAsset("level".to_string()));
+    };
+    let Some(slice) = level_aseprite.slices.get(level_slice) else {
+        return Err(LevelError::MissingSlice(level_slice.to_string()));
+    };
 
     info!(
         "bounds min_x:{} max_x:{} min_y:{} max_y:{}",
         slice.rect.min.x, slice.rect.max.x, slice.rect.min.y, slice.rect.max.y
     );
 
-    let chunk = image_to_tilemap(
+    image_to_level_data(
         &level_image,
         slice.rect.min.x as i32,
         slice.rect.max.x as i32,
         slice.rect.min.y as i32,
         slice.rect.max.y as i32,
-    );
+    )?
+    .to_chunk()
+}
+
+fn spawn_level(
+    mut commands: &mut Commands,
+    level_aseprites: &Res<Assets<Aseprite>>,
+    images: &Res<Assets<Image>>,
+    level_chunks: &Res<Assets<LevelChunk>>,
+    assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
+    life_bar_res: &Res<LifeBarResource>,
+    rng: &mut GameRng,
+    level: GameLevel,
+) -> Result<LevelChunk, LevelError> {
+    let chunk = load_level_chunk(level_aseprites, images, level_chunks, assets, rng, level)?;
 
     let mut empties = image_to_spawn_tiles(&chunk);
 
@@ -241,11 +341,19 @@ fn spawn_level(
 
     spawn_wall_collisions(&mut commands, &chunk);
 
-    spawn_entities(&mut commands, &assets, &life_bar_res, &chunk);
+    spawn_entities(
+        &mut commands,
+        &assets,
+        registry,
+        &life_bar_res,
+        &chunk,
+        rng,
+        level,
+    );
 
     if 30 < empties.len() {
         for _ in 0..10 {
-            let (x, y) = random_select_mut(&mut empties);
+            let (x, y) = random_select_mut(&mut rng.level, &mut empties);
             spawn_slime(
                 &mut commands,
                 &assets,
@@ -262,7 +370,7 @@ fn spawn_level(
         }
 
         for _ in 0..10 {
-            let (x, y) = random_select_mut(&mut empties);
+            let (x, y) = random_select_mut(&mut rng.level, &mut empties);
             spawn_eyeball(
                 &mut commands,
                 &assets,
@@ -271,99 +379,145 @@ fn spawn_level(
                     TILE_SIZE * -y as f32 - TILE_HALF,
                 ),
                 &life_bar_res,
+                3,
+                ActorGroup::Enemy,
             );
         }
 
-        let mut spells = Vec::from(SPELL_TYPES);
+        let spells = registry.spell_types();
         for _ in 0..3 {
-            let (x, y) = random_select_mut(&mut empties);
+            let (x, y) = random_select_mut(&mut rng.level, &mut empties);
+            let spell = *random_select(&mut rng.loot, spells);
             spawn_dropped_item(
                 &mut commands,
                 &assets,
+                registry,
+                rng,
                 Vec2::new(
                     TILE_SIZE * x as f32 + TILE_HALF,
                     TILE_SIZE * -y as f32 - TILE_HALF,
                 ),
                 InventoryItem {
-                    item_type: InventoryItemType::Spell(*random_select(&mut spells)),
+                    item_type: InventoryItemType::Spell(spell),
                     price: 0,
+                    tier: 0,
+                    wand_stats: None,
                 },
             );
         }
     }
 
-    return chunk;
+    Ok(chunk)
 }
 
 fn spawn_world_tilemap(commands: &mut Commands, assets: &Res<GameAssets>, chunk: &LevelChunk) {
     // 床と壁の生成
     for y in chunk.min_y..chunk.max_y as i32 {
         for x in chunk.min_x..chunk.max_x as i32 {
-            match chunk.get_tile(x, y) {
-                Tile::StoneTile => {
-                    commands.spawn((
-                        WorldTile,
-                        Name::new("stone_tile"),
-                        StateScoped(GameState::InGame),
-                        Transform::from_translation(Vec3::new(
-                            x as f32 * TILE_SIZE,
-                            y as f32 * -TILE_SIZE,
-                            FLOOR_LAYER_Z,
-                        )),
-                        AseSpriteSlice {
-                            aseprite: assets.atlas.clone(),
-                            name: "stone tile".into(),
+            spawn_world_tile(commands, assets, chunk, x, y);
+        }
+    }
+}
+
+/// 指定したタイルの床、壁、天井のスプライトを生成します
+/// 地形が変化したときは、変化したタイルとその周囲のタイルについてこの関数を呼び直します
+fn spawn_world_tile(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk: &LevelChunk,
+    x: i32,
+    y: i32,
+) {
+    let tile = chunk.get_tile(x, y);
+    match tile {
+        // 水や溶岩などの床は専用のスプライトがないため、石の床を色付けして表示します
+        Tile::StoneTile | Tile::Water | Tile::Lava | Tile::Ice | Tile::Spikes => {
+            let (name, color) = match tile {
+                Tile::Water => ("water", Color::srgb(0.4, 0.6, 1.0)),
+                Tile::Lava => ("lava", Color::srgb(1.0, 0.35, 0.2)),
+                Tile::Ice => ("ice", Color::srgb(0.7, 0.95, 1.0)),
+                Tile::Spikes => ("spikes", Color::srgb(0.6, 0.6, 0.65)),
+                _ => ("stone_tile", Color::WHITE),
+            };
+            commands.spawn((
+                WorldTile { x, y },
+                Name::new(name),
+                StateScoped(GameState::InGame),
+                Transform::from_translation(Vec3::new(
+                    x as f32 * TILE_SIZE,
+                    y as f32 * -TILE_SIZE,
+                    FLOOR_LAYER_Z,
+                )),
+                Sprite { color, ..default() },
+                AseSpriteSlice {
+                    aseprite: assets.atlas.clone(),
+                    name: "stone tile".into(),
+                },
+            ));
+        }
+        Tile::Wall | Tile::CrackedWall => {
+            let tx = x as f32 * TILE_SIZE;
+            let ty = y as f32 * -TILE_SIZE;
+            let tz = ENTITY_LAYER_Z + (-ty * Z_ORDER_SCALE);
+
+            // 壁
+            // ひび割れた壁は、壊せることがわかるように色を変えて表示します
+            if !chunk.is_wall(x as i32, y as i32 + 1) {
+                commands.spawn((
+                    WorldTile { x, y },
+                    Name::new("wall"),
+                    StateScoped(GameState::InGame),
+                    Transform::from_translation(Vec3::new(tx, ty - TILE_HALF, tz)),
+                    Sprite {
+                        color: match tile {
+                            Tile::CrackedWall => Color::srgb(0.8, 0.6, 0.5),
+                            _ => Color::WHITE,
                         },
-                    ));
-                }
-                Tile::Wall => {
-                    let tx = x as f32 * TILE_SIZE;
-                    let ty = y as f32 * -TILE_SIZE;
-                    let tz = ENTITY_LAYER_Z + (-ty * Z_ORDER_SCALE);
-
-                    // 壁
-                    if !chunk.equals(x as i32, y as i32 + 1, Tile::Wall) {
-                        commands.spawn((
-                            WorldTile,
-                            Name::new("wall"),
-                            StateScoped(GameState::InGame),
-                            Transform::from_translation(Vec3::new(tx, ty - TILE_HALF, tz)),
-                            AseSpriteSlice {
-                                aseprite: assets.atlas.clone(),
-                                name: "stone wall".into(),
-                            },
-                        ));
-                    }
+                        ..default()
+                    },
+                    AseSpriteSlice {
+                        aseprite: assets.atlas.clone(),
+                        name: "stone wall".into(),
+                    },
+                ));
+            }
 
-                    // // 天井
-                    if false
-                        || chunk.is_empty(x - 1, y - 1)
-                        || chunk.is_empty(x + 0, y - 1)
-                        || chunk.is_empty(x + 1, y - 1)
-                        || chunk.is_empty(x - 1, y + 0)
-                        || chunk.is_empty(x + 0, y + 0)
-                        || chunk.is_empty(x + 1, y + 0)
-                        || chunk.is_empty(x - 1, y + 1)
-                        || chunk.is_empty(x + 0, y + 1)
-                        || chunk.is_empty(x + 1, y + 1)
-                    {
-                        spawn_roof_tiles(commands, assets, &chunk, x, y)
-                    }
-                }
-                _ => {}
+            // // 天井
+            if false
+                || chunk.is_empty(x - 1, y - 1)
+                || chunk.is_empty(x + 0, y - 1)
+                || chunk.is_empty(x + 1, y - 1)
+                || chunk.is_empty(x - 1, y + 0)
+                || chunk.is_empty(x + 0, y + 0)
+                || chunk.is_empty(x + 1, y + 0)
+                || chunk.is_empty(x - 1, y + 1)
+                || chunk.is_empty(x + 0, y + 1)
+                || chunk.is_empty(x + 1, y + 1)
+            {
+                spawn_roof_tiles(commands, assets, &chunk, x, y)
             }
         }
+        _ => {}
     }
 }
 
 fn spawn_entities(
     mut commands: &mut Commands,
     assets: &Res<GameAssets>,
+    registry: &SpellRegistry,
     life_bar_resource: &Res<LifeBarResource>,
     chunk: &LevelChunk,
+    rng: &mut GameRng,
+    level: GameLevel,
 ) {
     // エンティティの生成
-    for (entity, x, y) in &chunk.entities {
+    for LevelEntity {
+        entity,
+        x,
+        y,
+        properties,
+    } in &chunk.entities
+    {
         let tx = TILE_SIZE * *x as f32;
         let ty = TILE_SIZE * -*y as f32;
         match entity {
@@ -460,28 +614,73 @@ fn spawn_entities(
                 ));
             }
             GameEntity::Spell => {
-                if 0.5 < rand::random::<f32>() {
-                    let spell = SPELL_TYPES[rand::random::<usize>() % SPELL_TYPES.len()];
-                    let props = spell.to_props();
+                // spell プロパティで呪文が指定されている場合は、無作為に選ばずにその呪文を並べます
+                // 呪文定義ファイルにない呪文が指定されている場合は、警告を出して無作為に選びます
+                let spell = match properties.get("spell") {
+                    Some(value) => {
+                        let spell = parse_spell_property(value).filter(|s| registry.contains(*s));
+                        if spell.is_none() {
+                            warn!("unknown spell {:?} at ({}, {})", value, x, y);
+                        }
+                        spell
+                    }
+                    None => None,
+                };
+                if let Some(spell) = spell {
+                    let props = spell.to_props(registry);
+                    spawn_dropped_item(
+                        &mut commands,
+                        &assets,
+                        registry,
+                        rng,
+                        Vec2::new(tx + TILE_HALF, ty - TILE_HALF),
+                        InventoryItem {
+                            item_type: InventoryItemType::Spell(spell),
+                            price: props.price,
+                            tier: 0,
+                            wand_stats: None,
+                        },
+                    );
+                } else if rng.loot.gen::<f32>() < SHOP_WAND_CHANCE {
+                    let wand = generate_wand(registry, level.depth(), &mut rng.loot);
+                    spawn_dropped_wand(
+                        &mut commands,
+                        &assets,
+                        registry,
+                        rng,
+                        Vec2::new(tx + TILE_HALF, ty - TILE_HALF),
+                        wand,
+                    );
+                } else if 0.5 < rng.loot.gen::<f32>() {
+                    let spell = *random_select(&mut rng.loot, registry.spell_types());
+                    let props = spell.to_props(registry);
                     spawn_dropped_item(
                         &mut commands,
                         &assets,
+                        registry,
+                        rng,
                         Vec2::new(tx + TILE_HALF, ty - TILE_HALF),
                         InventoryItem {
                             item_type: InventoryItemType::Spell(spell),
                             price: props.price,
+                            tier: 0,
+                            wand_stats: None,
                         },
                     );
                 } else {
-                    let equipment = EQUIPMENTS[rand::random::<usize>() % EQUIPMENTS.len()];
+                    let equipment = EQUIPMENTS[rng.loot.gen_range(0..EQUIPMENTS.len())];
                     let props = equipment.to_props();
                     spawn_dropped_item(
                         &mut commands,
                         &assets,
+                        registry,
+                        rng,
                         Vec2::new(tx + TILE_HALF, ty - TILE_HALF),
                         InventoryItem {
                             item_type: InventoryItemType::Equipment(equipment),
                             price: props.price,
+                            tier: 0,
+                            wand_stats: None,
                         },
                     );
                 }
@@ -506,6 +705,7 @@ fn spawn_entities(
                     &assets,
                     life_bar_resource,
                     Vec2::new(tx + TILE_HALF, ty - TILE_HALF),
+                    ActorGroup::Enemy,
                 );
             }
             GameEntity::ShopDoor => {
@@ -556,5 +756,6 @@ impl Plugin for WorldPlugin {
         app.add_systems(OnEnter(GameState::InGame), setup_level);
         app.add_systems(OnEnter(GameState::InGame), select_level_bgm);
         app.init_resource::<CurrentLevel>();
+        app.init_resource::<LevelLoadError>();
     }
 }
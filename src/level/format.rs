@@ -0,0 +1,516 @@
+use crate::entity::GameEntity;
+use crate::level::map::{Biome, EntryPoint, LevelChunk, LevelEntity};
+use crate::level::tile::Tile;
+use crate::spell::SpellType;
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+// タイルのレイヤーで、そのレイヤーではタイルを指定しないことを表す記号
+const EMPTY_SYMBOL: char = ' ';
+
+/// 凡例のひとつの記号が表すタイルです
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct TileDefinition {
+    pub tile: Tile,
+    pub biome: Biome,
+}
+
+/// タイルのレイヤーです
+/// rows のひとつの文字列がひとつの行で、その各文字が凡例の記号です
+/// レイヤーは先頭から順に重ねられ、空白の記号の位置では下のレイヤーのタイルがそのまま使われます
+#[derive(Clone, Debug, Deserialize)]
+pub struct TileLayer {
+    pub name: String,
+    pub rows: Vec<String>,
+}
+
+/// レベルに配置するエンティティです
+/// x と y はタイル座標です
+#[derive(Clone, Debug, Deserialize)]
+pub struct EntityObject {
+    #[serde(rename = "type")]
+    pub entity: GameEntity,
+    pub x: i32,
+    pub y: i32,
+    #[serde(default)]
+    pub properties: HashMap<String, String>,
+}
+
+/// プレイヤーが現れる位置です
+/// 名前はレベルの中で重複してはいけません
+#[derive(Clone, Debug, Deserialize)]
+pub struct EntryPointObject {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// レベル定義ファイル (*.level.json) の内容です
+/// aseprite の画像から読み込むレベルもいったんこの形式に変換され、同じ検証を経て LevelChunk になります
+#[derive(Clone, Debug, Deserialize)]
+pub struct LevelData {
+    /// 左上のタイルのタイル座標
+    #[serde(default)]
+    pub x: i32,
+    #[serde(default)]
+    pub y: i32,
+
+    /// 記号からタイルへの対応です
+    /// 記号はそれぞれ空白以外の一文字です
+    pub legend: HashMap<String, TileDefinition>,
+
+    pub layers: Vec<TileLayer>,
+
+    #[serde(default)]
+    pub entities: Vec<EntityObject>,
+
+    pub entry_points: Vec<EntryPointObject>,
+}
+
+impl LevelData {
+    pub fn from_json(json: &str) -> Result<Self, LevelError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// レイヤーを重ねてタイルを決め、エンティティと入口を配置したレベルを作成します
+    /// 内容に誤りがある場合は、その位置を含むエラーを返します
+    pub fn to_chunk(&self) -> Result<LevelChunk, LevelError> {
+        let chunk = self.build_chunk()?;
+        validate_chunk(&chunk)?;
+        Ok(chunk)
+    }
+
+    /// レイヤーと凡例、入口の名前だけを検証してレベルを作成します
+    /// エンティティと入口の配置は検証しないため、検証ツールはこの結果に対して配置の誤りをすべて列挙します
+    pub fn build_chunk(&self) -> Result<LevelChunk, LevelError> {
+        let mut legend: HashMap<char, TileDefinition> = HashMap::new();
+        for (symbol, definition) in self.legend.iter() {
+            let mut chars = symbol.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c != EMPTY_SYMBOL => {
+                    legend.insert(c, *definition);
+                }
+                _ => return Err(LevelError::InvalidSymbol(symbol.clone())),
+            }
+        }
+
+        let Some(base) = self.layers.first() else {
+            return Err(LevelError::NoLayers);
+        };
+        let height = base.rows.len();
+        let width = base.rows.first().map(|r| r.chars().count()).unwrap_or(0);
+        if width == 0 {
+            return Err(LevelError::NoLayers);
+        }
+
+        let mut cells: Vec<Option<TileDefinition>> = vec![None; width * height];
+        for layer in self.layers.iter() {
+            if layer.rows.len() != height
+                || layer.rows.iter().any(|row| row.chars().count() != width)
+            {
+                return Err(LevelError::LayerSize {
+                    layer: layer.name.clone(),
+                    width,
+                    height,
+                });
+            }
+            for (row, line) in layer.rows.iter().enumerate() {
+                for (column, symbol) in line.chars().enumerate() {
+                    if symbol == EMPTY_SYMBOL {
+                        continue;
+                    }
+                    let Some(definition) = legend.get(&symbol) else {
+                        return Err(LevelError::UnknownSymbol {
+                            layer: layer.name.clone(),
+                            symbol,
+                            x: self.x + column as i32,
+                            y: self.y + row as i32,
+                        });
+                    };
+                    cells[row * width + column] = Some(*definition);
+                }
+            }
+        }
+
+        let mut chunk = LevelChunk::new(
+            self.x,
+            self.x + width as i32,
+            self.y,
+            self.y + height as i32,
+            Tile::Blank,
+            Biome::SafeZone,
+        );
+        for (i, cell) in cells.iter().enumerate() {
+            let x = self.x + (i % width) as i32;
+            let y = self.y + (i / width) as i32;
+            let Some(definition) = cell else {
+                return Err(LevelError::MissingTile { x, y });
+            };
+            chunk.set_tile(x, y, definition.tile);
+            chunk.set_biome(x, y, definition.biome);
+        }
+
+        for object in self.entities.iter() {
+            chunk.entities.push(LevelEntity {
+                entity: object.entity,
+                x: object.x,
+                y: object.y,
+                properties: object.properties.clone(),
+            });
+        }
+
+        let mut names: HashSet<&str> = HashSet::new();
+        for entry_point in self.entry_points.iter() {
+            if !names.insert(&entry_point.name) {
+                return Err(LevelError::DuplicatedEntryPoint(entry_point.name.clone()));
+            }
+            chunk.entry_points.push(EntryPoint {
+                name: entry_point.name.clone(),
+                x: entry_point.x,
+                y: entry_point.y,
+            });
+        }
+
+        Ok(chunk)
+    }
+}
+
+/// レベルのエンティティと入口の配置を検証し、最初に見つかった誤りを返します
+pub fn validate_chunk(chunk: &LevelChunk) -> Result<(), LevelError> {
+    match find_placement_errors(chunk).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// レベルのエンティティと入口の配置の誤りをすべて返します
+/// 入口とエンティティはいずれもレベルの範囲内の床の上になければなりません
+pub fn find_placement_errors(chunk: &LevelChunk) -> Vec<LevelError> {
+    let mut errors = Vec::new();
+
+    if chunk.entry_points.is_empty() {
+        errors.push(LevelError::NoEntryPoint);
+    }
+
+    for entry_point in chunk.entry_points.iter() {
+        if !chunk.is_empty(entry_point.x, entry_point.y) {
+            errors.push(LevelError::InvalidEntryPoint {
+                name: entry_point.name.clone(),
+                x: entry_point.x,
+                y: entry_point.y,
+                message: "entry point must be on a floor tile".to_string(),
+            });
+        } else if chunk.get_tile(entry_point.x, entry_point.y).is_hazard() {
+            errors.push(LevelError::InvalidEntryPoint {
+                name: entry_point.name.clone(),
+                x: entry_point.x,
+                y: entry_point.y,
+                message: "entry point must not be on lava or spikes".to_string(),
+            });
+        }
+    }
+
+    for entity in chunk.entities.iter() {
+        let invalid = |message: String| LevelError::InvalidEntity {
+            entity: entity.entity,
+            x: entity.x,
+            y: entity.y,
+            message,
+        };
+
+        if !chunk.is_empty(entity.x, entity.y) {
+            errors.push(invalid("entity must be on a floor tile".to_string()));
+        }
+
+        for (key, value) in entity.properties.iter() {
+            match (entity.entity, key.as_str()) {
+                (GameEntity::Spell, "spell") => {
+                    if parse_spell_property(value).is_none() {
+                        errors.push(invalid(format!("unknown spell {:?}", value)));
+                    }
+                }
+                _ => errors.push(invalid(format!("unknown property {:?}", key))),
+            }
+        }
+    }
+
+    errors
+}
+
+/// どの入口からも床を歩いて到達できない床の区域を探します
+/// 区域ごとに、最も左上のタイルの座標と区域のタイル数を含むエラーを返します
+/// 掘る呪文で入れる隠し部屋などもありうるため、ゲームでの読み込みでは検査せず、検証ツールでだけ使います
+pub fn find_unreachable_areas(chunk: &LevelChunk) -> Vec<LevelError> {
+    let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    for entry_point in chunk.entry_points.iter() {
+        flood_floor(chunk, (entry_point.x, entry_point.y), &mut visited);
+    }
+
+    let mut errors = Vec::new();
+    for y in chunk.min_y..chunk.max_y {
+        for x in chunk.min_x..chunk.max_x {
+            if chunk.is_empty(x, y) && !visited.contains(&(x, y)) {
+                let size = flood_floor(chunk, (x, y), &mut visited);
+                errors.push(LevelError::UnreachableArea { x, y, size });
+            }
+        }
+    }
+    errors
+}
+
+/// 指定した位置から上下左右に床をたどり、訪れたタイルを visited に加えます
+/// 新たに訪れたタイルの数を返します
+fn flood_floor(chunk: &LevelChunk, from: (i32, i32), visited: &mut HashSet<(i32, i32)>) -> usize {
+    if !chunk.is_empty(from.0, from.1) || !visited.insert(from) {
+        return 0;
+    }
+    let mut count = 1;
+    let mut queue = VecDeque::from([from]);
+    while let Some((x, y)) = queue.pop_front() {
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = (x + dx, y + dy);
+            if chunk.is_empty(next.0, next.1) && visited.insert(next) {
+                count += 1;
+                queue.push_back(next);
+            }
+        }
+    }
+    count
+}
+
+/// Spell エンティティの spell プロパティの値を呪文の種類として読み取ります
+/// 値は呪文定義ファイルの spell_type に記述された識別子で、
+/// 定義ファイルから読み込まれたことのない識別子は None になります
+/// 呪文の定義はレベルとは別に読み込まれるため、現在の定義ファイルに存在するかどうかは配置するときに確認します
+pub fn parse_spell_property(value: &str) -> Option<SpellType> {
+    SpellType::find(value)
+}
+
+#[derive(Debug)]
+pub enum LevelError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    MissingSlice(String),
+    MissingAsset(String),
+    OutOfImage {
+        min_x: i32,
+        max_x: i32,
+        min_y: i32,
+        max_y: i32,
+    },
+    UnknownColor {
+        x: i32,
+        y: i32,
+        color: [u8; 4],
+    },
+    InvalidSymbol(String),
+    NoLayers,
+    LayerSize {
+        layer: String,
+        width: usize,
+        height: usize,
+    },
+    UnknownSymbol {
+        layer: String,
+        symbol: char,
+        x: i32,
+        y: i32,
+    },
+    MissingTile {
+        x: i32,
+        y: i32,
+    },
+    NoEntryPoint,
+    DuplicatedEntryPoint(String),
+    InvalidEntryPoint {
+        name: String,
+        x: i32,
+        y: i32,
+        message: String,
+    },
+    InvalidEntity {
+        entity: GameEntity,
+        x: i32,
+        y: i32,
+        message: String,
+    },
+    UnreachableArea {
+        x: i32,
+        y: i32,
+        size: usize,
+    },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::Io(err) => write!(f, "failed to read level: {}", err),
+            LevelError::Parse(err) => write!(f, "failed to parse level: {}", err),
+            LevelError::MissingSlice(name) => write!(f, "slice {:?} is not found", name),
+            LevelError::MissingAsset(name) => write!(f, "level asset {:?} is not loaded", name),
+            LevelError::OutOfImage {
+                min_x,
+                max_x,
+                min_y,
+                max_y,
+            } => write!(
+                f,
+                "level bounds ({}, {})-({}, {}) exceed the image",
+                min_x, min_y, max_x, max_y
+            ),
+            LevelError::UnknownColor { x, y, color } => write!(
+                f,
+                "unknown color ({}, {}, {}, {}) at ({}, {})",
+                color[0], color[1], color[2], color[3], x, y
+            ),
+            LevelError::InvalidSymbol(symbol) => write!(
+                f,
+                "legend symbol {:?} must be a single non-space character",
+                symbol
+            ),
+            LevelError::NoLayers => write!(f, "level has no tile layers"),
+            LevelError::LayerSize {
+                layer,
+                width,
+                height,
+            } => write!(
+                f,
+                "layer {:?} must be {} columns by {} rows",
+                layer, width, height
+            ),
+            LevelError::UnknownSymbol {
+                layer,
+                symbol,
+                x,
+                y,
+            } => write!(
+                f,
+                "unknown symbol {:?} in layer {:?} at ({}, {})",
+                symbol, layer, x, y
+            ),
+            LevelError::MissingTile { x, y } => {
+                write!(f, "no layer defines the tile at ({}, {})", x, y)
+            }
+            LevelError::NoEntryPoint => write!(f, "level has no entry points"),
+            LevelError::DuplicatedEntryPoint(name) => {
+                write!(f, "entry point {:?} is defined more than once", name)
+            }
+            LevelError::InvalidEntryPoint {
+                name,
+                x,
+                y,
+                message,
+            } => write!(
+                f,
+                "invalid entry point {:?} at ({}, {}): {}",
+                name, x, y, message
+            ),
+            LevelError::InvalidEntity {
+                entity,
+                x,
+                y,
+                message,
+            } => write!(
+                f,
+                "invalid entity {:?} at ({}, {}): {}",
+                entity, x, y, message
+            ),
+            LevelError::UnreachableArea { x, y, size } => write!(
+                f,
+                "floor area of {} tiles at ({}, {}) is not reachable from any entry point",
+                size, x, y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LevelError {}
+
+impl From<std::io::Error> for LevelError {
+    fn from(err: std::io::Error) -> Self {
+        LevelError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for LevelError {
+    fn from(err: serde_json::Error) -> Self {
+        LevelError::Parse(err)
+    }
+}
+
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelChunk;
+    type Settings = ();
+    type Error = LevelError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let json = String::from_utf8_lossy(&bytes);
+        LevelData::from_json(&json)?.to_chunk()
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.json"]
+    }
+}
+
+pub struct LevelFormatPlugin;
+
+impl Plugin for LevelFormatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelChunk>();
+        app.init_asset_loader::<LevelLoader>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk_with_entry(tile: Tile) -> LevelChunk {
+        let mut chunk = LevelChunk::new(0, 8, 0, 8, Tile::StoneTile, Biome::SafeZone);
+        chunk.set_tile(4, 4, tile);
+        chunk.entry_points.push(EntryPoint {
+            name: "entrance".to_string(),
+            x: 4,
+            y: 4,
+        });
+        chunk
+    }
+
+    #[test]
+    fn entry_point_on_hazard_is_rejected() {
+        for tile in [Tile::Lava, Tile::Spikes] {
+            let errors = find_placement_errors(&chunk_with_entry(tile));
+            assert!(
+                matches!(
+                    errors.as_slice(),
+                    [LevelError::InvalidEntryPoint { x: 4, y: 4, .. }]
+                ),
+                "{:?}",
+                tile
+            );
+        }
+    }
+
+    #[test]
+    fn entry_point_on_water_or_ice_is_accepted() {
+        for tile in [Tile::StoneTile, Tile::Water, Tile::Ice] {
+            assert!(find_placement_errors(&chunk_with_entry(tile)).is_empty());
+        }
+    }
+}
@@ -0,0 +1,355 @@
+use crate::entity::GameEntity;
+use crate::level::map::{Biome, EntryPoint, LevelChunk, LevelEntity};
+use crate::level::tile::Tile;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+
+// 生成されるレベルの幅と高さのタイル数
+const GENERATED_LEVEL_SIZE: i32 = 64;
+
+// 部屋を配置しようとする回数
+// 既存の部屋と重なる場合は配置されないため、実際の部屋の数はこれより少なくなります
+const ROOM_ATTEMPTS: usize = 40;
+
+const MAX_ROOMS: usize = 10;
+
+const MIN_ROOM_SIZE: i32 = 6;
+
+const MAX_ROOM_SIZE: i32 = 12;
+
+// 通路の幅のタイル数
+// 魔法使いが壁に引っかからずに通れるよう、2タイル以上にしています
+const CORRIDOR_WIDTH: i32 = 2;
+
+// 部屋に宝箱が置かれる確率
+const CHEST_CHANCE: f64 = 0.4;
+
+// 接続性の検査に失敗した場合に生成をやり直す回数の上限
+const MAX_GENERATION_ATTEMPTS: usize = 16;
+
+/// 生成されるレベルの部屋です
+/// x, y は左上のタイル座標で、w, h は床の幅と高さのタイル数です
+#[derive(Clone, Copy, Debug)]
+struct Room {
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+impl Room {
+    fn center(&self) -> (i32, i32) {
+        (self.x + self.w / 2, self.y + self.h / 2)
+    }
+
+    /// 部屋どうしの間に少なくとも1タイルの壁が残るよう、余白を含めて重なりを判定します
+    fn intersects(&self, other: &Room) -> bool {
+        self.x - 1 <= other.x + other.w
+            && other.x - 1 <= self.x + self.w
+            && self.y - 1 <= other.y + other.h
+            && other.y - 1 <= self.y + self.h
+    }
+}
+
+/// 乱数生成器をもとに、部屋と通路からなるレベルを生成します
+/// 同じシードで初期化された乱数生成器からは、常に同じレベルが生成されます
+/// 最初の部屋はモンスターの現れない安全地帯で入口が置かれ、入口から最も遠い部屋に次のレベルへの魔法陣が置かれます
+/// 生成したレベルは入口から魔法陣まで歩いて到達できることを検査し、到達できない場合は生成をやり直します
+/// やり直しても到達できるレベルが生成できなかった場合は None を返します
+pub fn generate_level<R: Rng>(rng: &mut R) -> Option<LevelChunk> {
+    for _ in 0..MAX_GENERATION_ATTEMPTS {
+        if let Some(chunk) = try_generate_level(rng) {
+            return Some(chunk);
+        }
+        warn!("generated level is not connected, retrying");
+    }
+    None
+}
+
+/// 自動生成に失敗した場合に代わりに使う、固定の配置のレベルです
+/// 入口のある部屋と魔法陣のある部屋を、まっすぐな通路でつないだだけの構成です
+pub fn fallback_level() -> LevelChunk {
+    let mut chunk = LevelChunk::new(
+        0,
+        GENERATED_LEVEL_SIZE,
+        0,
+        GENERATED_LEVEL_SIZE,
+        Tile::Wall,
+        Biome::SafeZone,
+    );
+
+    let start = Room {
+        x: 4,
+        y: 26,
+        w: 10,
+        h: 10,
+    };
+    let goal = Room {
+        x: GENERATED_LEVEL_SIZE - 14,
+        y: 26,
+        w: 10,
+        h: 10,
+    };
+    carve_room(&mut chunk, &start);
+    carve_room(&mut chunk, &goal);
+    dig_corridor(&mut chunk, true, start.center(), goal.center());
+    place_entrance(&mut chunk, &start);
+    place_exit(&mut chunk, goal.center());
+    chunk
+}
+
+fn try_generate_level<R: Rng>(rng: &mut R) -> Option<LevelChunk> {
+    let mut chunk = LevelChunk::new(
+        0,
+        GENERATED_LEVEL_SIZE,
+        0,
+        GENERATED_LEVEL_SIZE,
+        Tile::Wall,
+        Biome::SafeZone,
+    );
+
+    let rooms = place_rooms(rng);
+    if rooms.len() < 2 {
+        return None;
+    }
+
+    for room in rooms.iter() {
+        carve_room(&mut chunk, room);
+    }
+
+    // 部屋を配置した順につないでいくことで、すべての部屋がひと続きになります
+    for pair in rooms.windows(2) {
+        let horizontal_first = rng.gen_bool(0.5);
+        dig_corridor(
+            &mut chunk,
+            horizontal_first,
+            pair[0].center(),
+            pair[1].center(),
+        );
+    }
+
+    let entry = place_entrance(&mut chunk, &rooms[0]);
+
+    // 入口から歩いて最も遠い部屋を出口にします
+    let distances = walking_distances(&chunk, entry);
+    let exit_room = rooms[1..]
+        .iter()
+        .max_by_key(|room| distances.get(&room.center()).copied().unwrap_or(0))
+        .copied()?;
+    let exit = exit_room.center();
+    place_exit(&mut chunk, exit);
+
+    // 宝箱は通路の出入り口をふさがないよう部屋の縁と中央を避けて、入口と出口以外の部屋に置きます
+    for room in rooms[1..].iter() {
+        if room.center() == exit || !rng.gen_bool(CHEST_CHANCE) {
+            continue;
+        }
+        let x = rng.gen_range(room.x + 1..room.x + room.w - 1);
+        let y = rng.gen_range(room.y + 1..room.y + room.h - 1);
+        if (x, y) == room.center() {
+            continue;
+        }
+        chunk.set_biome(x, y, Biome::SafeZone);
+        push_entity(&mut chunk, GameEntity::Chest, x, y);
+    }
+
+    if !is_connected(&chunk, entry, exit) {
+        return None;
+    }
+
+    Some(chunk)
+}
+
+fn carve_room(chunk: &mut LevelChunk, room: &Room) {
+    for y in room.y..room.y + room.h {
+        for x in room.x..room.x + room.w {
+            chunk.set_tile(x, y, Tile::StoneTile);
+            chunk.set_biome(x, y, Biome::Dungeon);
+        }
+    }
+}
+
+/// 部屋の中央に入口を置きます
+/// 入口のある部屋は安全地帯です
+fn place_entrance(chunk: &mut LevelChunk, start: &Room) -> (i32, i32) {
+    for y in start.y..start.y + start.h {
+        for x in start.x..start.x + start.w {
+            chunk.set_biome(x, y, Biome::SafeZone);
+        }
+    }
+
+    let entry = start.center();
+    chunk.entry_points.push(EntryPoint {
+        name: "entrance".to_string(),
+        x: entry.0,
+        y: entry.1,
+    });
+    push_entity(chunk, GameEntity::BrokenMagicCircle, entry.0, entry.1);
+    push_entity(chunk, GameEntity::StoneLantern, start.x, start.y);
+    entry
+}
+
+/// 次のレベルへの魔法陣を置きます
+fn place_exit(chunk: &mut LevelChunk, exit: (i32, i32)) {
+    chunk.set_biome(exit.0, exit.1, Biome::SafeZone);
+    push_entity(chunk, GameEntity::MagicCircle, exit.0, exit.1);
+}
+
+fn push_entity(chunk: &mut LevelChunk, entity: GameEntity, x: i32, y: i32) {
+    chunk.entities.push(LevelEntity {
+        entity,
+        x,
+        y,
+        properties: HashMap::new(),
+    });
+}
+
+/// 重ならないように部屋を配置します
+/// レベルの外周の1タイルは必ず壁として残ります
+fn place_rooms<R: Rng>(rng: &mut R) -> Vec<Room> {
+    let mut rooms: Vec<Room> = Vec::new();
+    for _ in 0..ROOM_ATTEMPTS {
+        if MAX_ROOMS <= rooms.len() {
+            break;
+        }
+        let w = rng.gen_range(MIN_ROOM_SIZE..=MAX_ROOM_SIZE);
+        let h = rng.gen_range(MIN_ROOM_SIZE..=MAX_ROOM_SIZE);
+        let room = Room {
+            x: rng.gen_range(1..GENERATED_LEVEL_SIZE - w - 1),
+            y: rng.gen_range(1..GENERATED_LEVEL_SIZE - h - 1),
+            w,
+            h,
+        };
+        if rooms.iter().all(|r| !r.intersects(&room)) {
+            rooms.push(room);
+        }
+    }
+    rooms
+}
+
+/// ふたつの位置を L 字型の通路でつなぎます
+/// horizontal_first が有効な場合は横から、そうでなければ縦から掘ります
+/// 通路は部屋と同じくモンスターの現れる区域になります
+fn dig_corridor(chunk: &mut LevelChunk, horizontal_first: bool, from: (i32, i32), to: (i32, i32)) {
+    let corner = if horizontal_first {
+        (to.0, from.1)
+    } else {
+        (from.0, to.1)
+    };
+    for (a, b) in [(from, corner), (corner, to)] {
+        for y in a.1.min(b.1)..=a.1.max(b.1) + CORRIDOR_WIDTH - 1 {
+            for x in a.0.min(b.0)..=a.0.max(b.0) + CORRIDOR_WIDTH - 1 {
+                // 外周の壁は残します
+                if x <= chunk.min_x
+                    || chunk.max_x - 1 <= x
+                    || y <= chunk.min_y
+                    || chunk.max_y - 1 <= y
+                {
+                    continue;
+                }
+                if chunk.get_tile(x, y) == Tile::Wall {
+                    chunk.set_tile(x, y, Tile::StoneTile);
+                    chunk.set_biome(x, y, Biome::Dungeon);
+                }
+            }
+        }
+    }
+}
+
+/// 指定した位置から、床のタイルを上下左右に歩いて到達できる各タイルまでの歩数を返します
+/// 到達できないタイルは含まれません
+fn walking_distances(chunk: &LevelChunk, from: (i32, i32)) -> HashMap<(i32, i32), u32> {
+    let mut distances = HashMap::new();
+    if chunk.get_tile(from.0, from.1) != Tile::StoneTile {
+        return distances;
+    }
+    let mut queue = VecDeque::new();
+    distances.insert(from, 0);
+    queue.push_back(from);
+    while let Some((x, y)) = queue.pop_front() {
+        let distance = distances[&(x, y)];
+        for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+            let next = (x + dx, y + dy);
+            if chunk.get_tile(next.0, next.1) == Tile::StoneTile && !distances.contains_key(&next) {
+                distances.insert(next, distance + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+/// 指定したふたつの位置が、床のタイルを歩いてつながっているかどうかを返します
+pub fn is_connected(chunk: &LevelChunk, from: (i32, i32), to: (i32, i32)) -> bool {
+    walking_distances(chunk, from).contains_key(&to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn entry_and_exit(chunk: &LevelChunk) -> ((i32, i32), (i32, i32)) {
+        let entry = &chunk.entry_points[0];
+        let exit = chunk
+            .entities
+            .iter()
+            .find(|e| e.entity == GameEntity::MagicCircle)
+            .unwrap();
+        ((entry.x, entry.y), (exit.x, exit.y))
+    }
+
+    #[test]
+    fn generated_levels_are_connected() {
+        for seed in 0..32 {
+            let chunk = generate_level(&mut StdRng::seed_from_u64(seed)).unwrap();
+            let (entry, exit) = entry_and_exit(&chunk);
+            assert!(is_connected(&chunk, entry, exit));
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_level() {
+        let tiles = |chunk: &LevelChunk| -> Vec<Tile> {
+            (chunk.min_y..chunk.max_y)
+                .flat_map(|y| (chunk.min_x..chunk.max_x).map(move |x| (x, y)))
+                .map(|(x, y)| chunk.get_tile(x, y))
+                .collect()
+        };
+        let entities = |chunk: &LevelChunk| -> Vec<(GameEntity, i32, i32)> {
+            chunk
+                .entities
+                .iter()
+                .map(|e| (e.entity, e.x, e.y))
+                .collect()
+        };
+        let entry_points = |chunk: &LevelChunk| -> Vec<(String, i32, i32)> {
+            chunk
+                .entry_points
+                .iter()
+                .map(|e| (e.name.clone(), e.x, e.y))
+                .collect()
+        };
+
+        for seed in 0..8 {
+            let a = generate_level(&mut StdRng::seed_from_u64(seed)).unwrap();
+            let b = generate_level(&mut StdRng::seed_from_u64(seed)).unwrap();
+            assert_eq!(
+                (a.min_x, a.max_x, a.min_y, a.max_y),
+                (b.min_x, b.max_x, b.min_y, b.max_y)
+            );
+            assert_eq!(tiles(&a), tiles(&b));
+            assert_eq!(entities(&a), entities(&b));
+            assert_eq!(entry_points(&a), entry_points(&b));
+        }
+    }
+
+    #[test]
+    fn fallback_level_is_connected() {
+        let chunk = fallback_level();
+        let (entry, exit) = entry_and_exit(&chunk);
+        assert!(is_connected(&chunk, entry, exit));
+    }
+}
@@ -1,9 +1,14 @@
+use crate::level::format::{
+    EntityObject, EntryPointObject, LevelData, LevelError, TileDefinition, TileLayer,
+};
 use crate::{entity::GameEntity, level::tile::Tile};
 use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
 
 use super::TILE_SIZE;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Deserialize)]
 pub enum Biome {
     /// モンスターがスポーンしないエリア
     SafeZone,
@@ -18,18 +23,61 @@ struct LevelTileMapile {
     biome: Biome,
 }
 
-#[derive(Clone, Resource, Debug)]
+/// レベルに配置されたエンティティです
+#[derive(Clone, Debug)]
+pub struct LevelEntity {
+    pub entity: GameEntity,
+    pub x: i32,
+    pub y: i32,
+
+    /// レベル定義ファイルでエンティティに設定された追加の値です
+    /// 使える値はエンティティの種類ごとに決まっていて、読み込み時に検証されます
+    pub properties: HashMap<String, String>,
+}
+
+/// プレイヤーが現れる位置です
+#[derive(Clone, Debug)]
+pub struct EntryPoint {
+    pub name: String,
+    pub x: i32,
+    pub y: i32,
+}
+
+/// レベル定義ファイル (*.level.json) から読み込まれたレベルは、検証を経てこの形でアセットとして登録されます
+#[derive(Clone, Resource, Debug, Asset, TypePath)]
 pub struct LevelChunk {
     tiles: Vec<LevelTileMapile>,
     pub min_x: i32,
     pub min_y: i32,
     pub max_x: i32,
     pub max_y: i32,
-    pub entities: Vec<(GameEntity, i32, i32)>,
-    pub entry_points: Vec<Vec2>,
+    pub entities: Vec<LevelEntity>,
+    pub entry_points: Vec<EntryPoint>,
 }
 
 impl LevelChunk {
+    /// すべてのタイルが tile と biome で埋められたレベルを作成します
+    /// 自動生成されるレベルは、壁で埋めたレベルから部屋や通路を掘り出して作られます
+    pub fn new(
+        min_x: i32,
+        max_x: i32,
+        min_y: i32,
+        max_y: i32,
+        tile: Tile,
+        biome: Biome,
+    ) -> LevelChunk {
+        let count = ((max_x - min_x) * (max_y - min_y)) as usize;
+        LevelChunk {
+            tiles: vec![LevelTileMapile { tile, biome }; count],
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+            entities: Vec::new(),
+            entry_points: Vec::new(),
+        }
+    }
+
     pub fn get_tile(&self, x: i32, y: i32) -> Tile {
         if x < self.min_x || x >= self.max_x || y < self.min_y || y >= self.max_y {
             return Tile::Blank;
@@ -40,8 +88,7 @@ impl LevelChunk {
     }
 
     pub fn get_tile_by_coords(&self, p: Vec2) -> Tile {
-        let x = (p.x / TILE_SIZE as f32).floor() as i32;
-        let y = (-p.y / TILE_SIZE as f32).floor() as i32;
+        let (x, y) = coords_to_tile(p);
         self.get_tile(x, y)
     }
 
@@ -54,18 +101,17 @@ impl LevelChunk {
         return self.tiles[i].biome;
     }
 
-    /// 指定した位置のタイルが、指定したタイルと同じ種類かどうかを返します
+    /// 指定した位置のタイルが壁かどうかを返します
     /// 範囲外を指定した場合は、trueを返します
-    pub fn equals(&self, x: i32, y: i32, tile: Tile) -> bool {
+    pub fn is_wall(&self, x: i32, y: i32) -> bool {
         if x < self.min_x || x >= self.max_x || y < self.min_y || y >= self.max_y {
             return true;
         }
         let w = self.max_x - self.min_x;
         let i = ((y - self.min_y) * w + (x - self.min_x)) as usize;
-        return self.tiles[i].tile == tile;
+        return self.tiles[i].tile.is_wall();
     }
 
-    #[allow(dead_code)]
     pub fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
         if x < self.min_x || x >= self.max_x || y < self.min_y || y >= self.max_y {
             return;
@@ -75,174 +121,244 @@ impl LevelChunk {
         self.tiles[i].tile = tile;
     }
 
+    pub fn set_biome(&mut self, x: i32, y: i32, biome: Biome) {
+        if x < self.min_x || x >= self.max_x || y < self.min_y || y >= self.max_y {
+            return;
+        }
+        let w = self.max_x - self.min_x;
+        let i = ((y - self.min_y) * w + (x - self.min_x)) as usize;
+        self.tiles[i].biome = biome;
+    }
+
     pub fn is_empty(&self, x: i32, y: i32) -> bool {
-        self.get_tile(x, y) == Tile::StoneTile
+        self.get_tile(x, y).is_floor()
     }
 }
 
-pub fn image_to_tilemap(
+/// ピクセル座標を、その位置にあるタイルのタイル座標に変換します
+pub fn coords_to_tile(p: Vec2) -> (i32, i32) {
+    let x = (p.x / TILE_SIZE as f32).floor() as i32;
+    let y = (-p.y / TILE_SIZE as f32).floor() as i32;
+    (x, y)
+}
+
+// aseprite の画像から変換したレベルの凡例の記号
+const IMAGE_DUNGEON_FLOOR: char = '.';
+const IMAGE_SAFE_FLOOR: char = ',';
+const IMAGE_WALL: char = '#';
+const IMAGE_WATER: char = '~';
+const IMAGE_LAVA: char = '^';
+const IMAGE_ICE: char = '_';
+const IMAGE_SPIKES: char = 'x';
+const IMAGE_CRACKED_WALL: char = '%';
+
+/// aseprite の画像の指定した範囲を、レベル定義ファイルと同じ形式に変換します
+/// 変換したレベルは LevelData::to_chunk で、定義ファイルのレベルと同じ検証を経て LevelChunk になります
+pub fn image_to_level_data(
     level_image: &Image,
     min_x: i32,
     max_x: i32,
     min_y: i32,
     max_y: i32,
-) -> LevelChunk {
-    let texture_width = level_image.width();
-    let mut tiles: Vec<LevelTileMapile> = Vec::new();
-    let mut entities = Vec::new();
-    let mut entry_points = Vec::<Vec2>::new();
+) -> Result<LevelData, LevelError> {
+    rgba_to_level_data(
+        &level_image.data,
+        level_image.width(),
+        level_image.height(),
+        min_x,
+        max_x,
+        min_y,
+        max_y,
+    )
+}
+
+/// RGBA8 のピクセル列の指定した範囲を、レベル定義ファイルと同じ形式に変換します
+/// 各ピクセルの色がひとつのタイルに対応し、エンティティの色のピクセルは安全地帯の床として扱われます
+/// 知らない色のピクセルがある場合は、最初に見つかったピクセルの座標を含むエラーを返します
+pub fn rgba_to_level_data(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    min_x: i32,
+    max_x: i32,
+    min_y: i32,
+    max_y: i32,
+) -> Result<LevelData, LevelError> {
+    let (level, errors) =
+        rgba_to_level_data_with_errors(data, width, height, min_x, max_x, min_y, max_y)?;
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(level),
+    }
+}
+
+/// rgba_to_level_data と同様に変換しますが、知らない色のピクセルを壁として変換を続け、
+/// 変換したレベルとともに知らない色のピクセルごとのエラーをすべて返します
+/// ゲームの外で aseprite ファイルを読み込むレベルの検証ツールで、誤りをまとめて報告するために使われます
+pub fn rgba_to_level_data_with_errors(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    min_x: i32,
+    max_x: i32,
+    min_y: i32,
+    max_y: i32,
+) -> Result<(LevelData, Vec<LevelError>), LevelError> {
+    let texture_width = width as i32;
+    let texture_height = height as i32;
+    if min_x < 0
+        || min_y < 0
+        || texture_width < max_x
+        || texture_height < max_y
+        || data.len() < 4 * (width * height) as usize
+    {
+        return Err(LevelError::OutOfImage {
+            min_x,
+            max_x,
+            min_y,
+            max_y,
+        });
+    }
+
+    let mut errors: Vec<LevelError> = Vec::new();
+    let mut rows: Vec<String> = Vec::new();
+    let mut entities: Vec<EntityObject> = Vec::new();
+    let mut entry_points: Vec<EntryPointObject> = Vec::new();
     for y in min_y..max_y {
+        let mut row = String::new();
         for x in min_x..max_x {
-            let i = 4 * (y * texture_width as i32 + x) as usize;
-            let r = level_image.data[i + 0];
-            let g = level_image.data[i + 1];
-            let b = level_image.data[i + 2];
-            let a = level_image.data[i + 3];
-
-            match (r, g, b, a) {
-                (203, 219, 252, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::Dungeon,
-                    });
-                }
-                (234, 255, 214, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                }
-                (82, 75, 36, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::Wall,
-                        biome: Biome::SafeZone,
-                    });
-                }
-                (118, 66, 138, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::BookShelf, x, y));
-                }
-                (251, 242, 54, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Chest, x, y));
-                }
-                (48, 96, 130, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::MagicCircle, x, y));
-                }
-                (47, 96, 130, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::MultiPlayArenaMagicCircle, x, y));
-                }
-                (56, 111, 161, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::MagicCircleHome, x, y));
-                }
+            let i = 4 * (y * texture_width + x) as usize;
+            let r = data[i + 0];
+            let g = data[i + 1];
+            let b = data[i + 2];
+            let a = data[i + 3];
+
+            let (symbol, entity) = match (r, g, b, a) {
+                (203, 219, 252, 255) => (IMAGE_DUNGEON_FLOOR, None),
+                (234, 255, 214, 255) => (IMAGE_SAFE_FLOOR, None),
+                (82, 75, 36, 255) => (IMAGE_WALL, None),
+                (99, 155, 255, 255) => (IMAGE_WATER, None),
+                (172, 50, 50, 255) => (IMAGE_LAVA, None),
+                (95, 205, 228, 255) => (IMAGE_ICE, None),
+                (155, 173, 183, 255) => (IMAGE_SPIKES, None),
+                (143, 86, 59, 255) => (IMAGE_CRACKED_WALL, None),
+                (118, 66, 138, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::BookShelf)),
+                (251, 242, 54, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Chest)),
+                (48, 96, 130, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::MagicCircle)),
+                (47, 96, 130, 255) => (
+                    IMAGE_SAFE_FLOOR,
+                    Some(GameEntity::MultiPlayArenaMagicCircle),
+                ),
+                (56, 111, 161, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::MagicCircleHome)),
                 (255, 0, 0, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entry_points.push(Vec2::new(x as f32, y as f32));
-                    entities.push((GameEntity::BrokenMagicCircle, x, y));
-                }
-                (255, 0, 255, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
+                    entry_points.push(EntryPointObject {
+                        name: format!("entry{}", entry_points.len()),
+                        x,
+                        y,
                     });
-                    entities.push((GameEntity::Usage, x, y));
-                }
-                (254, 0, 255, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Routes, x, y));
-                }
-                (223, 113, 38, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::StoneLantern, x, y));
-                }
-                (0, 222, 255, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Spell, x, y));
-                }
-                (102, 57, 49, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Crate, x, y));
-                }
-                (184, 0, 255, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::HugeSlime, x, y));
-                }
-                (255, 243, 0, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Rabbit, x, y));
-                }
-                (182, 0, 255, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::Witch, x, y));
-                }
-                (197, 255, 142, 255) => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::StoneTile,
-                        biome: Biome::SafeZone,
-                    });
-                    entities.push((GameEntity::ShopDoor, x, y));
+                    (IMAGE_SAFE_FLOOR, Some(GameEntity::BrokenMagicCircle))
                 }
+                (255, 0, 255, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Usage)),
+                (254, 0, 255, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Routes)),
+                (223, 113, 38, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::StoneLantern)),
+                (0, 222, 255, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Spell)),
+                (102, 57, 49, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Crate)),
+                (184, 0, 255, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::HugeSlime)),
+                (255, 243, 0, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Rabbit)),
+                (182, 0, 255, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::Witch)),
+                (197, 255, 142, 255) => (IMAGE_SAFE_FLOOR, Some(GameEntity::ShopDoor)),
                 _ => {
-                    tiles.push(LevelTileMapile {
-                        tile: Tile::Blank,
-                        biome: Biome::SafeZone,
+                    errors.push(LevelError::UnknownColor {
+                        x,
+                        y,
+                        color: [r, g, b, a],
                     });
-                    panic!("Unknown color: ({}, {}, {}, {})", r, g, b, a);
+                    (IMAGE_WALL, None)
                 }
+            };
+
+            row.push(symbol);
+            if let Some(entity) = entity {
+                entities.push(EntityObject {
+                    entity,
+                    x,
+                    y,
+                    properties: HashMap::new(),
+                });
             }
         }
+        rows.push(row);
     }
-    return LevelChunk {
-        tiles,
-        min_x,
-        max_x,
-        min_y,
-        max_y,
+
+    let level = LevelData {
+        x: min_x,
+        y: min_y,
+        legend: HashMap::from([
+            (
+                IMAGE_DUNGEON_FLOOR.to_string(),
+                TileDefinition {
+                    tile: Tile::StoneTile,
+                    biome: Biome::Dungeon,
+                },
+            ),
+            (
+                IMAGE_SAFE_FLOOR.to_string(),
+                TileDefinition {
+                    tile: Tile::StoneTile,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_WALL.to_string(),
+                TileDefinition {
+                    tile: Tile::Wall,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_WATER.to_string(),
+                TileDefinition {
+                    tile: Tile::Water,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_LAVA.to_string(),
+                TileDefinition {
+                    tile: Tile::Lava,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_ICE.to_string(),
+                TileDefinition {
+                    tile: Tile::Ice,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_SPIKES.to_string(),
+                TileDefinition {
+                    tile: Tile::Spikes,
+                    biome: Biome::SafeZone,
+                },
+            ),
+            (
+                IMAGE_CRACKED_WALL.to_string(),
+                TileDefinition {
+                    tile: Tile::CrackedWall,
+                    biome: Biome::SafeZone,
+                },
+            ),
+        ]),
+        layers: vec![TileLayer {
+            name: "terrain".to_string(),
+            rows,
+        }],
         entities,
         entry_points,
     };
+    Ok((level, errors))
 }
 
 pub fn image_to_spawn_tiles(tilemap: &LevelChunk) -> Vec<(i32, i32)> {
@@ -259,3 +375,43 @@ pub fn image_to_spawn_tiles(tilemap: &LevelChunk) -> Vec<(i32, i32)> {
     }
     tiles
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNKNOWN: [u8; 4] = [1, 2, 3, 255];
+    const DUNGEON_FLOOR: [u8; 4] = [203, 219, 252, 255];
+
+    fn pixels() -> Vec<u8> {
+        [UNKNOWN, DUNGEON_FLOOR, UNKNOWN].concat()
+    }
+
+    #[test]
+    fn every_unknown_color_is_reported() {
+        let (level, errors) = rgba_to_level_data_with_errors(&pixels(), 3, 1, 0, 3, 0, 1).unwrap();
+
+        let positions: Vec<(i32, i32)> = errors
+            .iter()
+            .map(|err| match err {
+                LevelError::UnknownColor { x, y, .. } => (*x, *y),
+                _ => panic!("unexpected error {:?}", err),
+            })
+            .collect();
+        assert_eq!(positions, vec![(0, 0), (2, 0)]);
+
+        // 知らない色のピクセルは壁として変換されます
+        let row: String = [IMAGE_WALL, IMAGE_DUNGEON_FLOOR, IMAGE_WALL]
+            .iter()
+            .collect();
+        assert_eq!(level.layers[0].rows, vec![row]);
+    }
+
+    #[test]
+    fn first_unknown_color_fails_conversion() {
+        assert!(matches!(
+            rgba_to_level_data(&pixels(), 3, 1, 0, 3, 0, 1),
+            Err(LevelError::UnknownColor { x: 0, y: 0, .. })
+        ));
+    }
+}
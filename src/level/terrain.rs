@@ -0,0 +1,288 @@
+use crate::asset::GameAssets;
+use crate::constant::*;
+use crate::entity::bullet_particle::{spawn_particle_system, BulletParticleResource};
+use crate::level::map::{coords_to_tile, LevelChunk};
+use crate::level::spawn_world_tile;
+use crate::level::tile::{Tile, WorldTile};
+use crate::level::wall::{merge_wall_tiles, spawn_wall_collider, WallCollider};
+use crate::level::CurrentLevel;
+use crate::random::GameRng;
+use crate::se::{SEEvent, SE};
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use std::collections::HashSet;
+
+/// 地形を変化させるイベントです
+/// position にあるタイルを tile に置き換えます
+/// 壁を掘る場合は壁のタイルだけが、壁を立てる場合は床のタイルだけが置き換えられます
+/// レベルの外へ出られないよう、レベルの外周のタイルは変化しません
+/// 弾丸はリモートでも同じように生成されるため、このイベントはリモートには送信せず、それぞれのクライアントで処理します
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ModifyTerrain {
+    pub position: Vec2,
+    pub tile: Tile,
+}
+
+/// 地形にダメージを与えるイベントです
+/// radius が 0 の場合は position にあるタイルだけに、そうでなければ円の範囲内にあるタイルにダメージを与えます
+/// ダメージを受けたひび割れた壁は崩れて床になります
+/// ModifyTerrain と同じく、リモートには送信せずそれぞれのクライアントで処理します
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageTerrain {
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+/// ダメージを受けたひび割れた壁を、床に置き換えるイベントに変換します
+fn read_damage_terrain_event(
+    mut reader: EventReader<DamageTerrain>,
+    mut writer: EventWriter<ModifyTerrain>,
+    current: Res<CurrentLevel>,
+) {
+    let Some(chunk) = current.chunk.as_ref() else {
+        return;
+    };
+
+    for DamageTerrain { position, radius } in reader.read() {
+        for (x, y) in damaged_cracked_walls(chunk, *position, *radius) {
+            writer.send(ModifyTerrain {
+                position: Vec2::new(
+                    TILE_SIZE * x as f32 + TILE_HALF,
+                    TILE_SIZE * -y as f32 - TILE_HALF,
+                ),
+                tile: Tile::StoneTile,
+            });
+        }
+    }
+}
+
+/// ダメージを受けるひび割れた壁のタイル座標を返します
+/// position にあるタイルと、タイルの中心が position から radius 以内にあるタイルが対象です
+fn damaged_cracked_walls(chunk: &LevelChunk, position: Vec2, radius: f32) -> Vec<(i32, i32)> {
+    let (cx, cy) = coords_to_tile(position);
+    let r = (radius / TILE_SIZE).ceil() as i32;
+    let mut tiles = Vec::new();
+    for y in (cy - r)..=(cy + r) {
+        for x in (cx - r)..=(cx + r) {
+            if chunk.get_tile(x, y) != Tile::CrackedWall {
+                continue;
+            }
+            let center = Vec2::new(
+                TILE_SIZE * x as f32 + TILE_HALF,
+                TILE_SIZE * -y as f32 - TILE_HALF,
+            );
+            if (x, y) == (cx, cy) || center.distance(position) <= radius {
+                tiles.push((x, y));
+            }
+        }
+    }
+    tiles
+}
+
+/// 地形を変化させ、変化したタイルの周囲のスプライトと衝突形状だけを作り直します
+/// 同じフレームに複数のタイルが変化した場合も、作り直しはまとめて一度だけ行います
+fn read_modify_terrain_event(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut reader: EventReader<ModifyTerrain>,
+    mut current: ResMut<CurrentLevel>,
+    tile_query: Query<(Entity, &WorldTile)>,
+    collider_query: Query<(Entity, &WallCollider)>,
+    rapier_context: Query<&RapierContext, With<DefaultRapierContext>>,
+    particle: Res<BulletParticleResource>,
+    mut se_writer: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Some(chunk) = current.chunk.as_mut() else {
+        return;
+    };
+    let context: &RapierContext = rapier_context.single();
+
+    let mut changed: HashSet<(i32, i32)> = HashSet::new();
+
+    for ModifyTerrain { position, tile } in reader.read() {
+        let (x, y) = coords_to_tile(*position);
+        if x <= chunk.min_x || chunk.max_x - 1 <= x || y <= chunk.min_y || chunk.max_y - 1 <= y {
+            continue;
+        }
+
+        match (chunk.get_tile(x, y), tile) {
+            (Tile::Wall | Tile::CrackedWall, Tile::StoneTile) => {}
+            // 何かが立っている場所に壁を立てると閉じ込めてしまうため、その場合は何もしません
+            (Tile::StoneTile, Tile::Wall) if !is_occupied(context, x, y) => {}
+            _ => continue,
+        }
+
+        chunk.set_tile(x, y, *tile);
+        changed.insert((x, y));
+
+        let center = Vec2::new(
+            TILE_SIZE * x as f32 + TILE_HALF,
+            TILE_SIZE * -y as f32 - TILE_HALF,
+        );
+        spawn_particle_system(&mut commands, center, &particle, &mut rng);
+        se_writer.send(SEEvent::pos(
+            match tile {
+                Tile::Wall => SE::Drop,
+                _ => SE::Break,
+            },
+            center,
+        ));
+    }
+
+    if changed.is_empty() {
+        return;
+    }
+
+    rebuild_world_tiles(&mut commands, &assets, chunk, &tile_query, &changed);
+    rebuild_wall_colliders(&mut commands, chunk, &collider_query, &changed);
+}
+
+/// 指定したタイルの上に、壁の衝突形状を持たないアクターやエンティティがいるかどうかを返します
+fn is_occupied(context: &RapierContext, x: i32, y: i32) -> bool {
+    let center = Vec2::new(
+        TILE_SIZE * x as f32 + TILE_HALF,
+        TILE_SIZE * -y as f32 - TILE_HALF,
+    );
+    let mut occupied = false;
+    context.intersections_with_shape(
+        center,
+        0.0,
+        &Collider::cuboid(TILE_HALF, TILE_HALF),
+        QueryFilter::default()
+            .exclude_sensors()
+            .groups(CollisionGroups::new(
+                WALL_GROUP,
+                ENTITY_GROUP | WITCH_GROUP | ENEMY_GROUP,
+            )),
+        |_| {
+            occupied = true;
+            false // ひとつ見つかれば検索を終えます
+        },
+    );
+    occupied
+}
+
+/// 変化したタイルとその周囲8タイルのスプライトを作り直します
+/// 壁の側面や天井の形は隣接するタイルによって決まるため、周囲のタイルも作り直す必要があります
+fn rebuild_world_tiles(
+    commands: &mut Commands,
+    assets: &Res<GameAssets>,
+    chunk: &LevelChunk,
+    tile_query: &Query<(Entity, &WorldTile)>,
+    changed: &HashSet<(i32, i32)>,
+) {
+    let mut affected: HashSet<(i32, i32)> = HashSet::new();
+    for (x, y) in changed.iter() {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                affected.insert((x + dx, y + dy));
+            }
+        }
+    }
+
+    for (entity, tile) in tile_query.iter() {
+        if affected.contains(&(tile.x, tile.y)) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for (x, y) in affected {
+        spawn_world_tile(commands, assets, chunk, x, y);
+    }
+}
+
+/// 変化したタイルに重なる壁の衝突形状を取り除き、その範囲の壁だけを矩形にまとめ直します
+/// 取り除いた衝突形状の範囲と変化したタイルの外側にある衝突形状には影響しません
+fn rebuild_wall_colliders(
+    commands: &mut Commands,
+    chunk: &LevelChunk,
+    collider_query: &Query<(Entity, &WallCollider)>,
+    changed: &HashSet<(i32, i32)>,
+) {
+    let mut removed: Vec<IRect> = Vec::new();
+    for (entity, collider) in collider_query.iter() {
+        if changed
+            .iter()
+            .any(|(x, y)| collider.rect.contains(IVec2::new(*x, *y)))
+        {
+            commands.entity(entity).despawn_recursive();
+            removed.push(collider.rect);
+        }
+    }
+
+    let bounds = changed
+        .iter()
+        .map(|(x, y)| IRect::new(*x, *y, *x, *y))
+        .chain(removed.iter().copied())
+        .reduce(|a, b| a.union(b))
+        .unwrap();
+
+    let rects = merge_wall_tiles(bounds, |x, y| {
+        chunk.get_tile(x, y).is_wall()
+            && (changed.contains(&(x, y)) || removed.iter().any(|r| r.contains(IVec2::new(x, y))))
+    });
+    for rect in rects {
+        spawn_wall_collider(commands, rect);
+    }
+}
+
+pub struct TerrainPlugin;
+
+impl Plugin for TerrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ModifyTerrain>();
+        app.add_event::<DamageTerrain>();
+        app.add_systems(
+            FixedUpdate,
+            (read_damage_terrain_event, read_modify_terrain_event)
+                .chain()
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::level::map::Biome;
+
+    /// 中央の (5, 5) から右に並んだひび割れた壁のレベルです
+    fn chunk() -> LevelChunk {
+        let mut chunk = LevelChunk::new(0, 16, 0, 16, Tile::StoneTile, Biome::Dungeon);
+        for x in 5..10 {
+            chunk.set_tile(x, 5, Tile::CrackedWall);
+        }
+        chunk.set_tile(5, 6, Tile::Wall);
+        chunk
+    }
+
+    fn tile_center(x: i32, y: i32) -> Vec2 {
+        Vec2::new(
+            TILE_SIZE * x as f32 + TILE_HALF,
+            TILE_SIZE * -y as f32 - TILE_HALF,
+        )
+    }
+
+    #[test]
+    fn zero_radius_damages_only_the_hit_tile() {
+        let tiles = damaged_cracked_walls(&chunk(), tile_center(5, 5), 0.0);
+        assert_eq!(tiles, vec![(5, 5)]);
+    }
+
+    #[test]
+    fn radius_selects_tiles_whose_center_is_inside() {
+        // 2タイル分の半径では、中心が範囲内にある2つ隣のタイルまでが対象になります
+        let mut tiles = damaged_cracked_walls(&chunk(), tile_center(5, 5), TILE_SIZE * 2.0);
+        tiles.sort();
+        assert_eq!(tiles, vec![(5, 5), (6, 5), (7, 5)]);
+    }
+
+    #[test]
+    fn plain_walls_and_floors_are_not_damaged() {
+        let tiles = damaged_cracked_walls(&chunk(), tile_center(5, 7), TILE_SIZE);
+        assert!(tiles.is_empty());
+    }
+}
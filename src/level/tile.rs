@@ -1,11 +1,111 @@
 use bevy::prelude::Component;
+use serde::Deserialize;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Deserialize)]
 pub enum Tile {
     Blank,
     Wall,
     StoneTile,
+
+    /// 移動が遅くなり、炎上が消える水場です
+    Water,
+
+    /// 上にいるあいだ炎上し続ける溶岩です
+    Lava,
+
+    /// 滑りやすく、止まりにくい氷の床です
+    Ice,
+
+    /// 踏み込んだときにダメージを受けるトゲの床です
+    Spikes,
+
+    /// ダメージを受けると崩れて床になる、ひび割れた壁です
+    CrackedWall,
+}
+
+impl Tile {
+    /// 壁として衝突形状や天井を持つタイルかどうかを返します
+    pub fn is_wall(&self) -> bool {
+        match self {
+            Tile::Wall | Tile::CrackedWall => true,
+            _ => false,
+        }
+    }
+
+    /// アクターやエンティティが立つことのできる床のタイルかどうかを返します
+    pub fn is_floor(&self) -> bool {
+        match self {
+            Tile::StoneTile | Tile::Water | Tile::Lava | Tile::Ice | Tile::Spikes => true,
+            _ => false,
+        }
+    }
+
+    /// 上に立つとダメージや状態異常を受ける床のタイルかどうかを返します
+    /// プレイヤーが現れた瞬間にダメージを受けないよう、入口はこのタイルに置けません
+    pub fn is_hazard(&self) -> bool {
+        match self {
+            Tile::Lava | Tile::Spikes => true,
+            _ => false,
+        }
+    }
 }
 
+/// 床や壁、天井などのタイルのスプライトです
+/// x と y は、このスプライトを生成したタイルのタイル座標です
+/// 地形が変化したときに、作り直す必要のあるスプライトを探すために使います
 #[derive(Component)]
-pub struct WorldTile;
+pub struct WorldTile {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TILES: [Tile; 8] = [
+        Tile::Blank,
+        Tile::Wall,
+        Tile::StoneTile,
+        Tile::Water,
+        Tile::Lava,
+        Tile::Ice,
+        Tile::Spikes,
+        Tile::CrackedWall,
+    ];
+
+    #[test]
+    fn walls_are_not_floors() {
+        let walls: Vec<Tile> = TILES.into_iter().filter(Tile::is_wall).collect();
+        assert_eq!(walls, vec![Tile::Wall, Tile::CrackedWall]);
+        for tile in TILES {
+            assert!(!(tile.is_wall() && tile.is_floor()), "{:?}", tile);
+        }
+    }
+
+    #[test]
+    fn special_floors_are_floors() {
+        let floors: Vec<Tile> = TILES.into_iter().filter(Tile::is_floor).collect();
+        assert_eq!(
+            floors,
+            vec![
+                Tile::StoneTile,
+                Tile::Water,
+                Tile::Lava,
+                Tile::Ice,
+                Tile::Spikes
+            ]
+        );
+        assert!(!Tile::Blank.is_floor());
+    }
+
+    #[test]
+    fn hazards_are_floors() {
+        for tile in TILES.into_iter().filter(Tile::is_hazard) {
+            assert!(tile.is_floor(), "{:?}", tile);
+        }
+        assert!(Tile::Lava.is_hazard());
+        assert!(Tile::Spikes.is_hazard());
+        assert!(!Tile::Ice.is_hazard());
+    }
+}
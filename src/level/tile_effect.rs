@@ -0,0 +1,141 @@
+use crate::controller::remote::RemotePlayer;
+use crate::element::DamageType;
+use crate::entity::actor::Actor;
+use crate::entity::damege::spawn_damage_number;
+use crate::entity::life::Life;
+use crate::entity::status_effect::{StatusEffect, StatusEffectType, StatusEffects};
+use crate::level::tile::Tile;
+use crate::level::CurrentLevel;
+use crate::se::{SEEvent, SE};
+use crate::states::GameState;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+// 水の上での移動力の倍率
+const WATER_MOVE_FORCE_FACTOR: f32 = 0.5;
+
+// 氷の上での減衰の倍率
+// 減衰が小さくなるため、移動をやめてもしばらく滑り続けます
+const ICE_DAMPING_FACTOR: f32 = 0.15;
+
+// 氷の上での移動力の倍率
+// 最高速度は移動力と減衰の比で決まるため、減衰と同じ倍率にして床の上と同じ最高速度に保ちます
+// そのぶん加速も遅くなり、動き出しにくく止まりにくい床になります
+const ICE_MOVE_FORCE_FACTOR: f32 = ICE_DAMPING_FACTOR;
+
+// 溶岩の上にいるあいだ付与し続ける炎上の持続時間のフレーム数
+// 溶岩から出たあとも、この時間だけ炎上が続きます
+const LAVA_BURNING_DURATION: u32 = 60;
+
+// トゲの床に踏み込んだときのダメージ
+const SPIKES_DAMAGE: i32 = 5;
+
+/// アクターが立っているタイルです
+/// タイルが変わったことを検出するため、前回のフレームのタイルを記録します
+/// 氷の上で減衰を下げるため、もとの減衰の値も記録します
+#[derive(Component, Debug)]
+pub struct Footing {
+    tile: Tile,
+    linear_damping: f32,
+}
+
+impl Footing {
+    /// 足元のタイルによる移動力の倍率です
+    pub fn move_force_factor(&self) -> f32 {
+        match self.tile {
+            Tile::Water => WATER_MOVE_FORCE_FACTOR,
+            Tile::Ice => ICE_MOVE_FORCE_FACTOR,
+            _ => 1.0,
+        }
+    }
+}
+
+/// アクターの足元のタイルに応じて、減衰や状態異常、ダメージを適用します
+/// リモートのプレイヤーにはそれぞれのクライアントで適用されるため、ここでは扱いません
+fn apply_tile_effects(
+    mut commands: Commands,
+    current: Res<CurrentLevel>,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut Damping,
+            &mut Life,
+            Option<&mut StatusEffects>,
+            Option<&mut Footing>,
+        ),
+        (With<Actor>, Without<RemotePlayer>),
+    >,
+    mut se_writer: EventWriter<SEEvent>,
+) {
+    let Some(chunk) = current.chunk.as_ref() else {
+        return;
+    };
+
+    for (entity, transform, mut damping, mut life, mut status, footing) in query.iter_mut() {
+        let position = transform.translation.truncate();
+        let tile = chunk.get_tile_by_coords(position);
+
+        let Some(mut footing) = footing else {
+            // 最初のフレームでは、もとの減衰の値を記録するだけにします
+            commands.entity(entity).insert(Footing {
+                tile,
+                linear_damping: damping.linear_damping,
+            });
+            continue;
+        };
+
+        let entered = footing.tile != tile;
+        footing.tile = tile;
+
+        let linear_damping = match tile {
+            Tile::Ice => footing.linear_damping * ICE_DAMPING_FACTOR,
+            _ => footing.linear_damping,
+        };
+        if damping.linear_damping != linear_damping {
+            damping.linear_damping = linear_damping;
+        }
+
+        match tile {
+            Tile::Water => {
+                if let Some(ref mut status) = status {
+                    status.remove(StatusEffectType::Burning);
+                }
+            }
+            Tile::Lava => {
+                if let Some(ref mut status) = status {
+                    status.apply(StatusEffect {
+                        effect_type: StatusEffectType::Burning,
+                        duration: LAVA_BURNING_DURATION,
+                        stacks: 1,
+                    });
+                }
+            }
+            Tile::Spikes if entered => {
+                let damage = life.resistance.apply(SPIKES_DAMAGE, DamageType::Physical);
+                let damage = status
+                    .as_ref()
+                    .map(|s| s.filter_damage(damage))
+                    .unwrap_or(damage);
+                life.life = (life.life - damage).max(0);
+                life.amplitude = 6.0;
+                spawn_damage_number(&mut commands, damage, DamageType::Physical, position);
+                se_writer.send(SEEvent::pos(SE::Damage, position));
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct TileEffectPlugin;
+
+impl Plugin for TileEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            apply_tile_effects
+                .run_if(in_state(GameState::InGame))
+                .before(PhysicsSet::SyncBackend),
+        );
+    }
+}
@@ -2,7 +2,7 @@ use crate::level::{
     map::LevelChunk, ENEMY_BULLET_GROUP, ENEMY_GROUP, ENTITY_GROUP, TILE_HALF, TILE_SIZE,
     WALL_GROUP, WITCH_BULLET_GROUP, WITCH_GROUP,
 };
-use crate::{level::Tile, states::GameState};
+use crate::states::GameState;
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::{
     CoefficientCombineRule, Collider, CollisionGroups, Friction, RigidBody,
@@ -14,7 +14,16 @@ use std::collections::HashMap;
 /// TODO: 本棚などのエンティティもここで一括で生成したほうが効率はいい？
 /// でもエンティティが個別に削除されることも多そうなので、その場合はエンティティは別のほうがいいかも
 /// https://github.com/Trouv/bevy_ecs_ldtk/blob/main/examples/platformer/walls.rs
-pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<Rect> {
+pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<IRect> {
+    merge_wall_tiles(
+        IRect::new(chunk.min_x, chunk.min_y, chunk.max_x - 1, chunk.max_y - 1),
+        |x, y| chunk.get_tile(x, y).is_wall(),
+    )
+}
+
+/// bounds の範囲内で is_wall を満たすタイルを、なるべく少ない矩形にまとめます
+/// bounds と返り値の矩形は、どちらも両端のタイルを含むタイル座標で表します
+pub fn merge_wall_tiles(bounds: IRect, is_wall: impl Fn(i32, i32) -> bool) -> Vec<IRect> {
     /// Represents a wide wall that is 1 tile tall
     /// Used to spawn wall collisions
     #[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
@@ -26,16 +35,13 @@ pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<Rect> {
     // combine wall tiles into flat "plates" in each individual row
     let mut plate_stack: Vec<Vec<Plate>> = Vec::new();
 
-    for y in chunk.min_y..chunk.max_y {
+    for y in bounds.min.y..=bounds.max.y {
         let mut row_plates: Vec<Plate> = Vec::new();
         let mut plate_start = None;
 
         // + 1 to the width so the algorithm "terminates" plates that touch the right edge
-        for x in chunk.min_x..(chunk.max_x + 1) {
-            match (
-                plate_start,
-                chunk.get_tile(x as i32, y as i32) == Tile::Wall,
-            ) {
+        for x in bounds.min.x..=(bounds.max.x + 1) {
+            match (plate_start, x <= bounds.max.x && is_wall(x, y)) {
                 (Some(s), false) => {
                     row_plates.push(Plate {
                         left: s,
@@ -52,9 +58,9 @@ pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<Rect> {
     }
 
     // combine "plates" into rectangles across multiple rows
-    let mut rect_builder: HashMap<Plate, Rect> = HashMap::new();
+    let mut rect_builder: HashMap<Plate, IRect> = HashMap::new();
     let mut prev_row: Vec<Plate> = Vec::new();
-    let mut wall_rects: Vec<Rect> = Vec::new();
+    let mut wall_rects: Vec<IRect> = Vec::new();
 
     // an extra empty row so the algorithm "finishes" the rects that touch the top edge
     plate_stack.push(Vec::new());
@@ -69,18 +75,13 @@ pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<Rect> {
             }
         }
 
-        let y = chunk.min_y + plate_index as i32;
+        let y = bounds.min.y + plate_index as i32;
 
         for plate in &current_row {
             rect_builder
                 .entry(plate.clone())
-                .and_modify(|e| e.max.y += 1.0)
-                .or_insert(Rect::new(
-                    plate.left as f32,
-                    y as f32,
-                    plate.right as f32,
-                    y as f32,
-                ));
+                .and_modify(|e| e.max.y += 1)
+                .or_insert(IRect::new(plate.left, y, plate.right, y));
         }
         prev_row = current_row;
     }
@@ -88,37 +89,45 @@ pub fn get_wall_collisions(chunk: &LevelChunk) -> Vec<Rect> {
     wall_rects
 }
 
+/// 壁の衝突形状です
+/// rect は衝突形状が覆っている範囲のタイル座標で、両端のタイルを含みます
+/// 地形が変化したときに、作り直す必要のある衝突形状を探すために使います
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Component)]
-pub struct WallCollider;
+pub struct WallCollider {
+    pub rect: IRect,
+}
 
 pub fn spawn_wall_collisions(commands: &mut Commands, chunk: &LevelChunk) {
     // 衝突形状の生成
     for rect in get_wall_collisions(&chunk) {
-        let w = TILE_HALF * (rect.width() + 1.0);
-        let h = TILE_HALF * (rect.height() + 1.0);
-        let x = rect.min.x as f32 * TILE_SIZE + w;
-        let y = rect.min.y as f32 * -TILE_SIZE - h;
-        commands.spawn((
-            Name::new("wall collider"),
-            WallCollider,
-            StateScoped(GameState::InGame),
-            Transform::from_translation(Vec3::new(x, y, 0.0)),
-            GlobalTransform::default(),
-            // todo: merge colliders
-            Collider::cuboid(w, h),
-            RigidBody::Fixed,
-            Friction {
-                coefficient: 0.0,
-                combine_rule: CoefficientCombineRule::Min,
-            },
-            CollisionGroups::new(
-                WALL_GROUP,
-                ENTITY_GROUP | WITCH_GROUP | WITCH_BULLET_GROUP | ENEMY_GROUP | ENEMY_BULLET_GROUP,
-            ),
-        ));
+        spawn_wall_collider(commands, rect);
     }
+}
 
-    commands.insert_resource(chunk.clone());
+/// タイル座標の矩形を覆う、壁の衝突形状を生成します
+pub fn spawn_wall_collider(commands: &mut Commands, rect: IRect) {
+    let w = TILE_HALF * (rect.width() + 1) as f32;
+    let h = TILE_HALF * (rect.height() + 1) as f32;
+    let x = rect.min.x as f32 * TILE_SIZE + w;
+    let y = rect.min.y as f32 * -TILE_SIZE - h;
+    commands.spawn((
+        Name::new("wall collider"),
+        WallCollider { rect },
+        StateScoped(GameState::InGame),
+        Transform::from_translation(Vec3::new(x, y, 0.0)),
+        GlobalTransform::default(),
+        // todo: merge colliders
+        Collider::cuboid(w, h),
+        RigidBody::Fixed,
+        Friction {
+            coefficient: 0.0,
+            combine_rule: CoefficientCombineRule::Min,
+        },
+        CollisionGroups::new(
+            WALL_GROUP,
+            ENTITY_GROUP | WITCH_GROUP | WITCH_BULLET_GROUP | ENEMY_GROUP | ENEMY_BULLET_GROUP,
+        ),
+    ));
 }
 
 pub struct WallPlugin;
@@ -0,0 +1,38 @@
+// ゲーム本体と、レベルの検証ツールなどの補助的なバイナリの両方から使われます
+mod asset;
+mod audio;
+mod camera;
+mod cast;
+mod config;
+mod constant;
+mod controller;
+mod curve;
+mod debug;
+mod element;
+mod enemy;
+mod entity;
+mod equipment;
+mod footsteps;
+pub mod game;
+mod hud;
+mod input;
+mod inventory;
+mod inventory_item;
+mod language;
+pub mod level;
+mod page;
+mod physics;
+mod player_state;
+mod random;
+mod se;
+mod set;
+mod speech_bubble;
+mod spell;
+mod spell_props;
+pub mod spell_registry;
+mod states;
+mod ui;
+mod wand;
+mod wand_generator;
+mod wand_props;
+mod wand_simulator;
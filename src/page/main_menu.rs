@@ -3,7 +3,8 @@ use crate::config::GameConfig;
 use crate::constant::HUD_Z_INDEX;
 use crate::hud::overlay::OverlayEvent;
 use crate::language::Languages;
-use crate::level::CurrentLevel;
+use crate::level::{CurrentLevel, LevelLoadError};
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
 use crate::ui::on_press::OnPress;
 use crate::{
@@ -13,6 +14,9 @@ use crate::{
 use bevy::core::FrameCount;
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
+use bevy_simple_text_input::{
+    TextInput, TextInputSettings, TextInputTextColor, TextInputTextFont, TextInputValue,
+};
 use git_version::git_version;
 
 const SCALE: f32 = 4.0;
@@ -38,15 +42,43 @@ struct LanguageButton;
 #[derive(Component)]
 struct ClickToStart;
 
+/// 冒険のシードを入力する欄です
+/// 空欄のまま始めると無作為なシードが使われます
+#[derive(Component)]
+struct SeedInput;
+
 fn setup_main_menu(
     mut commands: Commands,
     assets: Res<GameAssets>,
     mut next_bgm: ResMut<NextBGM>,
     mut current: ResMut<CurrentLevel>,
+    mut load_error: ResMut<LevelLoadError>,
 ) {
     *next_bgm = NextBGM(Some(assets.boubaku.clone()));
     *current = CurrentLevel::default();
 
+    // レベルの読み込みに失敗して戻ってきた場合は、そのエラーを表示します
+    if let Some(message) = load_error.0.take() {
+        commands.spawn((
+            Name::new("level_load_error"),
+            StateScoped(GameState::MainMenu),
+            GlobalZIndex(HUD_Z_INDEX),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(20.0),
+                top: Val::Px(20.0),
+                right: Val::Px(20.0),
+                ..default()
+            },
+            Text::new(message),
+            TextColor::from(Color::srgb(1.0, 0.4, 0.4)),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+        ));
+    }
+
     commands.spawn((
         Name::new("main_menu"),
         StateScoped(GameState::MainMenu),
@@ -212,6 +244,53 @@ fn setup_main_menu(
                 ..default()
             },
         ));
+
+    commands
+        .spawn((
+            Name::new("seed_input"),
+            StateScoped(GameState::MainMenu),
+            GlobalZIndex(HUD_Z_INDEX),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(40.0),
+                bottom: Val::Px(40.0),
+                column_gap: Val::Px(8.0),
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("シード / Seed"),
+                TextColor::from(Color::WHITE),
+                TextFont {
+                    font_size: 16.0,
+                    font: assets.dotgothic.clone(),
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                SeedInput,
+                Node {
+                    width: Val::Px(200.0),
+                    padding: UiRect::new(Val::Px(8.0), Val::Px(8.0), Val::Px(4.0), Val::Px(4.0)),
+                    ..default()
+                },
+                BackgroundColor::from(Color::hsva(0.0, 0.0, 0.0, 0.5)),
+                Interaction::default(),
+                TextInput,
+                TextInputTextFont(TextFont {
+                    font_size: 16.0,
+                    ..default()
+                }),
+                TextInputTextColor(Color::WHITE.into()),
+                TextInputSettings {
+                    retain_on_submit: true,
+                    ..default()
+                },
+                TextInputValue("".to_string()),
+            ));
+        });
 }
 
 fn spawn_cloud<T: Component>(
@@ -291,7 +370,12 @@ fn start_game(
     buttons: Res<ButtonInput<MouseButton>>,
     mut writer: EventWriter<Events>,
     changed: Res<LanguageChanged>,
+    seed_query: Query<&Interaction, With<SeedInput>>,
 ) {
+    // シードの入力欄をクリックしたときはスタートしません
+    if seed_query.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
     if !changed.0 && buttons.any_just_pressed(vec![MouseButton::Left, MouseButton::Right]) {
         writer.send(Events::Start);
     }
@@ -304,6 +388,8 @@ fn read_events(
     mut reader: EventReader<Events>,
     mut next_bgm: ResMut<NextBGM>,
     mut overlay_event_writer: EventWriter<OverlayEvent>,
+    mut rng: ResMut<GameRng>,
+    seed_query: Query<&TextInputValue, With<SeedInput>>,
 ) {
     for event in reader.read() {
         match event {
@@ -311,6 +397,12 @@ fn read_events(
                 for mut visibility in &mut query {
                     *visibility = Visibility::Hidden;
                 }
+                let seed = seed_query
+                    .get_single()
+                    .map(|value| GameRng::parse_seed(&value.0))
+                    .unwrap_or_else(|_| GameRng::parse_seed(""));
+                *rng = GameRng::new(seed);
+                info!("starting a new run with seed {}", seed);
                 menu_next_state.set(MainMenuPhase::Paused);
                 writer.send(SEEvent::new(SE::Click));
                 overlay_event_writer.send(OverlayEvent::Close(GameState::InGame));
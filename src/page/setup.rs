@@ -1,6 +1,13 @@
 use crate::{constant::LOADING_Z_INDEX, states::GameState};
+use bevy::asset::UntypedAssetLoadFailedEvent;
 use bevy::prelude::*;
 
+/// 読み込みに失敗したアセットのエラーメッセージです
+/// LoadError 画面で表示します
+/// フォントのアセットも読み込めていない可能性があるため、メッセージは英語の組み込みフォントで表示します
+#[derive(Resource, Default)]
+struct LoadErrors(Vec<String>);
+
 fn setup(mut commands: Commands) {
     commands.spawn((
         StateScoped(GameState::Setup),
@@ -20,10 +27,48 @@ fn setup(mut commands: Commands) {
     ));
 }
 
+/// アセットの読み込みの失敗を記録します
+/// 定義ファイルの解析エラーなどは、ここで記録したメッセージとして LoadError 画面に表示されます
+fn read_load_failed_event(
+    mut reader: EventReader<UntypedAssetLoadFailedEvent>,
+    mut errors: ResMut<LoadErrors>,
+) {
+    for event in reader.read() {
+        error!("{}", event.error);
+        errors.0.push(event.error.to_string());
+    }
+}
+
+fn setup_load_error(mut commands: Commands, errors: Res<LoadErrors>) {
+    commands.spawn((
+        StateScoped(GameState::LoadError),
+        Text::new(format!("Failed to load assets\n\n{}", errors.0.join("\n"))),
+        TextColor(Color::WHITE),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(20.0),
+            top: Val::Px(20.0),
+            right: Val::Px(20.0),
+            ..default()
+        },
+        GlobalZIndex(LOADING_Z_INDEX),
+    ));
+}
+
 pub struct SetupPlugin;
 
 impl Plugin for SetupPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<LoadErrors>();
         app.add_systems(Startup, setup);
+        app.add_systems(
+            Update,
+            read_load_failed_event.run_if(in_state(GameState::Setup)),
+        );
+        app.add_systems(OnEnter(GameState::LoadError), setup_load_error);
     }
 }
@@ -73,12 +73,33 @@ impl PlayerState {
         inventory.insert_free(InventoryItemType::Spell(SpellType::Homing));
         inventory.insert_free(InventoryItemType::Spell(SpellType::Homing));
         inventory.insert_free(InventoryItemType::Wand(WandType::KeyWand));
+        inventory.insert_free(InventoryItemType::Wand(WandType::GuidingWand));
         inventory.insert_free(InventoryItemType::Spell(SpellType::HeavyShot));
         inventory.insert_free(InventoryItemType::Spell(SpellType::HeavyShot));
         inventory.insert_free(InventoryItemType::Spell(SpellType::HeavyShot));
         inventory.insert_free(InventoryItemType::Spell(SpellType::SummonFriendSlime));
         inventory.insert_free(InventoryItemType::Spell(SpellType::SummonEnemySlime));
         inventory.insert_free(InventoryItemType::Spell(SpellType::Dash));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::TriggerBolt));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::TimerBolt));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::PowerShot));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::LongLife));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::BigShot));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Precision));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Knockback));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Bounce));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Piercing));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::FireBolt));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::IceBolt));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Haste));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Protection));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::BlastBolt));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::MagicBeam));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Blink));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Barrier));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Dig));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::RaiseWall));
+        inventory.insert_free(InventoryItemType::Spell(SpellType::Grenade));
         inventory.sort();
 
         let mut equipments = [None; MAX_ITEMS_IN_EQUIPMENT];
@@ -94,6 +115,7 @@ impl PlayerState {
                     Some(WandSpell {
                         spell_type: SpellType::MagicBolt,
                         price: 0,
+                        tier: 0,
                     }),
                     None,
                     None,
@@ -110,6 +132,7 @@ impl PlayerState {
                     Some(WandSpell {
                         spell_type: SpellType::SummonFriendSlime,
                         price: 0,
+                        tier: 0,
                     }),
                     None,
                     None,
@@ -126,6 +149,7 @@ impl PlayerState {
                     Some(WandSpell {
                         spell_type: SpellType::SummonEnemySlime,
                         price: 0,
+                        tier: 0,
                     }),
                     None,
                     None,
@@ -142,6 +166,7 @@ impl PlayerState {
                     Some(WandSpell {
                         spell_type: SpellType::Dash,
                         price: 0,
+                        tier: 0,
                     }),
                     None,
                     None,
@@ -1,8 +1,110 @@
-pub fn random_select_mut<T: Copy>(xs: &mut Vec<T>) -> T {
-    xs.remove((rand::random::<usize>() % xs.len()) as usize)
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// ゲーム全体で共有する乱数生成器です
+/// ひとつのシードから用途ごとに独立した系列を作り、すべてのシステムはいずれかの系列から乱数を取り出します
+/// 系列が分かれているため、たとえば戦闘で弾丸を多く撃っても、その後に生成されるレベルや宝箱の中身は変わりません
+/// 同じシードで冒険を始めれば同じ展開を再現できるので、不具合の報告にはシードを添えてください
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+
+    /// レベルの生成と、敵やアイテムの配置
+    pub level: StdRng,
+
+    /// 弾丸の拡散や召喚など、戦闘の結果に関わる乱数
+    pub combat: StdRng,
+
+    /// 宝箱やショップ、床に落ちているアイテムの中身
+    pub loot: StdRng,
+
+    /// パーティクルや BGM の選択など、ゲームの結果に影響しない演出
+    pub cosmetic: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng {
+            seed,
+            level: stream(seed, 0),
+            combat: stream(seed, 1),
+            loot: stream(seed, 2),
+            cosmetic: stream(seed, 3),
+        }
+    }
+
+    /// 入力された文字列からシードを読み取ります
+    /// 空欄や数値として読めない場合は、無作為なシードを返します
+    pub fn parse_seed(text: &str) -> u64 {
+        text.trim()
+            .parse::<u64>()
+            .unwrap_or_else(|_| rand::random::<u64>())
+    }
+}
+
+/// シードと系列の番号から、系列ごとの乱数生成器を作ります
+/// シードに番号を足すだけでは隣り合うシードどうしで系列が重なるため、SplitMix64 で混ぜ合わせたシードを使います
+fn stream(seed: u64, stream_id: u64) -> StdRng {
+    StdRng::seed_from_u64(split_mix64(split_mix64(seed) ^ stream_id))
+}
+
+fn split_mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
 }
 
-pub fn random_select<T: Copy>(xs: &Vec<T>) -> &T {
-    xs.get((rand::random::<usize>() % xs.len()) as usize)
-        .unwrap()
+impl Default for GameRng {
+    fn default() -> Self {
+        GameRng::new(rand::random::<u64>())
+    }
+}
+
+pub fn random_select_mut<T, R: Rng>(rng: &mut R, xs: &mut Vec<T>) -> T {
+    xs.remove(rng.gen_range(0..xs.len()))
+}
+
+pub fn random_select<'a, T: Copy, R: Rng>(rng: &mut R, xs: &'a Vec<T>) -> &'a T {
+    xs.get(rng.gen_range(0..xs.len())).unwrap()
+}
+
+pub struct RandomPlugin;
+
+impl Plugin for RandomPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameRng>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adjacent_seeds_do_not_share_streams() {
+        let mut firsts: Vec<u64> = Vec::new();
+        for seed in 0..8 {
+            let mut rng = GameRng::new(seed);
+            firsts.push(rng.level.gen());
+            firsts.push(rng.combat.gen());
+            firsts.push(rng.loot.gen());
+            firsts.push(rng.cosmetic.gen());
+        }
+        let count = firsts.len();
+        firsts.sort();
+        firsts.dedup();
+        assert_eq!(firsts.len(), count);
+    }
+
+    #[test]
+    fn same_seed_gives_same_streams() {
+        let mut a = GameRng::new(42);
+        let mut b = GameRng::new(42);
+        assert_eq!(a.level.gen::<u64>(), b.level.gen::<u64>());
+        assert_eq!(a.combat.gen::<u64>(), b.combat.gen::<u64>());
+        assert_eq!(a.loot.gen::<u64>(), b.loot.gen::<u64>());
+        assert_eq!(a.cosmetic.gen::<u64>(), b.cosmetic.gen::<u64>());
+    }
 }
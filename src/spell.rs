@@ -1,23 +1,117 @@
+use crate::language::intern;
 use bevy::reflect::Reflect;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Mutex;
 
-#[derive(Reflect, Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
-pub enum SpellType {
-    MagicBolt,
-    PurpleBolt,
-    SlimeCharge,
-    Heal,
-    BulletSpeedUp,
-    BulletSpeedDoown,
-    DualCast,
-    TripleCast,
-    Homing,
-    HeavyShot,
-    SummonFriendSlime,
-    SummonEnemySlime,
-    Dash,
+/// 呪文定義ファイルから読み込まれたことのある呪文の識別子の一覧です
+/// 通信などで受け取った識別子はこの一覧にあるものだけを受け付けるため、
+/// 未知の識別子の文字列がリークされ続けることはありません
+static REGISTERED: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+/// 呪文の種類です
+/// 呪文の識別子は定義ファイルの spell_type に記述された文字列で、
+/// 呪文を追加するには定義ファイルに記述するだけで足ります
+/// 各呪文の性能は SpellRegistry に読み込まれた定義ファイルから取得します
+#[derive(Reflect, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[reflect(opaque)]
+pub struct SpellType(&'static str);
+
+/// ゲームのコードから直接参照される呪文です
+/// 定義ファイルにだけ記述された呪文は、ここに定数を追加しなくても使えます
+#[allow(non_upper_case_globals)]
+impl SpellType {
+    pub const MagicBolt: SpellType = SpellType("MagicBolt");
+    pub const PurpleBolt: SpellType = SpellType("PurpleBolt");
+    pub const SlimeCharge: SpellType = SpellType("SlimeCharge");
+    pub const Heal: SpellType = SpellType("Heal");
+    pub const BulletSpeedUp: SpellType = SpellType("BulletSpeedUp");
+    pub const BulletSpeedDoown: SpellType = SpellType("BulletSpeedDoown");
+    pub const DualCast: SpellType = SpellType("DualCast");
+    pub const TripleCast: SpellType = SpellType("TripleCast");
+    pub const Homing: SpellType = SpellType("Homing");
+    pub const HeavyShot: SpellType = SpellType("HeavyShot");
+    pub const SummonFriendSlime: SpellType = SpellType("SummonFriendSlime");
+    pub const SummonEnemySlime: SpellType = SpellType("SummonEnemySlime");
+    pub const Dash: SpellType = SpellType("Dash");
+    pub const TriggerBolt: SpellType = SpellType("TriggerBolt");
+    pub const TimerBolt: SpellType = SpellType("TimerBolt");
+    pub const PowerShot: SpellType = SpellType("PowerShot");
+    pub const LongLife: SpellType = SpellType("LongLife");
+    pub const BigShot: SpellType = SpellType("BigShot");
+    pub const Precision: SpellType = SpellType("Precision");
+    pub const Knockback: SpellType = SpellType("Knockback");
+    pub const Bounce: SpellType = SpellType("Bounce");
+    pub const Piercing: SpellType = SpellType("Piercing");
+    pub const FireBolt: SpellType = SpellType("FireBolt");
+    pub const IceBolt: SpellType = SpellType("IceBolt");
+    pub const Haste: SpellType = SpellType("Haste");
+    pub const Protection: SpellType = SpellType("Protection");
+    pub const BlastBolt: SpellType = SpellType("BlastBolt");
+    pub const MagicBeam: SpellType = SpellType("MagicBeam");
+    pub const Blink: SpellType = SpellType("Blink");
+    pub const Barrier: SpellType = SpellType("Barrier");
+    pub const Dig: SpellType = SpellType("Dig");
+    pub const RaiseWall: SpellType = SpellType("RaiseWall");
+    pub const Grenade: SpellType = SpellType("Grenade");
+}
+
+impl SpellType {
+    /// 定義ファイルに記述された識別子から呪文の種類を作成し、既知の呪文として登録します
+    /// 識別子の文字列はリークされるため、定義ファイルの読み込み以外では SpellType::find を使います
+    pub fn new(id: &str) -> SpellType {
+        let id = intern(id);
+        REGISTERED
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(id);
+        SpellType(id)
+    }
+
+    /// ゲームのコードから参照される呪文か、定義ファイルから読み込まれたことのある呪文の中から、
+    /// 識別子の一致するものを返します
+    /// 現在の定義ファイルに記述されているかどうかは、SpellRegistry::contains で確認します
+    pub fn find(id: &str) -> Option<SpellType> {
+        if let Some(spell_type) = BUILTIN_SPELL_TYPES.iter().find(|s| s.0 == id) {
+            return Some(*spell_type);
+        }
+        REGISTERED
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(id)
+            .copied()
+            .map(SpellType)
+    }
+
+    pub fn id(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl fmt::Debug for SpellType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for SpellType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpellType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        SpellType::find(&id).ok_or_else(|| D::Error::custom(format!("unknown spell {:?}", id)))
+    }
 }
 
-pub const SPELL_TYPES: [SpellType; 13] = [
+/// ゲームのコードから直接参照される呪文の一覧です
+/// 定義ファイルにはこれらすべての呪文が含まれている必要があります
+pub const BUILTIN_SPELL_TYPES: [SpellType; 33] = [
     SpellType::MagicBolt,
     SpellType::PurpleBolt,
     SpellType::SlimeCharge,
@@ -31,4 +125,24 @@ pub const SPELL_TYPES: [SpellType; 13] = [
     SpellType::SummonFriendSlime,
     SpellType::SummonEnemySlime,
     SpellType::Dash,
+    SpellType::TriggerBolt,
+    SpellType::TimerBolt,
+    SpellType::PowerShot,
+    SpellType::LongLife,
+    SpellType::BigShot,
+    SpellType::Precision,
+    SpellType::Knockback,
+    SpellType::Bounce,
+    SpellType::Piercing,
+    SpellType::FireBolt,
+    SpellType::IceBolt,
+    SpellType::Haste,
+    SpellType::Protection,
+    SpellType::BlastBolt,
+    SpellType::MagicBeam,
+    SpellType::Blink,
+    SpellType::Barrier,
+    SpellType::Dig,
+    SpellType::RaiseWall,
+    SpellType::Grenade,
 ];
@@ -1,255 +1,434 @@
-use crate::{language::{Dict, Languages}, spell::SpellType};
+use crate::{
+    constant::SPELL_TIER_BONUS,
+    element::DamageType,
+    entity::{status_effect::StatusEffect, summon::SummonType},
+    language::{Dict, Languages},
+    spell::SpellType,
+    spell_registry::SpellRegistry,
+};
+use bevy::reflect::Reflect;
+use serde::{Deserialize, Serialize};
 
 /// 呪文を詠唱したときの動作を表します
 /// 弾丸系魔法は Bullet にまとめられており、
 /// そのほかの魔法も動作の種別によって分類されています
+/// 呪文定義ファイルの cast フィールドとしても読み込まれます
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SpellCast {
-    Bullet {
-        slice: &'static str,
+    Bullet(BulletProps),
+    Heal,
+    /// 次に発射される弾丸に修飾を加えます
+    /// 修飾は記述された順に適用されます
+    Modifier(Vec<BulletModifier>),
+    MultipleCast {
+        amount: u32,
+    },
+    /// 指定した種類の生き物を、詠唱者の向いている方向へ召喚します
+    /// friend が有効な場合は詠唱者の味方、そうでなければ詠唱者の敵として召喚されます
+    /// 召喚された生き物は duration フレームが過ぎると消滅します
+    Summon {
+        summon_type: SummonType,
+        friend: bool,
+        duration: u32,
+    },
+    Dash,
+    /// 詠唱者を、ポインターの方向へ最大 range だけ瞬間移動させます
+    /// 移動先は壁の手前で止まり、壁を通り抜けることはできません
+    Teleport {
+        range: f32,
+    },
+    /// 詠唱者自身に状態異常を付与します
+    SelfEffect(StatusEffect),
+    /// 詠唱者の向いている方向へビームを発射します
+    Beam(BeamProps),
+    /// 詠唱者の向いている方向へ、敵の弾丸を防ぐ障壁を生成します
+    Barrier(BarrierProps),
+    /// ポインターの位置へ向けて、壁やアクターを飛び越える放物線を描く弾を投げます
+    /// 杖で次に並んでいる呪文をペイロードとして保持し、着地した位置からそれを詠唱します
+    Lob(LobProps),
+}
 
-        collier_radius: f32,
+impl SpellCast {
+    /// 杖で次に並んでいる呪文をペイロードとして取り出す呪文かどうかを返します
+    pub fn takes_payload(&self) -> bool {
+        match self {
+            SpellCast::Bullet(bullet) => bullet.trigger.is_some(),
+            SpellCast::Lob(_) => true,
+            _ => false,
+        }
+    }
 
-        /// 魔法弾の速度
-        /// pixels_per_meter が 100.0 に設定されているので、
-        /// 200は1フレームに2ピクセル移動する速度です
-        speed: f32,
+    /// 強化段階に応じて性能を強化した呪文を返します
+    /// 1段階ごとにダメージや持続時間などの値が SPELL_TIER_BONUS の割合だけ増えます
+    /// 反射や貫通の回数、詠唱回数などの回数を表す値は変わりません
+    pub fn upgrade(&self, tier: u32) -> SpellCast {
+        let scale = 1.0 + SPELL_TIER_BONUS * tier as f32;
+        let mut cast = self.clone();
+        match cast {
+            SpellCast::Bullet(ref mut bullet) => {
+                bullet.damage = scale_i32(bullet.damage, scale);
+                bullet.explosion_radius *= scale;
+            }
+            SpellCast::Modifier(ref mut modifiers) => {
+                for modifier in modifiers.iter_mut() {
+                    *modifier = modifier.upgrade(scale);
+                }
+            }
+            SpellCast::Summon {
+                ref mut duration, ..
+            } => {
+                *duration = scale_u32(*duration, scale);
+            }
+            SpellCast::Teleport { ref mut range } => {
+                *range *= scale;
+            }
+            SpellCast::SelfEffect(ref mut effect) => {
+                effect.duration = scale_u32(effect.duration, scale);
+            }
+            SpellCast::Beam(ref mut beam) => {
+                beam.damage = scale_i32(beam.damage, scale);
+                beam.range *= scale;
+            }
+            SpellCast::Barrier(ref mut barrier) => {
+                barrier.life = scale_i32(barrier.life, scale);
+                barrier.duration = scale_u32(barrier.duration, scale);
+            }
+            SpellCast::Lob(ref mut lob) => {
+                lob.damage = scale_i32(lob.damage, scale);
+                lob.explosion_radius *= scale;
+            }
+            SpellCast::Heal | SpellCast::MultipleCast { .. } | SpellCast::Dash => {}
+        }
+        cast
+    }
+}
 
-        lifetime: u32,
-        damage: i32,
-        impulse: f32,
+/// 正の値を有利な効果として、有利な値は scale 倍に、不利な値は 1/scale 倍にします
+fn scale_benefit(delta: f32, scale: f32) -> f32 {
+    if 0.0 < delta {
+        delta * scale
+    } else {
+        delta / scale
+    }
+}
 
-        scattering: f32,
+/// 整数の値に scale_benefit を適用します
+fn scale_benefit_i32(value: i32, scale: f32) -> i32 {
+    scale_benefit(value as f32, scale).round() as i32
+}
 
-        light_intensity: f32,
-        light_radius: f32,
-        light_color_hlsa: [f32; 4],
-    },
-    Heal,
-    BulletSpeedUpDown {
-        delta: f32,
-    },
-    MultipleCast {
-        amount: u32,
-    },
-    Homing,
-    HeavyShot,
-    SummonSlime { friend: bool } ,
-    Dash
+/// 1倍を基準とする倍率に scale_benefit を適用します
+/// 1倍を下回る倍率は1倍に近づくだけで、0倍以下になることはありません
+fn scale_factor_benefit(factor: f32, scale: f32) -> f32 {
+    1.0 + scale_benefit(factor - 1.0, scale)
+}
+
+fn scale_i32(value: i32, scale: f32) -> i32 {
+    (value as f32 * scale).round() as i32
+}
+
+fn scale_u32(value: u32, scale: f32) -> u32 {
+    (value as f32 * scale).round() as u32
+}
+
+/// 弾丸系魔法の性能です
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BulletProps {
+    pub slice: String,
+
+    pub collier_radius: f32,
+
+    /// 魔法弾の速度
+    /// pixels_per_meter が 100.0 に設定されているので、
+    /// 200は1フレームに2ピクセル移動する速度です
+    pub speed: f32,
+
+    pub lifetime: u32,
+    pub damage: i32,
+
+    /// ダメージの属性
+    #[serde(default)]
+    pub damage_type: DamageType,
+
+    pub impulse: f32,
+
+    pub scattering: f32,
+
+    pub light_intensity: f32,
+    pub light_radius: f32,
+    pub light_color_hlsa: [f32; 4],
+
+    /// 追尾性能
+    /// 1フレームあたりに目標へ向けて回転する角度の上限です
+    #[serde(default)]
+    pub homing: f32,
+
+    /// 弾丸の大きさの倍率
+    /// スプライトとコライダーの両方に適用されます
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+
+    /// 壁で反射できる回数
+    #[serde(default)]
+    pub bounces: u32,
+
+    /// 命中しても消滅せずに貫通できる回数
+    /// 貫通するたびにダメージは減衰します
+    #[serde(default)]
+    pub pierce: u32,
+
+    /// トリガー付きの弾丸の場合、杖で次に並んでいる呪文をペイロードとして保持し、
+    /// 条件を満たしたときに弾丸の位置からそれを詠唱します
+    #[serde(default)]
+    pub trigger: Option<BulletTrigger>,
+
+    /// 命中したアクターに付与する状態異常
+    #[serde(default)]
+    pub status_effects: Vec<StatusEffect>,
+
+    /// 爆発の半径
+    /// 0より大きい場合、弾丸が何かに衝突するか寿命が尽きて消滅したときに爆発し、
+    /// 周囲に減衰するダメージと吹き飛ばしを与えます
+    #[serde(default)]
+    pub explosion_radius: f32,
+
+    /// 弾丸が地形に与える効果
+    #[serde(default)]
+    pub terrain: Option<TerrainEffect>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// ビーム系魔法の性能です
+/// ビームは弾丸とは異なり、詠唱者から壁までの間にいる相手へ一定間隔でダメージを与え続けます
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BeamProps {
+    pub slice: String,
+
+    /// ビームの最大の長さ
+    pub range: f32,
+
+    /// 1回あたりのダメージ
+    pub damage: i32,
+
+    #[serde(default)]
+    pub damage_type: DamageType,
+
+    /// ダメージを与える間隔のフレーム数
+    pub interval: u32,
+
+    /// 1回の詠唱でビームが持続するフレーム数
+    /// 詠唱遅延と同じかそれ以上にすると、発射ボタンを押し続けている間は途切れなくなります
+    pub duration: u32,
+
+    pub light_intensity: f32,
+    pub light_radius: f32,
+    pub light_color_hlsa: [f32; 4],
+}
+
+/// 障壁系魔法の性能です
+/// 障壁は詠唱者を中心とした円弧の形をしており、詠唱者の敵の弾丸だけを受け止めます
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BarrierProps {
+    /// 障壁の耐久力
+    /// 弾丸を受け止めるたびにダメージの分だけ減り、ゼロになると壊れます
+    pub life: i32,
+
+    /// 障壁が持続するフレーム数
+    pub duration: u32,
+
+    /// 詠唱者の中心から障壁までの距離
+    pub radius: f32,
+
+    /// 障壁の円弧の角度 (ラジアン)
+    /// 2π で詠唱者を囲む円になります
+    pub arc: f32,
+}
+
+/// 投擲系魔法の性能です
+/// 投げられた弾は地形や衝突を無視して飛び、flight_time フレーム後に着地します
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LobProps {
+    pub slice: String,
+
+    /// 最大の飛距離
+    /// ポインターがこれより遠い場合は、ポインターの方向へこの距離だけ飛びます
+    pub range: f32,
+
+    /// 着地するまでのフレーム数
+    pub flight_time: u32,
+
+    /// 放物線の最も高い位置の高さ
+    pub height: f32,
+
+    /// 着地したときの衝撃波の半径
+    /// 0の場合は衝撃波は発生しません
+    #[serde(default)]
+    pub explosion_radius: f32,
+
+    /// 衝撃波の中心でのダメージ
+    #[serde(default)]
+    pub damage: i32,
+
+    #[serde(default)]
+    pub damage_type: DamageType,
+
+    #[serde(default)]
+    pub impulse: f32,
+}
+
+/// 弾丸に加えられる修飾です
+/// 修飾呪文で詠唱中のアクターに蓄積され、次に発射される弾丸に順に適用されます
+#[derive(Clone, Copy, Debug, Reflect, Serialize, Deserialize)]
+pub enum BulletModifier {
+    /// 弾速を (1 + 値) 倍にします
+    Speed(f32),
+    /// ダメージを加算します
+    Damage(i32),
+    /// ダメージを乗算します
+    DamageMultiply(f32),
+    /// 持続時間をフレーム単位で加算します
+    Lifetime(i32),
+    /// 大きさを乗算します
+    Size(f32),
+    /// 拡散を加算します
+    Scattering(f32),
+    /// ノックバックを加算します
+    Knockback(f32),
+    /// 追尾性能を加算します
+    Homing(f32),
+    /// 壁で反射できる回数を加算します
+    Bounce(u32),
+    /// 貫通できる回数を加算します
+    Pierce(u32),
+    /// 命中時に付与する状態異常を追加します
+    Inflict(StatusEffect),
+}
+
+impl BulletModifier {
+    /// 強化段階による倍率 scale を修飾の効果の大きさに適用します
+    /// 乗算の修飾は1倍からの差分が、そのほかの修飾は値そのものが、有利な向きであれば scale 倍になります
+    /// 不利な向きの値は強化とともに 1/scale 倍に小さくなるため、強化で呪文が弱くなることはありません
+    fn upgrade(&self, scale: f32) -> BulletModifier {
+        match *self {
+            BulletModifier::Speed(delta) => BulletModifier::Speed(scale_benefit(delta, scale)),
+            BulletModifier::Damage(amount) => {
+                BulletModifier::Damage(scale_benefit_i32(amount, scale))
+            }
+            BulletModifier::DamageMultiply(factor) => {
+                BulletModifier::DamageMultiply(scale_factor_benefit(factor, scale))
+            }
+            BulletModifier::Lifetime(frames) => {
+                BulletModifier::Lifetime(scale_benefit_i32(frames, scale))
+            }
+            BulletModifier::Size(factor) => {
+                BulletModifier::Size(scale_factor_benefit(factor, scale))
+            }
+            // 拡散は小さくなるほうが有利です
+            BulletModifier::Scattering(delta) => {
+                BulletModifier::Scattering(-scale_benefit(-delta, scale))
+            }
+            BulletModifier::Knockback(amount) => {
+                BulletModifier::Knockback(scale_benefit(amount, scale))
+            }
+            BulletModifier::Homing(amount) => BulletModifier::Homing(scale_benefit(amount, scale)),
+            BulletModifier::Bounce(amount) => BulletModifier::Bounce(amount),
+            BulletModifier::Pierce(amount) => BulletModifier::Pierce(amount),
+            BulletModifier::Inflict(effect) => BulletModifier::Inflict(StatusEffect {
+                duration: scale_u32(effect.duration, scale),
+                ..effect
+            }),
+        }
+    }
+
+    pub fn apply(&self, bullet: &mut BulletProps) {
+        match *self {
+            BulletModifier::Speed(delta) => {
+                bullet.speed *= (1.0 + delta).max(0.1);
+            }
+            BulletModifier::Damage(amount) => {
+                bullet.damage += amount;
+            }
+            BulletModifier::DamageMultiply(factor) => {
+                bullet.damage = (bullet.damage as f32 * factor).round() as i32;
+            }
+            BulletModifier::Lifetime(frames) => {
+                bullet.lifetime = (bullet.lifetime as i32 + frames).max(1) as u32;
+            }
+            BulletModifier::Size(factor) => {
+                bullet.scale = (bullet.scale * factor).max(0.1);
+            }
+            BulletModifier::Scattering(delta) => {
+                bullet.scattering = (bullet.scattering + delta).max(0.0);
+            }
+            BulletModifier::Knockback(amount) => {
+                bullet.impulse = (bullet.impulse + amount).max(0.0);
+            }
+            BulletModifier::Homing(amount) => {
+                bullet.homing = (bullet.homing + amount).max(-0.1).min(0.1);
+            }
+            BulletModifier::Bounce(amount) => {
+                bullet.bounces += amount;
+            }
+            BulletModifier::Pierce(amount) => {
+                bullet.pierce += amount;
+            }
+            BulletModifier::Inflict(effect) => {
+                bullet.status_effects.push(effect);
+            }
+        }
+    }
+}
+
+/// 弾丸がペイロードを詠唱する条件です
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum BulletTrigger {
+    /// 何かに衝突したとき
+    Hit,
+    /// 寿命が尽きたとき
+    Expire,
+}
+
+/// 弾丸が地形に与える効果です
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum TerrainEffect {
+    /// 壁に命中したとき、その壁を掘って床にします
+    Dig,
+    /// 消滅したとき、その位置の床に壁を立てます
+    Raise,
 }
 
 /// 呪文の基礎情報
+/// 各呪文の値は assets/data/registry.spell.json で定義されており、
+/// 起動時に SpellRegistry として読み込まれます
+#[derive(Clone, Debug)]
 pub struct SpellProps {
     pub name: Dict,
     pub description: Dict,
     pub cast_delay: u32,
+
+    /// 詠唱に必要なマナ
+    /// マナは Actor 内部で10倍の値で保持されているため、表示上は10分の1になります
+    pub mana_cost: u32,
+
     pub icon: &'static str,
     pub price: u32,
     pub cast: SpellCast,
 }
 
 impl SpellType {
-    pub fn to_props(&self) -> SpellProps {
-        match self {
-            SpellType::MagicBolt =>  SpellProps {
-                name: Dict {
-                    ja: "マジックボルト",
-                    en: "Magic Bolt",
-                },
-                description: Dict {
-                    ja: "魔力の塊を発射する、最も基本的な攻撃魔法です。",
-                    en: "A basic attack spell that fires a bolt of magic.",
-                },
-                cast_delay: 20,
-                icon: "bullet_magic_bolt",
-                price: 10,
-                cast: SpellCast::Bullet {
-                    slice: "bullet_magic_bolt",
-                    collier_radius: 5.0,
-                    speed: 100.0,
-                    lifetime: 240,
-                    damage: 8,
-                    impulse: 20000.0,
-                    scattering: 0.4,
-                    light_intensity: 1.0,
-                    light_radius: 50.0,
-                    light_color_hlsa: [245.0, 1.0, 0.6, 1.0],
-                },
-            },
-            SpellType::PurpleBolt =>  SpellProps {
-                name: Dict {
-                    ja: "悪意の視線",
-                    en: "Evil Eye",
-                },
-                description: Dict {
-                    ja:
-                        "邪悪な魔力を帯びた視線です。浴びせられると少し悪寒が走ります。",
-                    en: "Fires a slow-moving purple energy bolt. It is weak but consumes little mana.",
-                },
-                cast_delay: 120,
-                icon: "bullet_purple",
-                price: 5,
-                cast: SpellCast::Bullet {
-                    slice: "bullet_purple",
-                    collier_radius: 5.0,
-                    speed: 50.0,
-                    lifetime: 500,
-                    damage: 3,
-                    impulse: 0.0,
-                    scattering: 0.6,
-                    light_intensity: 0.0,
-                    light_radius: 0.0,
-                    light_color_hlsa: [0.0, 0.0, 0.0, 1.0],
-                },
-            },
-            SpellType::SlimeCharge => SpellProps {
-                name: Dict {
-                    ja: "スライムの塊",
-                    en: "Slime Limp",
-                },
-                description: Dict { 
-                    ja: "ぷにぷにとした塊で殴りつけます。痛くはありませんが、相手を大きく吹き飛ばします。",
-                    en: "Slap with a soft, squishy lump. It doesn't hurt much, but it knocks the opponent backward."
-                },
-                cast_delay: 30,
-                icon: "bullet_slime_charge",
-                price: 15,
-                cast: SpellCast::Bullet {
-                    slice: "bullet_slime_charge",
-                    collier_radius: 5.0,
-                    speed: 2.0,
-                    lifetime: 5,
-                    damage: 1,
-                    impulse: 40000.0,
-                    scattering: 0.0,
-                    light_intensity: 0.0,
-                    light_radius: 0.0,
-                    light_color_hlsa: [0.0, 0.0, 0.0, 1.0],
-                },
-            },
-            SpellType::Heal =>  SpellProps {
-                name: Dict {
-                    ja: "回復",
-                    en: "Heal",
-                },
-                description: Dict { ja: "自分自身の体力を少しだけ回復します。", 
-                en: "Heals a small amount of your own health." },
-                cast_delay: 120,
-                icon: "spell_heal",
-                price: 40,
-                cast: SpellCast::Heal,
-            },
-            SpellType::BulletSpeedUp => SpellProps {
-                name: Dict {
-                    ja: "加速",
-                    en: "Speed Up",
-                },
-                description: Dict { ja: "次に発射する魔法の弾速を50%上昇させます。",
-                en: "Increases the speed of the next magic bullet by 50%." },
-                cast_delay: 0,
-                icon: "bullet_speed_up",
-                price: 50,
-                cast: SpellCast::BulletSpeedUpDown { delta: 0.5 },
-            },
-            SpellType::BulletSpeedDoown =>  SpellProps {
-                name: Dict {
-                    ja: "減速",
-                    en: "Speed Down",
-                },
-                description: Dict { ja: "次に発射する魔法の弾速を50%低下させます。",
-                en: "Reduces the speed of the next magic bullet by 50%." },
-                cast_delay: 0,
-                icon: "bullet_speed_down",
-                price: 50,
-                cast: SpellCast::BulletSpeedUpDown { delta: -0.5 },
-            },
-            SpellType::DualCast => SpellProps {
-                name: Dict {
-                    ja: "並列詠唱",
-                    en: "Dual Cast",
-                },
-                description: Dict { ja: "ふたつの投射物呪文を同時に詠唱します。詠唱遅延は大きいほうに揃えられます。", 
-                en: "Casts two projectile spells at the same time." },
-                cast_delay: 0,
-                icon: "spell_dual_cast",
-                price: 50,
-                cast: SpellCast::MultipleCast { amount: 2 },
-            },
-            SpellType::TripleCast =>  SpellProps {
-                name: Dict {
-                    ja: "三並列詠唱",
-                    en: "Triple Cast",
-                },
-                description: Dict { ja: "みっつの投射物呪文を同時に詠唱します。", 
-                en: "Casts three projectile spells at the same time." },
-                cast_delay: 0,
-                icon: "spell_triple_cast",
-                price:100,
-                cast: SpellCast::MultipleCast { amount: 3 },
-            },
-            SpellType::Homing => SpellProps {
-                name: Dict {
-                    ja: "追尾",
-                    en: "Homing",
-                },
-                description: Dict { ja: "次に発射する魔法弾が近くの敵に向かって追尾します。", 
-                en: "The next magic bullet you fire will home in on the enemy." },
-                cast_delay: 5,
-                icon: "spell_homing",
-                price: 100,
-                cast: SpellCast::Homing,
-            },
-            SpellType::HeavyShot => SpellProps {
-                name: Dict {
-                    ja: "ヘヴィーショット",
-                    en: "Heavy Shot",
-                },
-                description: Dict { ja: "次に発射する魔法弾の威力が上昇しますが、飛翔速度が低下します。",
-                en: "The next magic bullet you fire will be more powerful and slower." },
-                cast_delay: 5,
-                icon: "spell_heavy_shot",
-                price: 80,
-                cast: SpellCast::HeavyShot,
-            },
-            SpellType::SummonFriendSlime => SpellProps {
-                name: Dict {
-                    ja: "味方スライム召喚",
-                    en: "Summon Friend Slime",
-                },
-                description: Dict { ja: "味方のスライムを召喚します。",
-                en: "Summons a friend slime" },
-                cast_delay: 60,
-                icon: "friend_slime_seed",
-                price: 200,
-                cast: SpellCast::SummonSlime { friend: true },
-            },
-            SpellType::SummonEnemySlime => SpellProps {
-                name: Dict {
-                    ja: "敵スライム召喚",
-                    en: "Summon Enemy Slime",
-                },
-                description: Dict { ja: "敵のスライムを召喚します。",
-                en: "Summons a enemy slime" },
-                cast_delay: 60,
-                icon: "slime_seed",
-                price: 200,
-                cast: SpellCast::SummonSlime { friend:false },
-            },
-            SpellType::Dash => SpellProps {
-                name: Dict {
-                    ja: "ダッシュ",
-                    en: "Dash",
-                },
-                description: Dict { ja: "短距離を素早く走ります。",
-                en: "Dashes a short distance." },
-                cast_delay: 60,
-                icon: "dash",
-                price: 500,
-                cast: SpellCast::Dash,
-            },
-        }
+    /// 呪文の性能を返します
+    /// 読み込み時にゲームのコードから参照される呪文がすべて定義されていることと、
+    /// インベントリや杖の呪文が定義ファイルから作られていることを前提としています
+    /// 他のプレイヤーから受け取った呪文には、代わりに SpellRegistry::get を使います
+    pub fn to_props<'a>(&self, registry: &'a SpellRegistry) -> &'a SpellProps {
+        registry
+            .get(*self)
+            .unwrap_or_else(|| panic!("spell {:?} is not defined", self))
     }
-}   
-
+}
 
 const DAMAGE: Dict = Dict {
     ja: "ダメージ",
@@ -266,6 +445,11 @@ const SPEED: Dict = Dict {
     en: "Speed",
 };
 
+const DURABILITY: Dict = Dict {
+    ja: "耐久力",
+    en: "Durability",
+};
+
 const LIFETIME: Dict = Dict {
     ja: "持続時間",
     en: "Lifetime",
@@ -285,23 +469,107 @@ const HEAL_TEXT: Dict = Dict {
     ja: "回復",
     en: "Heal",
 };
- 
-pub fn get_spell_appendix(cast: SpellCast, language: Languages) -> String {
+
+const HOMING: Dict = Dict {
+    ja: "追尾",
+    en: "Homing",
+};
+
+const BOUNCE: Dict = Dict {
+    ja: "反射",
+    en: "Bounce",
+};
+
+const PIERCE: Dict = Dict {
+    ja: "貫通",
+    en: "Pierce",
+};
+
+const ELEMENT: Dict = Dict {
+    ja: "属性",
+    en: "Element",
+};
+
+const EXPLOSION: Dict = Dict {
+    ja: "爆発範囲",
+    en: "Blast Radius",
+};
+
+const RANGE: Dict = Dict {
+    ja: "射程",
+    en: "Range",
+};
+
+const INTERVAL: Dict = Dict {
+    ja: "間隔",
+    en: "Interval",
+};
+
+const STATUS_EFFECT: Dict = Dict {
+    ja: "効果",
+    en: "Effect",
+};
+
+const SUMMON: Dict = Dict {
+    ja: "召喚",
+    en: "Summon",
+};
+
+const TERRAIN_DIG: Dict = Dict {
+    ja: "壁を掘る",
+    en: "Digs walls",
+};
+
+const TERRAIN_RAISE: Dict = Dict {
+    ja: "壁を立てる",
+    en: "Raises a wall",
+};
+
+const FLIGHT_TIME: Dict = Dict {
+    ja: "滞空時間",
+    en: "Flight Time",
+};
+
+const TRIGGER: Dict = Dict {
+    ja: "トリガー",
+    en: "Trigger",
+};
+
+const TRIGGER_HIT: Dict = Dict {
+    ja: "命中時",
+    en: "On Hit",
+};
+
+const TRIGGER_EXPIRE: Dict = Dict {
+    ja: "消滅時",
+    en: "On Expire",
+};
+
+pub fn get_spell_appendix(cast: &SpellCast, language: Languages) -> String {
     match cast {
-        SpellCast::Bullet {
+        SpellCast::Bullet(BulletProps {
             slice: _,
             collier_radius,
             speed,
             lifetime,
             damage,
+            damage_type,
             impulse,
             scattering,
             light_intensity: _,
             light_radius: _,
             light_color_hlsa: _,
-        } => {
-            format!(
-                "{}:{}  {}:{}\n{}:{}  {}:{}\n{}:{}  {}:{}",
+            homing: _,
+            scale: _,
+            bounces,
+            pierce,
+            trigger,
+            status_effects,
+            explosion_radius,
+            terrain,
+        }) => {
+            let mut appendix = format!(
+                "{}:{}  {}:{}\n{}:{}  {}:{}\n{}:{}  {}:{}\n{}:{}",
                 DAMAGE.get(language),
                 damage,
                 KNOCKBACK.get(language),
@@ -314,16 +582,277 @@ pub fn get_spell_appendix(cast: SpellCast, language: Languages) -> String {
                 scattering,
                 SIZE.get(language),
                 collier_radius,
-            )
+                ELEMENT.get(language),
+                damage_type.to_dict().get(language),
+            );
+            if 0 < *bounces {
+                appendix += &format!("\n{}:{}", BOUNCE.get(language), bounces);
+            }
+            if 0 < *pierce {
+                appendix += &format!("\n{}:{}", PIERCE.get(language), pierce);
+            }
+            if 0.0 < *explosion_radius {
+                appendix += &format!("\n{}:{}", EXPLOSION.get(language), explosion_radius);
+            }
+            if let Some(trigger) = trigger {
+                appendix += &format!(
+                    "\n{}:{}",
+                    TRIGGER.get(language),
+                    match trigger {
+                        BulletTrigger::Hit => TRIGGER_HIT.get(language),
+                        BulletTrigger::Expire => TRIGGER_EXPIRE.get(language),
+                    }
+                );
+            }
+            if let Some(terrain) = terrain {
+                appendix += &format!(
+                    "\n{}",
+                    match terrain {
+                        TerrainEffect::Dig => TERRAIN_DIG.get(language),
+                        TerrainEffect::Raise => TERRAIN_RAISE.get(language),
+                    }
+                );
+            }
+            for effect in status_effects.iter() {
+                appendix += &format!("\n{}", get_status_effect_text(effect, language));
+            }
+            appendix
         }
         SpellCast::Heal => {
             format!("{}:{}", HEAL_TEXT.get(language), 10)
         }
-        SpellCast::BulletSpeedUpDown { delta: _ } => format!(""),
+        SpellCast::Modifier(modifiers) => modifiers
+            .iter()
+            .map(|modifier| get_modifier_text(modifier, language))
+            .collect::<Vec<String>>()
+            .join("\n"),
         SpellCast::MultipleCast { amount: _ } => format!(""),
-        SpellCast::Homing => format!(""),
-        SpellCast::HeavyShot => format!("威力: +5"),
-        SpellCast::SummonSlime {..} => format!(""),
-        SpellCast::Dash {..} => format!(""),
+        SpellCast::Summon {
+            summon_type,
+            friend: _,
+            duration,
+        } => format!(
+            "{}:{}  {}:{}s",
+            SUMMON.get(language),
+            summon_type.to_dict().get(language),
+            LIFETIME.get(language),
+            *duration as f32 / 60.0
+        ),
+        SpellCast::Dash { .. } => format!(""),
+        SpellCast::Teleport { range } => format!("{}:{}", RANGE.get(language), range),
+        SpellCast::SelfEffect(effect) => get_status_effect_text(effect, language),
+        SpellCast::Beam(BeamProps {
+            slice: _,
+            range,
+            damage,
+            damage_type,
+            interval,
+            duration: _,
+            light_intensity: _,
+            light_radius: _,
+            light_color_hlsa: _,
+        }) => format!(
+            "{}:{}  {}:{}\n{}:{}  {}:{}",
+            DAMAGE.get(language),
+            damage,
+            INTERVAL.get(language),
+            interval,
+            RANGE.get(language),
+            range,
+            ELEMENT.get(language),
+            damage_type.to_dict().get(language),
+        ),
+        SpellCast::Lob(LobProps {
+            slice: _,
+            range,
+            flight_time,
+            height: _,
+            explosion_radius,
+            damage,
+            damage_type,
+            impulse,
+        }) => {
+            let mut appendix = format!(
+                "{}:{}  {}:{}s",
+                RANGE.get(language),
+                range,
+                FLIGHT_TIME.get(language),
+                *flight_time as f32 / 60.0
+            );
+            if 0.0 < *explosion_radius {
+                appendix += &format!(
+                    "\n{}:{}  {}:{}\n{}:{}  {}:{}",
+                    DAMAGE.get(language),
+                    damage,
+                    KNOCKBACK.get(language),
+                    impulse * 0.001,
+                    EXPLOSION.get(language),
+                    explosion_radius,
+                    ELEMENT.get(language),
+                    damage_type.to_dict().get(language),
+                );
+            }
+            appendix
+        }
+        SpellCast::Barrier(BarrierProps {
+            life,
+            duration,
+            radius: _,
+            arc: _,
+        }) => format!(
+            "{}:{}  {}:{}s",
+            DURABILITY.get(language),
+            life,
+            LIFETIME.get(language),
+            *duration as f32 / 60.0
+        ),
+    }
+}
+
+fn get_status_effect_text(effect: &StatusEffect, language: Languages) -> String {
+    format!(
+        "{}:{} {}s",
+        STATUS_EFFECT.get(language),
+        effect.effect_type.to_dict().get(language),
+        effect.duration as f32 / 60.0
+    )
+}
+
+pub fn get_modifier_text(modifier: &BulletModifier, language: Languages) -> String {
+    match *modifier {
+        BulletModifier::Speed(delta) => {
+            format!("{}:{:+}%", SPEED.get(language), (delta * 100.0).round())
+        }
+        BulletModifier::Damage(amount) => format!("{}:{:+}", DAMAGE.get(language), amount),
+        BulletModifier::DamageMultiply(factor) => {
+            format!("{}:x{}", DAMAGE.get(language), factor)
+        }
+        BulletModifier::Lifetime(frames) => format!("{}:{:+}", LIFETIME.get(language), frames),
+        BulletModifier::Size(factor) => format!("{}:x{}", SIZE.get(language), factor),
+        BulletModifier::Scattering(delta) => {
+            format!("{}:{:+}", SCATTERING.get(language), delta)
+        }
+        BulletModifier::Knockback(amount) => {
+            format!("{}:{:+}", KNOCKBACK.get(language), amount * 0.001)
+        }
+        BulletModifier::Homing(amount) => format!("{}:{:+}", HOMING.get(language), amount),
+        BulletModifier::Bounce(amount) => format!("{}:{:+}", BOUNCE.get(language), amount),
+        BulletModifier::Pierce(amount) => format!("{}:{:+}", PIERCE.get(language), amount),
+        BulletModifier::Inflict(effect) => get_status_effect_text(&effect, language),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgraded(modifiers: Vec<BulletModifier>, tier: u32) -> Vec<BulletModifier> {
+        match SpellCast::Modifier(modifiers).upgrade(tier) {
+            SpellCast::Modifier(modifiers) => modifiers,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn upgrade_weakens_speed_penalty() {
+        // ビッグショットと同じく、大きくするかわりに弾速が落ちる修飾です
+        let modifiers = upgraded(
+            vec![BulletModifier::Size(2.0), BulletModifier::Speed(-0.3)],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::Size(factor) if 2.0 < factor));
+        assert!(
+            matches!(modifiers[1], BulletModifier::Speed(delta) if -0.3 < delta && delta < 0.0)
+        );
+    }
+
+    #[test]
+    fn upgrade_strengthens_scattering_reduction() {
+        let modifiers = upgraded(
+            vec![
+                BulletModifier::Scattering(-0.4),
+                BulletModifier::Scattering(0.4),
+            ],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::Scattering(delta) if delta < -0.4));
+        assert!(
+            matches!(modifiers[1], BulletModifier::Scattering(delta) if 0.0 < delta && delta < 0.4)
+        );
+    }
+
+    #[test]
+    fn upgrade_weakens_damage_penalty() {
+        let modifiers = upgraded(
+            vec![BulletModifier::Damage(10), BulletModifier::Damage(-10)],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::Damage(amount) if 10 < amount));
+        assert!(
+            matches!(modifiers[1], BulletModifier::Damage(amount) if -10 < amount && amount < 0)
+        );
+    }
+
+    #[test]
+    fn upgrade_weakens_damage_multiply_penalty() {
+        let modifiers = upgraded(
+            vec![
+                BulletModifier::DamageMultiply(1.5),
+                BulletModifier::DamageMultiply(0.5),
+            ],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::DamageMultiply(factor) if 1.5 < factor));
+        assert!(
+            matches!(modifiers[1], BulletModifier::DamageMultiply(factor) if 0.5 < factor && factor < 1.0)
+        );
+    }
+
+    #[test]
+    fn upgrade_weakens_lifetime_penalty() {
+        let modifiers = upgraded(
+            vec![BulletModifier::Lifetime(60), BulletModifier::Lifetime(-60)],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::Lifetime(frames) if 60 < frames));
+        assert!(
+            matches!(modifiers[1], BulletModifier::Lifetime(frames) if -60 < frames && frames < 0)
+        );
+    }
+
+    #[test]
+    fn upgrade_weakens_size_penalty() {
+        let modifiers = upgraded(vec![BulletModifier::Size(0.5)], 2);
+
+        assert!(
+            matches!(modifiers[0], BulletModifier::Size(factor) if 0.5 < factor && factor < 1.0)
+        );
+    }
+
+    #[test]
+    fn upgrade_weakens_knockback_and_homing_penalty() {
+        let modifiers = upgraded(
+            vec![
+                BulletModifier::Knockback(20.0),
+                BulletModifier::Knockback(-20.0),
+                BulletModifier::Homing(0.1),
+                BulletModifier::Homing(-0.1),
+            ],
+            2,
+        );
+
+        assert!(matches!(modifiers[0], BulletModifier::Knockback(amount) if 20.0 < amount));
+        assert!(
+            matches!(modifiers[1], BulletModifier::Knockback(amount) if -20.0 < amount && amount < 0.0)
+        );
+        assert!(matches!(modifiers[2], BulletModifier::Homing(amount) if 0.1 < amount));
+        assert!(
+            matches!(modifiers[3], BulletModifier::Homing(amount) if -0.1 < amount && amount < 0.0)
+        );
     }
 }
@@ -0,0 +1,466 @@
+use crate::{
+    asset::GameAssets,
+    language::{intern, Dict},
+    spell::{SpellType, BUILTIN_SPELL_TYPES},
+    spell_props::{SpellCast, SpellProps},
+    states::GameState,
+};
+use bevy::{
+    asset::{io::Reader, AssetLoader, LoadContext},
+    prelude::*,
+    reflect::TypePath,
+};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::fmt;
+
+/// 定義ファイル内の多言語テキストです
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpellText {
+    ja: String,
+    en: String,
+}
+
+/// 定義ファイル内の呪文ひとつぶんの記述です
+/// 綴りを誤ったフィールドが黙って無視されないよう、知らないフィールドはエラーになります
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SpellDefinition {
+    #[serde(deserialize_with = "deserialize_new_spell_type")]
+    spell_type: SpellType,
+    name: SpellText,
+    description: SpellText,
+    cast_delay: u32,
+    mana_cost: u32,
+    icon: String,
+    price: u32,
+    cast: SpellCast,
+}
+
+/// 呪文定義ファイル (*.spell.json) から読み込まれた、すべての呪文の性能の一覧です
+/// 読み込み完了後はリソースとしても登録されるため、システムからは Res<SpellRegistry> で参照できます
+#[derive(Asset, TypePath, Resource, Clone, Debug)]
+pub struct SpellRegistry {
+    /// 定義ファイルに記述された順の呪文の一覧
+    /// ランダムな呪文の選択などに使います
+    spell_types: Vec<SpellType>,
+    props: HashMap<SpellType, SpellProps>,
+}
+
+impl SpellRegistry {
+    /// 指定した呪文の性能を返します
+    /// 他のプレイヤーから受け取った呪文は、異なる定義ファイルに由来する未知の呪文である場合があり、
+    /// その場合は None を返します
+    pub fn get(&self, spell_type: SpellType) -> Option<&SpellProps> {
+        self.props.get(&spell_type)
+    }
+
+    /// 指定した呪文が定義ファイルに記述されているかどうかを返します
+    pub fn contains(&self, spell_type: SpellType) -> bool {
+        self.props.contains_key(&spell_type)
+    }
+
+    pub fn spell_types(&self) -> &Vec<SpellType> {
+        &self.spell_types
+    }
+
+    /// 定義ファイルの文字列を解析し、内容を検証します
+    pub fn from_json(json: &str) -> Result<Self, SpellRegistryError> {
+        let definitions: Vec<SpellDefinition> = serde_json::from_str(json)?;
+
+        let mut spell_types = Vec::new();
+        let mut props = HashMap::new();
+
+        for (index, definition) in definitions.into_iter().enumerate() {
+            if props.contains_key(&definition.spell_type) {
+                return Err(SpellRegistryError::Duplicated(definition.spell_type));
+            }
+
+            validate_definition(index, &definition)?;
+
+            spell_types.push(definition.spell_type);
+            props.insert(
+                definition.spell_type,
+                SpellProps {
+                    name: intern_text(&definition.name),
+                    description: intern_text(&definition.description),
+                    cast_delay: definition.cast_delay,
+                    mana_cost: definition.mana_cost,
+                    icon: intern(&definition.icon),
+                    price: definition.price,
+                    cast: definition.cast,
+                },
+            );
+        }
+
+        for spell_type in BUILTIN_SPELL_TYPES {
+            if !props.contains_key(&spell_type) {
+                return Err(SpellRegistryError::Missing(spell_type));
+            }
+        }
+
+        Ok(SpellRegistry { spell_types, props })
+    }
+}
+
+/// 定義ファイルの spell_type は新しい呪文の識別子であるため、既知の呪文に限らず受け付けます
+fn deserialize_new_spell_type<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SpellType, D::Error> {
+    let id = String::deserialize(deserializer)?;
+    Ok(SpellType::new(&id))
+}
+
+fn intern_text(text: &SpellText) -> Dict {
+    Dict {
+        ja: intern(&text.ja),
+        en: intern(&text.en),
+    }
+}
+
+fn validate_definition(
+    index: usize,
+    definition: &SpellDefinition,
+) -> Result<(), SpellRegistryError> {
+    let invalid = |message: &str| {
+        Err(SpellRegistryError::Invalid {
+            index,
+            spell_type: definition.spell_type,
+            message: message.to_string(),
+        })
+    };
+
+    if definition.spell_type.id().is_empty() {
+        return invalid("spell_type is empty");
+    }
+
+    if definition.icon.is_empty() {
+        return invalid("icon is empty");
+    }
+
+    match &definition.cast {
+        SpellCast::Bullet(bullet) => {
+            if bullet.slice.is_empty() {
+                return invalid("slice is empty");
+            }
+            if bullet.collier_radius <= 0.0 {
+                return invalid("collier_radius must be positive");
+            }
+            if bullet.speed <= 0.0 {
+                return invalid("speed must be positive");
+            }
+            if bullet.lifetime == 0 {
+                return invalid("lifetime must be positive");
+            }
+            if bullet.scattering < 0.0 {
+                return invalid("scattering must not be negative");
+            }
+            if bullet.scale <= 0.0 {
+                return invalid("scale must be positive");
+            }
+            if bullet.explosion_radius < 0.0 {
+                return invalid("explosion_radius must not be negative");
+            }
+            if bullet.status_effects.iter().any(|e| e.duration == 0) {
+                return invalid("status effect duration must be positive");
+            }
+        }
+        SpellCast::Beam(beam) => {
+            if beam.slice.is_empty() {
+                return invalid("slice is empty");
+            }
+            if beam.range <= 0.0 {
+                return invalid("range must be positive");
+            }
+            if beam.interval == 0 {
+                return invalid("interval must be positive");
+            }
+            if beam.duration == 0 {
+                return invalid("duration must be positive");
+            }
+        }
+        SpellCast::Lob(lob) => {
+            if lob.slice.is_empty() {
+                return invalid("slice is empty");
+            }
+            if lob.range <= 0.0 {
+                return invalid("range must be positive");
+            }
+            if lob.flight_time == 0 {
+                return invalid("flight_time must be positive");
+            }
+            if lob.height < 0.0 {
+                return invalid("height must not be negative");
+            }
+            if lob.explosion_radius < 0.0 {
+                return invalid("explosion_radius must not be negative");
+            }
+        }
+        SpellCast::Barrier(barrier) => {
+            if barrier.life <= 0 {
+                return invalid("life must be positive");
+            }
+            if barrier.duration == 0 {
+                return invalid("duration must be positive");
+            }
+            if barrier.radius <= 0.0 {
+                return invalid("radius must be positive");
+            }
+            if barrier.arc <= 0.0 || std::f32::consts::TAU < barrier.arc {
+                return invalid("arc must be between 0 and 2π");
+            }
+        }
+        SpellCast::SelfEffect(effect) if effect.duration == 0 => {
+            return invalid("status effect duration must be positive");
+        }
+        SpellCast::Modifier(modifiers) if modifiers.is_empty() => {
+            return invalid("modifiers are empty");
+        }
+        SpellCast::MultipleCast { amount } if *amount == 0 => {
+            return invalid("amount must be positive");
+        }
+        SpellCast::Summon { duration, .. } if *duration == 0 => {
+            return invalid("duration must be positive");
+        }
+        SpellCast::Teleport { range } if *range <= 0.0 => {
+            return invalid("range must be positive");
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SpellRegistryError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    Duplicated(SpellType),
+    Missing(SpellType),
+    Invalid {
+        index: usize,
+        spell_type: SpellType,
+        message: String,
+    },
+}
+
+impl fmt::Display for SpellRegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpellRegistryError::Io(err) => write!(f, "failed to read spell definitions: {}", err),
+            SpellRegistryError::Parse(err) => {
+                write!(f, "failed to parse spell definitions: {}", err)
+            }
+            SpellRegistryError::Duplicated(spell_type) => {
+                write!(f, "spell {:?} is defined more than once", spell_type)
+            }
+            SpellRegistryError::Missing(spell_type) => {
+                write!(f, "spell {:?} is not defined", spell_type)
+            }
+            SpellRegistryError::Invalid {
+                index,
+                spell_type,
+                message,
+            } => write!(
+                f,
+                "invalid spell definition at index {} ({:?}): {}",
+                index, spell_type, message
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpellRegistryError {}
+
+impl From<std::io::Error> for SpellRegistryError {
+    fn from(err: std::io::Error) -> Self {
+        SpellRegistryError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SpellRegistryError {
+    fn from(err: serde_json::Error) -> Self {
+        SpellRegistryError::Parse(err)
+    }
+}
+
+#[derive(Default)]
+struct SpellRegistryLoader;
+
+impl AssetLoader for SpellRegistryLoader {
+    type Asset = SpellRegistry;
+    type Settings = ();
+    type Error = SpellRegistryError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let json = String::from_utf8_lossy(&bytes);
+        SpellRegistry::from_json(&json)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["spell.json"]
+    }
+}
+
+/// 読み込みが完了した定義ファイルをリソースとして登録します
+/// 定義ファイルの読み込みに失敗した場合は GameAssets が登録されないまま LoadError へ遷移するため、何もしません
+fn insert_spell_registry(
+    mut commands: Commands,
+    assets: Option<Res<GameAssets>>,
+    registries: Res<Assets<SpellRegistry>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+    match registries.get(&assets.spells) {
+        Some(registry) => commands.insert_resource(registry.clone()),
+        None => error!("spell registry is not loaded"),
+    }
+}
+
+pub struct SpellRegistryPlugin;
+
+impl Plugin for SpellRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SpellRegistry>();
+        app.init_asset_loader::<SpellRegistryLoader>();
+        app.add_systems(OnExit(GameState::Setup), insert_spell_registry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::summon::SummonType;
+    use serde_json::Value;
+
+    fn definitions() -> Vec<Value> {
+        serde_json::from_str(include_str!("../assets/data/registry.spell.json")).unwrap()
+    }
+
+    fn load(definitions: &[Value]) -> Result<SpellRegistry, SpellRegistryError> {
+        SpellRegistry::from_json(&serde_json::to_string(definitions).unwrap())
+    }
+
+    fn find(definitions: &[Value], spell_type: SpellType) -> usize {
+        definitions
+            .iter()
+            .position(|d| d["spell_type"] == spell_type.id())
+            .unwrap()
+    }
+
+    #[test]
+    fn bundled_definitions_are_valid() {
+        let registry = load(&definitions()).unwrap();
+        for spell_type in BUILTIN_SPELL_TYPES {
+            assert!(registry.contains(spell_type));
+        }
+    }
+
+    #[test]
+    fn every_summon_type_can_be_cast() {
+        let registry = load(&definitions()).unwrap();
+        for summon_type in [SummonType::Slime, SummonType::Eyeball, SummonType::Witch] {
+            assert!(registry.spell_types().iter().any(|spell_type| matches!(
+                registry.get(*spell_type).unwrap().cast,
+                SpellCast::Summon { summon_type: s, .. } if s == summon_type
+            )));
+        }
+    }
+
+    #[test]
+    fn unknown_field_is_rejected() {
+        let mut definitions = definitions();
+        let index = find(&definitions, SpellType::MagicBolt);
+        definitions[index]["mana_cots"] = Value::from(10);
+
+        assert!(matches!(
+            load(&definitions),
+            Err(SpellRegistryError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn duplicated_spell_is_rejected() {
+        let mut definitions = definitions();
+        let index = find(&definitions, SpellType::Heal);
+        definitions.push(definitions[index].clone());
+
+        assert!(matches!(
+            load(&definitions),
+            Err(SpellRegistryError::Duplicated(spell_type)) if spell_type == SpellType::Heal
+        ));
+    }
+
+    #[test]
+    fn missing_builtin_spell_is_rejected() {
+        let mut definitions = definitions();
+        let index = find(&definitions, SpellType::Heal);
+        definitions.remove(index);
+
+        assert!(matches!(
+            load(&definitions),
+            Err(SpellRegistryError::Missing(spell_type)) if spell_type == SpellType::Heal
+        ));
+    }
+
+    #[test]
+    fn spell_can_be_added_only_in_definitions() {
+        let mut definitions = definitions();
+        let index = find(&definitions, SpellType::MagicBolt);
+        let mut definition = definitions[index].clone();
+        definition["spell_type"] = Value::from("TestBolt");
+        definitions.push(definition);
+
+        let registry = load(&definitions).unwrap();
+        let spell_type = SpellType::find("TestBolt").unwrap();
+        assert!(registry.contains(spell_type));
+        assert_eq!(registry.spell_types().last(), Some(&spell_type));
+    }
+
+    #[test]
+    fn unknown_spell_fails_to_deserialize() {
+        load(&definitions()).unwrap();
+        assert!(serde_json::from_str::<SpellType>("\"UnknownRemoteBolt\"").is_err());
+        assert_eq!(SpellType::find("UnknownRemoteBolt"), None);
+        assert_eq!(
+            serde_json::from_str::<SpellType>("\"MagicBolt\"").unwrap(),
+            SpellType::MagicBolt
+        );
+    }
+
+    #[test]
+    fn unknown_spell_has_no_props() {
+        let registry = load(&definitions()).unwrap();
+        let mut definitions = definitions();
+        let index = find(&definitions, SpellType::MagicBolt);
+        let mut definition = definitions[index].clone();
+        definition["spell_type"] = Value::from("RemovedBolt");
+        definitions.push(definition);
+        let removed = load(&definitions)
+            .unwrap()
+            .spell_types()
+            .last()
+            .copied()
+            .unwrap();
+
+        assert!(registry.get(removed).is_none());
+    }
+
+    #[test]
+    fn reloading_reuses_strings() {
+        let a = load(&definitions()).unwrap();
+        let b = load(&definitions()).unwrap();
+        let name_a = a.get(SpellType::MagicBolt).unwrap().name.en;
+        let name_b = b.get(SpellType::MagicBolt).unwrap().name.en;
+        assert!(std::ptr::eq(name_a, name_b));
+    }
+}
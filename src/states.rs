@@ -31,6 +31,10 @@ pub enum GameState {
 
     // エンディング画面
     Ending,
+
+    /// アセットの読み込みに失敗したことを表示する画面
+    /// 呪文の定義ファイルに誤りがある場合などにここへ遷移し、ゲームは開始しません
+    LoadError,
     //
     // 画面を追加したら OverlayPlugin や update_pointer_image_by_angle にも変更が必要
 }
@@ -61,6 +61,8 @@ fn update_equipment_sprite(
                 _ => actor.equipments[sprite.index].map(|e| InventoryItem {
                     item_type: InventoryItemType::Equipment(e.equipment_type),
                     price: e.price,
+                    tier: 0,
+                    wand_stats: None,
                 }),
             };
         }
@@ -3,14 +3,19 @@ use crate::{
     asset::GameAssets,
     constant::{MAX_SPELLS_IN_WAND, WAND_EDITOR_FLOATING_Z_INDEX},
     controller::player::{Equipment, Player},
-    entity::{actor::Actor, dropped_item::spawn_dropped_item},
+    entity::{
+        actor::Actor,
+        dropped_item::{spawn_dropped_item, spawn_dropped_wand},
+    },
     hud::DropArea,
     inventory::InventoryItem,
     inventory_item::InventoryItemType,
-    level::{tile::Tile, CurrentLevel},
+    level::CurrentLevel,
+    random::GameRng,
     se::{SEEvent, SE},
+    spell_registry::SpellRegistry,
     states::{GameMenuState, GameState},
-    wand::{Wand, WandSpell},
+    wand::{Wand, WandSpell, SEQUENTIAL_ORDER},
 };
 use bevy::{prelude::*, window::PrimaryWindow};
 
@@ -32,6 +37,8 @@ impl FloatingContent {
                     Some(spell) => Some(InventoryItem {
                         item_type: InventoryItemType::Spell(spell.spell_type),
                         price: spell.price,
+                        tier: spell.tier,
+                        wand_stats: None,
                     }),
                     None => None,
                 }),
@@ -40,6 +47,8 @@ impl FloatingContent {
                     Some(InventoryItem {
                         item_type: InventoryItemType::Wand(wand.wand_type),
                         price: wand.price,
+                        tier: 0,
+                        wand_stats: Some(wand.stats),
                     })
                 })
             }
@@ -47,6 +56,8 @@ impl FloatingContent {
                 actor.equipments[*index].clone().map(|ref e| InventoryItem {
                     item_type: InventoryItemType::Equipment(e.equipment_type),
                     price: e.price,
+                    tier: 0,
+                    wand_stats: None,
                 })
             }
         }
@@ -140,10 +151,12 @@ fn drop(
     drop_query: Query<&DropArea>,
     mut commands: Commands,
     assets: Res<GameAssets>,
+    registry: Res<SpellRegistry>,
     q_window: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), (With<Camera2d>, Without<Player>)>,
     map: Res<CurrentLevel>,
     mut se: EventWriter<SEEvent>,
+    mut rng: ResMut<GameRng>,
 ) {
     let mut floating = floating_query.single_mut();
     if mouse.just_released(MouseButton::Left) {
@@ -172,19 +185,36 @@ fn drop(
                                     ) {
                                         let pointer_in_world = mouse_in_world.origin.truncate();
 
-                                        if chunk.get_tile_by_coords(pointer_in_world)
-                                            == Tile::StoneTile
-                                        {
+                                        if chunk.get_tile_by_coords(pointer_in_world).is_floor() {
                                             if let Some(item) = content.get_inventory_item(&actor) {
                                                 let spells = content.get_wand_spells(&actor);
+                                                // 杖の枠から落とした杖は、性能と呪文を保ったまま床に置きます
+                                                let wand = match content {
+                                                    FloatingContent::Wand(w) => {
+                                                        actor.wands[w].clone()
+                                                    }
+                                                    _ => None,
+                                                };
                                                 content.set_item(None, &spells, &mut actor, false);
 
-                                                spawn_dropped_item(
-                                                    &mut commands,
-                                                    &assets,
-                                                    pointer_in_world,
-                                                    item,
-                                                );
+                                                match wand {
+                                                    Some(wand) => spawn_dropped_wand(
+                                                        &mut commands,
+                                                        &assets,
+                                                        &registry,
+                                                        &mut rng,
+                                                        pointer_in_world,
+                                                        wand,
+                                                    ),
+                                                    None => spawn_dropped_item(
+                                                        &mut commands,
+                                                        &assets,
+                                                        &registry,
+                                                        &mut rng,
+                                                        pointer_in_world,
+                                                        item,
+                                                    ),
+                                                }
                                                 floating.content = None;
 
                                                 se.send(SEEvent::new(SE::PickUp));
@@ -205,6 +235,18 @@ fn drop(
 
                     let spells_to = target.get_wand_spells(&actor);
 
+                    // 同じ段階の同じ呪文に重ねた場合は、ひとつにまとめて強化します
+                    if content != target {
+                        if let (Some(from), Some(to)) = (item_optional_from, item_optional_to) {
+                            if let Some(merged) = from.merge(&to) {
+                                content.set_item(None, &spells_from, &mut actor, false);
+                                target.set_item(Some(merged), &spells_to, &mut actor, false);
+                                se.send(SEEvent::new(SE::TurnOn));
+                                return;
+                            }
+                        }
+                    }
+
                     // 移動先に書きこみ
                     let ok_target =
                         target.set_item(item_optional_from, &spells_from, &mut actor, true);
@@ -231,12 +273,16 @@ impl FloatingContent {
             FloatingContent::WandSpell(w, i) => actor.get_spell(*w, *i).map(|w| InventoryItem {
                 item_type: InventoryItemType::Spell(w.spell_type),
                 price: w.price,
+                tier: w.tier,
+                wand_stats: None,
             }),
             FloatingContent::Wand(w) => {
                 if let Some(ref wand) = actor.wands[*w] {
                     Some(InventoryItem {
                         item_type: InventoryItemType::Wand(wand.wand_type),
                         price: wand.price,
+                        tier: 0,
+                        wand_stats: Some(wand.stats),
                     })
                 } else {
                     None
@@ -245,6 +291,8 @@ impl FloatingContent {
             FloatingContent::Equipment(e) => actor.equipments[*e].map(|e| InventoryItem {
                 item_type: InventoryItemType::Equipment(e.equipment_type),
                 price: e.price,
+                tier: 0,
+                wand_stats: None,
             }),
         }
     }
@@ -278,6 +326,8 @@ impl FloatingContent {
                             actor.inventory.insert(InventoryItem {
                                 item_type: InventoryItemType::Spell(spell.spell_type),
                                 price: spell.price,
+                                tier: spell.tier,
+                                wand_stats: None,
                             });
                         }
                     }
@@ -289,6 +339,8 @@ impl FloatingContent {
                 Some(InventoryItem {
                     item_type: InventoryItemType::Wand(wand_type),
                     price,
+                    wand_stats,
+                    ..
                 }),
             ) => {
                 if !dry_run {
@@ -298,6 +350,9 @@ impl FloatingContent {
                         slots: *slots,
                         index: 0,
                         delay: 0,
+                        stats: wand_stats.unwrap_or(wand_type.to_props().stats),
+                        order: SEQUENTIAL_ORDER,
+                        always_cast: wand_type.to_props().always_cast.to_vec(),
                     });
                 }
                 true
@@ -313,11 +368,17 @@ impl FloatingContent {
                 Some(InventoryItem {
                     item_type: InventoryItemType::Spell(spell_type),
                     price,
+                    tier,
+                    ..
                 }),
             ) => {
                 if !dry_run {
                     if let Some(ref mut wand) = actor.wands[*w] {
-                        wand.slots[*s] = Some(WandSpell { spell_type, price });
+                        wand.slots[*s] = Some(WandSpell {
+                            spell_type,
+                            price,
+                            tier,
+                        });
                     }
                 }
                 true
@@ -335,6 +396,7 @@ impl FloatingContent {
                 Some(InventoryItem {
                     item_type: InventoryItemType::Equipment(equipment),
                     price,
+                    ..
                 }),
             ) => {
                 if !dry_run {
@@ -385,3 +447,90 @@ impl Plugin for InventoryItemFloatingPlugin {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant::{MAX_ITEMS_IN_EQUIPMENT, MAX_SPELLS_IN_WAND, MAX_WANDS},
+        entity::actor::{ActorFireState, ActorGroup},
+        inventory::Inventory,
+        spell::SpellType,
+        wand::WandType,
+        wand_props::WandStats,
+    };
+    use uuid::Uuid;
+
+    fn actor(wands: [Option<Wand>; MAX_WANDS]) -> Actor {
+        Actor {
+            uuid: Uuid::new_v4(),
+            pointer: Vec2::ZERO,
+            intensity: 0.0,
+            move_direction: Vec2::ZERO,
+            move_force: 0.0,
+            fire_state: ActorFireState::Idle,
+            fire_state_secondary: ActorFireState::Idle,
+            current_wand: 0,
+            effects: default(),
+            actor_group: ActorGroup::Player,
+            golds: 0,
+            mana: 1000,
+            max_mana: 1000,
+            mana_regen: 10,
+            inventory: Inventory::new(),
+            equipments: [None; MAX_ITEMS_IN_EQUIPMENT],
+            wands,
+        }
+    }
+
+    fn move_item(actor: &mut Actor, from: FloatingContent, to: FloatingContent) {
+        let item_from = from.get_inventory_item(actor);
+        let item_to = to.get_inventory_item(actor);
+        let spells_from = from.get_wand_spells(actor);
+        let spells_to = to.get_wand_spells(actor);
+        assert!(to.set_item(item_from, &spells_from, actor, true));
+        assert!(from.set_item(item_to, &spells_to, actor, true));
+        to.set_item(item_from, &spells_from, actor, false);
+        from.set_item(item_to, &spells_to, actor, false);
+    }
+
+    #[test]
+    fn generated_wand_keeps_stats_after_move() {
+        let stats = WandStats {
+            capacity: 7,
+            recharge: 3,
+            cast_delay_multiplier: 0.6,
+            spread: 0.05,
+            mana_capacity: 1200,
+            shuffle: true,
+        };
+        let mut slots = [None; MAX_SPELLS_IN_WAND];
+        slots[0] = Some(WandSpell {
+            spell_type: SpellType::MagicBolt,
+            price: 0,
+            tier: 0,
+        });
+        let mut wand = Wand::with_slots(WandType::CypressWand, slots);
+        wand.stats = stats;
+        assert_ne!(stats, WandType::CypressWand.to_props().stats);
+
+        let mut actor = actor([Some(wand), None, None, None]);
+
+        // 杖の枠からインベントリへ、インベントリから別の杖の枠へ移します
+        move_item(
+            &mut actor,
+            FloatingContent::Wand(0),
+            FloatingContent::Inventory(0),
+        );
+        assert!(actor.wands[0].is_none());
+        move_item(
+            &mut actor,
+            FloatingContent::Inventory(0),
+            FloatingContent::Wand(1),
+        );
+
+        let moved = actor.wands[1].as_ref().unwrap();
+        assert_eq!(moved.wand_type, WandType::CypressWand);
+        assert_eq!(moved.stats, stats);
+    }
+}
@@ -3,9 +3,14 @@ use crate::ui::floating::Floating;
 use crate::{
     asset::GameAssets,
     config::GameConfig,
-    inventory_item::{get_inventory_item_description, inventory_item_to_props},
+    inventory_item::{
+        get_inventory_item_description, get_inventory_item_name, inventory_item_to_props,
+    },
+    spell::SpellType,
+    spell_registry::SpellRegistry,
     states::GameState,
     wand::WandType,
+    wand_props::{get_wand_appendix, WandStats},
 };
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
@@ -13,10 +18,12 @@ use bevy_aseprite_ultra::prelude::*;
 
 use super::inventory::InventoryGrid;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq)]
 pub enum SpellInformationItem {
     InventoryItem(InventoryItem),
-    Wand(WandType),
+
+    /// 装備中の杖と、その杖の性能と常時詠唱の呪文
+    Wand(WandType, WandStats, Vec<SpellType>),
 }
 
 #[derive(Component)]
@@ -109,6 +116,7 @@ fn update_spell_icon(
     mut query: Query<&mut AseUiSlice, With<SpellIcon>>,
     spell_info: Query<&SpellInformation>,
     floating_query: Query<&Floating>,
+    registry: Res<SpellRegistry>,
 ) {
     let floating = floating_query.single();
     if floating.content.is_some() {
@@ -119,10 +127,10 @@ fn update_spell_icon(
     let spell_info = spell_info.single();
     match spell_info {
         SpellInformation(Some(SpellInformationItem::InventoryItem(item))) => {
-            let props = inventory_item_to_props(item.item_type);
+            let props = inventory_item_to_props(&registry, item.item_type);
             slice.name = props.icon.into();
         }
-        SpellInformation(Some(SpellInformationItem::Wand(wand))) => {
+        SpellInformation(Some(SpellInformationItem::Wand(wand, ..))) => {
             slice.name = wand.to_props().slice.into();
         }
         _ => {
@@ -136,6 +144,7 @@ fn update_spell_name(
     spell_info: Query<&SpellInformation>,
     config: Res<GameConfig>,
     floating_query: Query<&Floating>,
+    registry: Res<SpellRegistry>,
 ) {
     let floating = floating_query.single();
     if floating.content.is_some() {
@@ -146,10 +155,9 @@ fn update_spell_name(
     let spell_info = spell_info.single();
     match spell_info {
         SpellInformation(Some(SpellInformationItem::InventoryItem(item))) => {
-            let props = inventory_item_to_props(item.item_type);
-            text.0 = props.name.get(config.language).to_string();
+            text.0 = get_inventory_item_name(&registry, *item, config.language);
         }
-        SpellInformation(Some(SpellInformationItem::Wand(wand))) => {
+        SpellInformation(Some(SpellInformationItem::Wand(wand, ..))) => {
             text.0 = wand.to_props().name.get(config.language).to_string();
         }
         _ => {
@@ -163,6 +171,7 @@ fn update_spell_description(
     spell_info: Query<&SpellInformation>,
     config: Res<GameConfig>,
     floating_query: Query<&Floating>,
+    registry: Res<SpellRegistry>,
 ) {
     let floating = floating_query.single();
     if floating.content.is_some() {
@@ -173,13 +182,17 @@ fn update_spell_description(
     let spell_info = spell_info.single();
     match spell_info {
         SpellInformation(Some(SpellInformationItem::InventoryItem(item))) => {
-            text.0 = get_inventory_item_description(item.item_type, config.language);
+            text.0 = get_inventory_item_description(&registry, *item, config.language);
             if 0 < item.price {
                 text.0 += &format!("\n未清算:{}ゴールド", item.price);
             }
         }
-        SpellInformation(Some(SpellInformationItem::Wand(wand))) => {
-            text.0 = wand.to_props().description.get(config.language).to_string();
+        SpellInformation(Some(SpellInformationItem::Wand(wand, stats, always_cast))) => {
+            text.0 = format!(
+                "{}\n{}",
+                wand.to_props().description.get(config.language),
+                get_wand_appendix(&registry, stats, always_cast, config.language)
+            );
         }
         _ => {
             text.0 = "".to_string();
@@ -1,5 +1,6 @@
 use crate::inventory::InventoryItem;
 use crate::inventory_item::InventoryItemType;
+use crate::spell_registry::SpellRegistry;
 use crate::{asset::GameAssets, states::GameState};
 use bevy::prelude::*;
 use bevy_aseprite_ultra::prelude::*;
@@ -7,13 +8,18 @@ use bevy_aseprite_ultra::prelude::*;
 #[derive(Component)]
 pub struct ItemPanel(pub Option<InventoryItem>);
 
+/// 取り外すことのできないアイテムを表示するパネルであることを表します
+/// 未清算の呪文の黄色い枠と区別できるよう、灰色の枠で表示されます
+#[derive(Component)]
+pub struct LockedItemPanel;
+
 #[derive(Component)]
 struct ItemFrame;
 
 #[derive(Component)]
 struct ChargeAlert;
 
-pub fn spawn_item_panel<T: Component>(
+pub fn spawn_item_panel<T: Bundle>(
     builder: &mut ChildBuilder,
     assets: &Res<GameAssets>,
     marker: T,
@@ -87,10 +93,13 @@ pub fn spawn_item_panel<T: Component>(
     });
 }
 
-fn update_inventory_slot(mut slot_query: Query<(&ItemPanel, &mut AseUiSlice)>) {
+fn update_inventory_slot(
+    mut slot_query: Query<(&ItemPanel, &mut AseUiSlice)>,
+    registry: Res<SpellRegistry>,
+) {
     for (slot, mut aseprite) in slot_query.iter_mut() {
         if let Some(item) = slot.0 {
-            aseprite.name = item.item_type.get_icon().into();
+            aseprite.name = item.item_type.get_icon(&registry).into();
         } else {
             aseprite.name = "empty".into();
         }
@@ -115,12 +124,18 @@ fn update_panel_width(
 }
 
 fn update_item_frame(
-    slot_query: Query<&ItemPanel>,
+    slot_query: Query<(&ItemPanel, Has<LockedItemPanel>)>,
     mut children_query: Query<(&Parent, &mut AseUiSlice), With<ItemFrame>>,
 ) {
     for (parent, mut aseprite) in children_query.iter_mut() {
-        let item_optional = slot_query.get(parent.get()).unwrap();
+        let (item_optional, locked) = slot_query.get(parent.get()).unwrap();
         match item_optional.0 {
+            Some(InventoryItem {
+                item_type: InventoryItemType::Spell(..),
+                ..
+            }) if locked => {
+                aseprite.name = "spell_frame_locked".into();
+            }
             Some(InventoryItem {
                 item_type: InventoryItemType::Spell(..),
                 ..
@@ -5,6 +5,7 @@ use crate::hud::overlay::OverlayEvent;
 use crate::language::{Dict, Languages};
 use crate::level::{CurrentLevel, GameLevel};
 use crate::physics::GamePhysics;
+use crate::random::GameRng;
 use crate::se::{SEEvent, SE};
 use crate::states::GameMenuState;
 use crate::ui::menu_button::menu_button;
@@ -132,6 +133,7 @@ fn setup_game_menu(
     assets: Res<GameAssets>,
     shots: Res<ButtonShots>,
     config: Res<GameConfig>,
+    rng: Res<GameRng>,
 ) {
     commands
         .spawn((
@@ -279,6 +281,17 @@ fn setup_game_menu(
                         },
                     );
 
+                    // 同じ展開を再現できるよう、この冒険のシードを表示します
+                    parent.spawn((
+                        Text::new(format!("シード/Seed: {}", rng.seed)),
+                        TextColor(Color::srgb(0.9, 0.9, 0.9)),
+                        TextFont {
+                            font_size: 24.0,
+                            font: assets.dotgothic.clone(),
+                            ..default()
+                        },
+                    ));
+
                     menu_button(
                         parent,
                         &assets,
@@ -1,9 +1,10 @@
 use super::{
     floating::{Floating, FloatingContent},
-    item_panel::{spawn_item_panel, ItemPanel},
+    item_panel::{spawn_item_panel, ItemPanel, LockedItemPanel},
 };
 use crate::{
     asset::GameAssets,
+    constant::MAX_SPELLS_IN_WAND,
     controller::player::Player,
     entity::actor::Actor,
     inventory::InventoryItem,
@@ -18,6 +19,14 @@ struct WandSpellSprite {
     spell_index: usize,
 }
 
+/// 杖の常時詠唱の呪文を表示するパネルです
+/// 通常のスロットの右側に並び、ドラッグして取り外すことはできません
+#[derive(Component, Debug, Clone)]
+struct AlwaysCastSprite {
+    wand_index: usize,
+    index: usize,
+}
+
 pub fn spawn_wand_spell_slot(
     mut parent: &mut ChildBuilder,
     assets: &Res<GameAssets>,
@@ -38,6 +47,23 @@ pub fn spawn_wand_spell_slot(
     );
 }
 
+pub fn spawn_always_cast_slot(
+    mut parent: &mut ChildBuilder,
+    assets: &Res<GameAssets>,
+    wand_index: usize,
+    index: usize,
+) {
+    spawn_item_panel(
+        &mut parent,
+        &assets,
+        (AlwaysCastSprite { wand_index, index }, LockedItemPanel),
+        64.0 + 32. * ((MAX_SPELLS_IN_WAND + index) as f32),
+        0.0,
+        None,
+        Some(BackgroundColor(Color::hsla(45.0, 0.6, 0.4, 0.2))),
+    );
+}
+
 fn update_panel_item(
     player_query: Query<&Actor, With<Player>>,
     mut sprite_query: Query<(&WandSpellSprite, &mut ItemPanel)>,
@@ -57,12 +83,43 @@ fn update_panel_item(
                     .map(|e| InventoryItem {
                         item_type: InventoryItemType::Spell(e.spell_type),
                         price: e.price,
+                        tier: e.tier,
+                        wand_stats: None,
                     }),
             };
         }
     }
 }
 
+fn update_always_cast_item(
+    player_query: Query<&Actor, With<Player>>,
+    mut sprite_query: Query<(&AlwaysCastSprite, &mut ItemPanel, &mut Visibility)>,
+    floating_query: Query<&Floating>,
+) {
+    if let Ok(actor) = player_query.get_single() {
+        let float = floating_query.single();
+        for (sprite, mut panel, mut visibility) in sprite_query.iter_mut() {
+            let spell = match float.content {
+                Some(FloatingContent::Wand(w)) if w == sprite.wand_index => None,
+                _ => actor
+                    .get_wand(sprite.wand_index)
+                    .and_then(|wand| wand.always_cast.get(sprite.index).copied()),
+            };
+            panel.0 = spell.map(|spell_type| InventoryItem {
+                item_type: InventoryItemType::Spell(spell_type),
+                price: 0,
+                tier: 0,
+                wand_stats: None,
+            });
+            *visibility = if spell.is_some() {
+                Visibility::default()
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
+
 fn update_spell_sprite_visibility(
     player_query: Query<&Actor, With<Player>>,
     mut sprite_query: Query<(&WandSpellSprite, &mut Visibility), With<ItemPanel>>,
@@ -77,7 +134,7 @@ fn update_spell_sprite_visibility(
                 }
                 _ => {
                     match actor.get_wand(sprite.wand_index) {
-                        Some(wand) if sprite.spell_index < wand.wand_type.to_props().capacity => {
+                        Some(wand) if sprite.spell_index < wand.stats.capacity => {
                             *visibility = Visibility::default();
                         }
                         _ => *visibility = Visibility::Hidden,
@@ -139,6 +196,7 @@ impl Plugin for SpellInWandPlugin {
             Update,
             (
                 update_panel_item,
+                update_always_cast_item,
                 interaction_spell_sprite,
                 update_spell_sprite_visibility,
             )
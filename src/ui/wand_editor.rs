@@ -7,11 +7,14 @@ use super::{
 };
 use crate::{
     asset::GameAssets,
+    config::GameConfig,
     constant::WAND_EDITOR_Z_INDEX,
     controller::player::Player,
     entity::actor::Actor,
     language::Dict,
+    spell_registry::SpellRegistry,
     states::{GameMenuState, GameState},
+    wand_simulator::get_simulation_text,
 };
 use bevy::prelude::*;
 
@@ -21,6 +24,10 @@ struct WandEditorRoot;
 #[derive(Component)]
 struct SortButton;
 
+/// 現在の杖を1周唱えたときの内容を表示するテキストです
+#[derive(Component)]
+struct WandPreview;
+
 const MENU_THEME_COLOR: Color = Color::hsla(63.0, 0.12, 0.5, 0.95);
 
 pub fn spawn_wand_editor(builder: &mut ChildBuilder, assets: &Res<GameAssets>) {
@@ -58,6 +65,16 @@ pub fn spawn_wand_editor(builder: &mut ChildBuilder, assets: &Res<GameAssets>) {
                     en: "Sort",
                 },
             );
+
+            parent.spawn((
+                WandPreview,
+                Text::new(""),
+                TextFont {
+                    font: assets.dotgothic.clone(),
+                    font_size: 12.0,
+                    ..default()
+                },
+            ));
         });
 
     builder
@@ -103,6 +120,22 @@ fn switch_sort_button_disabled(
     }
 }
 
+fn update_wand_preview(
+    mut query: Query<&mut Text, With<WandPreview>>,
+    player_query: Query<&Actor, With<Player>>,
+    registry: Res<SpellRegistry>,
+    config: Res<GameConfig>,
+) {
+    if let Ok(mut text) = query.get_single_mut() {
+        if let Ok(actor) = player_query.get_single() {
+            text.0 = match &actor.wands[actor.current_wand] {
+                Some(wand) => get_simulation_text(&registry, wand, config.language),
+                None => "".to_string(),
+            };
+        }
+    }
+}
+
 fn handle_tab_key(
     keys: Res<ButtonInput<KeyCode>>,
     state: Res<State<GameMenuState>>,
@@ -154,6 +187,7 @@ impl Plugin for WandEditorPlugin {
                 handle_tab_key,
                 sort_button_pressed,
                 switch_sort_button_disabled,
+                update_wand_preview,
             )
                 .run_if(in_state(GameState::InGame)),
         );
@@ -1,9 +1,9 @@
 use crate::asset::GameAssets;
-use crate::constant::{MAX_SPELLS_IN_WAND, MAX_WANDS};
+use crate::constant::{MAX_ALWAYS_CAST_IN_WAND, MAX_SPELLS_IN_WAND, MAX_WANDS};
 use crate::controller::player::Player;
 use crate::entity::actor::Actor;
 use crate::states::GameState;
-use crate::ui::spell_in_wand::spawn_wand_spell_slot;
+use crate::ui::spell_in_wand::{spawn_always_cast_slot, spawn_wand_spell_slot};
 use crate::ui::wand_sprite::spawn_wand_sprite_in_list;
 use bevy::{
     prelude::*,
@@ -61,6 +61,10 @@ fn spawn_wand_and_spell_slot(
                 spawn_wand_spell_slot(&mut parent, &assets, wand_index, spell_index);
             }
 
+            for index in 0..MAX_ALWAYS_CAST_IN_WAND {
+                spawn_always_cast_slot(&mut parent, &assets, wand_index, index);
+            }
+
             parent.spawn((
                 TriggerMarker,
                 AseUiSlice {
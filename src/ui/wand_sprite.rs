@@ -98,7 +98,11 @@ fn update_wand_information(
                 if let Ok(actor) = player_query.get_single_mut() {
                     if let Some(ref wand) = actor.wands[slot.wand_index] {
                         let mut info = spell_information_query.single_mut();
-                        *info = SpellInformation(Some(SpellInformationItem::Wand(wand.wand_type)));
+                        *info = SpellInformation(Some(SpellInformationItem::Wand(
+                            wand.wand_type,
+                            wand.stats,
+                            wand.always_cast.clone(),
+                        )));
                     }
                 }
             }
@@ -1,16 +1,23 @@
-use crate::{constant::MAX_SPELLS_IN_WAND, spell::SpellType};
+use crate::{constant::MAX_SPELLS_IN_WAND, spell::SpellType, wand_props::WandStats};
 use bevy::reflect::Reflect;
+use rand::seq::SliceRandom;
+use rand::Rng;
 
 #[derive(Reflect, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum WandType {
     CypressWand,
     KeyWand,
+    GuidingWand,
 }
 
 #[derive(Reflect, Clone, Copy, Debug)]
 pub struct WandSpell {
     pub spell_type: SpellType,
     pub price: u32,
+
+    /// 呪文の強化段階
+    /// 段階に応じて呪文の性能が強化されます
+    pub tier: u32,
 }
 
 #[derive(Reflect, Clone, Debug)]
@@ -20,8 +27,33 @@ pub struct Wand {
     pub slots: [Option<WandSpell>; MAX_SPELLS_IN_WAND],
     pub index: usize,
     pub delay: u32,
+
+    /// この杖の性能です
+    /// 通常は杖の種類の基本の性能ですが、生成された杖では個別に異なります
+    pub stats: WandStats,
+
+    /// 呪文を唱える順番です
+    /// index 番目に唱えられるのは slots[order[index]] の呪文です
+    /// 通常はスロットの並び順のままですが、シャッフルする杖では再装填のたびに並べ替えられます
+    pub order: [usize; MAX_SPELLS_IN_WAND],
+
+    /// 常時詠唱の呪文
+    /// スロットとは別に保持され、杖を振るたびに必ず詠唱されます
+    /// 杖の編集画面から取り外すことはできません
+    pub always_cast: Vec<SpellType>,
 }
 
+/// スロットの並び順どおりに唱える場合の順番です
+pub const SEQUENTIAL_ORDER: [usize; MAX_SPELLS_IN_WAND] = {
+    let mut order = [0; MAX_SPELLS_IN_WAND];
+    let mut i = 0;
+    while i < MAX_SPELLS_IN_WAND {
+        order[i] = i;
+        i += 1;
+    }
+    order
+};
+
 impl Wand {
     pub fn new(wand_type: WandType) -> Self {
         Self {
@@ -30,6 +62,9 @@ impl Wand {
             slots: [None; MAX_SPELLS_IN_WAND],
             index: 0,
             delay: 0,
+            stats: wand_type.to_props().stats,
+            order: SEQUENTIAL_ORDER,
+            always_cast: wand_type.to_props().always_cast.to_vec(),
         }
     }
 
@@ -40,6 +75,38 @@ impl Wand {
             slots,
             index: 0,
             delay: 0,
+            stats: wand_type.to_props().stats,
+            order: SEQUENTIAL_ORDER,
+            always_cast: wand_type.to_props().always_cast.to_vec(),
+        }
+    }
+
+    /// 唱える順番で position 番目の呪文を返します
+    pub fn spell_at(&self, position: usize) -> Option<WandSpell> {
+        self.slots[self.order[position]]
+    }
+
+    /// 残りの呪文がなくなったかどうかを返します
+    pub fn is_cycle_finished(&self) -> bool {
+        (self.index..MAX_SPELLS_IN_WAND).all(|i| self.spell_at(i).is_none())
+    }
+
+    /// 最初の呪文に戻り、杖の再装填時間を詠唱遅延に加えます
+    /// シャッフルする杖の場合は、ここで唱える順番を並べ替えます
+    pub fn recharge<R: Rng>(&mut self, rng: &mut R) {
+        self.index = 0;
+        self.delay += self.stats.recharge;
+        self.order = SEQUENTIAL_ORDER;
+        if self.stats.shuffle {
+            self.order[..self.stats.capacity].shuffle(rng);
+        }
+    }
+
+    /// 杖と装填されている呪文の価格をすべて0にします
+    pub fn clear_price(&mut self) {
+        self.price = 0;
+        for spell in self.slots.iter_mut().flatten() {
+            spell.price = 0;
         }
     }
 
@@ -52,11 +119,11 @@ impl Wand {
     }
 
     pub fn shift(&mut self) {
-        let props = self.wand_type.to_props();
-        self.index = (self.index + 1) % props.capacity;
+        let capacity = self.stats.capacity;
+        self.index = (self.index + 1) % capacity;
         for _ in 0..MAX_SPELLS_IN_WAND {
             if self.slots[self.index].is_none() {
-                self.index = (self.index + 1) % props.capacity;
+                self.index = (self.index + 1) % capacity;
                 continue;
             } else {
                 break;
@@ -0,0 +1,219 @@
+use crate::{
+    constant::MAX_SPELLS_IN_WAND,
+    spell::SpellType,
+    spell_registry::SpellRegistry,
+    wand::{Wand, WandSpell, WandType},
+    wand_props::WandStats,
+};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+
+/// 生成される杖の種類の一覧です
+const GENERATED_WAND_TYPES: [WandType; 3] = [
+    WandType::CypressWand,
+    WandType::KeyWand,
+    WandType::GuidingWand,
+];
+
+/// 敵だけが使う呪文の一覧です
+/// 生成される杖には装填されません
+const ENEMY_ONLY_SPELL_TYPES: [SpellType; 2] =
+    [SpellType::SummonEnemySlime, SpellType::SlimeCharge];
+
+/// この深さ以降は、性能の抽選が常に最も有利な範囲で行われます
+const MAX_DEPTH: u32 = 8;
+
+/// 深さ 0 で出現する呪文の価格の上限
+const BASE_SPELL_PRICE: u32 = 50;
+
+/// 深さがひとつ増えるごとに上がる、出現する呪文の価格の上限
+const SPELL_PRICE_PER_DEPTH: u32 = 60;
+
+/// 性能がもっとも低い杖の価格
+const BASE_WAND_PRICE: u32 = 50;
+
+/// すべての性能が上限の杖に加算される価格
+const STATS_PRICE: u32 = 450;
+
+/// 深さと乱数生成器をもとに、無作為な性能と呪文を持つ杖を生成します
+/// 性能は杖の種類ごとの min_stats から max_stats までの範囲で決まり、深いほど良い値が出やすくなります
+/// 呪文は深いほど高価なものが選ばれやすく、スロットの一部にあらかじめ装填されます
+/// 杖の価格は性能の良さから決まり、装填された呪文にはそれぞれの価格が設定されます
+pub fn generate_wand<R: Rng>(registry: &SpellRegistry, depth: u32, rng: &mut R) -> Wand {
+    let wand_type = GENERATED_WAND_TYPES[rng.gen_range(0..GENERATED_WAND_TYPES.len())];
+    let props = wand_type.to_props();
+
+    // 深いほど抽選の下限が上がり、良い性能が出やすくなります
+    let bias = depth.min(MAX_DEPTH) as f32 / MAX_DEPTH as f32 * 0.5;
+    let mut roll = || bias + rng.gen::<f32>() * (1.0 - bias);
+
+    let capacity_roll = roll();
+    let recharge_roll = roll();
+    let cast_delay_roll = roll();
+    let spread_roll = roll();
+    let mana_roll = roll();
+
+    let min = props.min_stats;
+    let max = props.max_stats;
+
+    // 容量とマナ上限は大きいほど、再装填、詠唱遅延、拡散は小さいほど良い性能です
+    let stats = WandStats {
+        capacity: (lerp(min.capacity as f32, max.capacity as f32, capacity_roll).round() as usize)
+            .clamp(1, MAX_SPELLS_IN_WAND),
+        recharge: lerp(max.recharge as f32, min.recharge as f32, recharge_roll).round() as u32,
+        cast_delay_multiplier: lerp(
+            max.cast_delay_multiplier,
+            min.cast_delay_multiplier,
+            cast_delay_roll,
+        ),
+        spread: lerp(max.spread, min.spread, spread_roll),
+        mana_capacity: lerp(
+            min.mana_capacity as f32,
+            max.mana_capacity as f32,
+            mana_roll,
+        )
+        .round() as u32,
+        shuffle: if min.shuffle == max.shuffle {
+            min.shuffle
+        } else {
+            rng.gen_bool(0.5)
+        },
+    };
+
+    let strength =
+        (capacity_roll + recharge_roll + cast_delay_roll + spread_roll + mana_roll) / 5.0;
+
+    let mut wand = Wand::new(wand_type);
+    wand.stats = stats;
+    wand.price = BASE_WAND_PRICE + (STATS_PRICE as f32 * strength).round() as u32;
+
+    let count = rng.gen_range(1..=(stats.capacity + 1) / 2);
+    let spells = select_spells(registry, depth, rng, count);
+    for (index, spell_type) in spells.into_iter().enumerate() {
+        wand.slots[index] = Some(WandSpell {
+            spell_type,
+            price: spell_type.to_props(registry).price,
+            tier: 0,
+        });
+    }
+
+    wand
+}
+
+/// 深さに応じた重みで、杖に装填する呪文を選びます
+/// 価格が上限を超える呪文と敵だけが使う呪文は選ばれず、上限に近いほど選ばれやすくなります
+fn select_spells<R: Rng>(
+    registry: &SpellRegistry,
+    depth: u32,
+    rng: &mut R,
+    count: usize,
+) -> Vec<SpellType> {
+    let max_price = BASE_SPELL_PRICE + SPELL_PRICE_PER_DEPTH * depth;
+
+    let candidates: Vec<SpellType> = registry
+        .spell_types()
+        .iter()
+        .copied()
+        .filter(|s| !ENEMY_ONLY_SPELL_TYPES.contains(s))
+        .filter(|s| s.to_props(registry).price <= max_price)
+        .collect();
+
+    let weights: Vec<f32> = candidates
+        .iter()
+        .map(|s| 1.0 + depth as f32 * s.to_props(registry).price as f32 / max_price as f32)
+        .collect();
+
+    let Ok(distribution) = WeightedIndex::new(&weights) else {
+        return Vec::new();
+    };
+
+    (0..count)
+        .map(|_| candidates[distribution.sample(rng)])
+        .collect()
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn registry() -> SpellRegistry {
+        SpellRegistry::from_json(include_str!("../assets/data/registry.spell.json")).unwrap()
+    }
+
+    fn in_range<T: PartialOrd + Copy>(value: T, a: T, b: T) -> bool {
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        low <= value && value <= high
+    }
+
+    #[test]
+    fn stats_are_within_wand_type_range() {
+        let registry = registry();
+        for seed in 0..64 {
+            for depth in [0, 4, 16] {
+                let wand = generate_wand(&registry, depth, &mut StdRng::seed_from_u64(seed));
+                let props = wand.wand_type.to_props();
+                let (min, max, stats) = (props.min_stats, props.max_stats, wand.stats);
+                assert!(in_range(stats.capacity, min.capacity, max.capacity));
+                assert!(in_range(stats.recharge, min.recharge, max.recharge));
+                assert!(in_range(
+                    stats.cast_delay_multiplier,
+                    min.cast_delay_multiplier,
+                    max.cast_delay_multiplier
+                ));
+                assert!(in_range(stats.spread, min.spread, max.spread));
+                assert!(in_range(
+                    stats.mana_capacity,
+                    min.mana_capacity,
+                    max.mana_capacity
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn spells_fit_in_capacity() {
+        let registry = registry();
+        for seed in 0..64 {
+            let wand = generate_wand(&registry, 8, &mut StdRng::seed_from_u64(seed));
+            let count = wand.slots.iter().filter(|s| s.is_some()).count();
+            assert!(0 < count && count <= wand.stats.capacity);
+            assert!(wand.slots[wand.stats.capacity..]
+                .iter()
+                .all(|s| s.is_none()));
+        }
+    }
+
+    #[test]
+    fn enemy_only_spells_are_not_generated() {
+        let registry = registry();
+        for seed in 0..64 {
+            let wand = generate_wand(&registry, 16, &mut StdRng::seed_from_u64(seed));
+            for spell in wand.slots.iter().flatten() {
+                assert!(!ENEMY_ONLY_SPELL_TYPES.contains(&spell.spell_type));
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_generates_same_wand() {
+        let registry = registry();
+        let a = generate_wand(&registry, 3, &mut StdRng::seed_from_u64(7));
+        let b = generate_wand(&registry, 3, &mut StdRng::seed_from_u64(7));
+
+        let spells = |wand: &Wand| -> Vec<Option<(SpellType, u32)>> {
+            wand.slots
+                .iter()
+                .map(|s| s.map(|s| (s.spell_type, s.price)))
+                .collect()
+        };
+        assert_eq!(a.wand_type, b.wand_type);
+        assert_eq!(a.price, b.price);
+        assert_eq!(a.stats, b.stats);
+        assert_eq!(spells(&a), spells(&b));
+    }
+}
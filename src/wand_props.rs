@@ -1,11 +1,56 @@
-use crate::{language::Dict, wand::WandType};
+use crate::{
+    language::{Dict, Languages},
+    spell::SpellType,
+    spell_registry::SpellRegistry,
+    wand::WandType,
+};
+use bevy::reflect::Reflect;
+
+/// 杖ごとに異なる性能です
+/// 手動で配置された杖は WandProps::stats の値を使い、
+/// 生成された杖は WandProps::min_stats から WandProps::max_stats までの範囲で無作為に決まります
+#[derive(Reflect, Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct WandStats {
+    pub capacity: usize,
+
+    /// すべての呪文を唱え終えたあと、最初の呪文に戻るまでの再装填時間のフレーム数
+    pub recharge: u32,
+
+    /// 呪文の詠唱遅延に掛かる倍率
+    pub cast_delay_multiplier: f32,
+
+    /// 発射する弾丸の拡散に加算される値
+    pub spread: f32,
+
+    /// 1回の詠唱で扱えるマナの上限
+    /// これを超えるマナを必要とする詠唱は不発になります
+    /// マナは Actor 内部と同様に10倍の値で保持しています
+    pub mana_capacity: u32,
+
+    /// 有効な場合、再装填のたびに呪文を唱える順番が無作為に並べ替えられます
+    pub shuffle: bool,
+}
 
 pub struct WandProps {
     pub name: Dict,
     pub description: Dict,
     pub slice: &'static str,
     pub icon: &'static str,
-    pub capacity: usize,
+
+    /// 基本の性能
+    pub stats: WandStats,
+
+    /// 生成される杖の性能の下限
+    /// shuffle は min_stats と max_stats で異なる場合のみ無作為に決まります
+    pub min_stats: WandStats,
+
+    /// 生成される杖の性能の上限
+    pub max_stats: WandStats,
+
+    /// 常時詠唱の呪文
+    /// スロットの呪文とは別に、杖を振るたびに必ず詠唱されます
+    /// 杖の生成時に Wand::always_cast にコピーされます
+    pub always_cast: &'static [SpellType],
 }
 
 const CYPRESS_WAND: WandProps = WandProps {
@@ -19,7 +64,31 @@ const CYPRESS_WAND: WandProps = WandProps {
     },
     slice: "wand_cypress",
     icon: "wand_icon_cypress",
-    capacity: 8,
+    stats: WandStats {
+        capacity: 8,
+        recharge: 30,
+        cast_delay_multiplier: 1.0,
+        spread: 0.0,
+        mana_capacity: 1000,
+        shuffle: false,
+    },
+    min_stats: WandStats {
+        capacity: 4,
+        recharge: 20,
+        cast_delay_multiplier: 0.8,
+        spread: 0.0,
+        mana_capacity: 600,
+        shuffle: false,
+    },
+    max_stats: WandStats {
+        capacity: 8,
+        recharge: 45,
+        cast_delay_multiplier: 1.2,
+        spread: 0.1,
+        mana_capacity: 1200,
+        shuffle: false,
+    },
+    always_cast: &[],
 };
 
 const KEY_WAND: WandProps = WandProps {
@@ -33,7 +102,69 @@ const KEY_WAND: WandProps = WandProps {
     },
     slice: "wand_key",
     icon: "wand_icon_key",
-    capacity: 4,
+    stats: WandStats {
+        capacity: 4,
+        recharge: 10,
+        cast_delay_multiplier: 1.0,
+        spread: 0.0,
+        mana_capacity: 500,
+        shuffle: false,
+    },
+    min_stats: WandStats {
+        capacity: 3,
+        recharge: 5,
+        cast_delay_multiplier: 0.5,
+        spread: 0.1,
+        mana_capacity: 300,
+        shuffle: false,
+    },
+    max_stats: WandStats {
+        capacity: 6,
+        recharge: 20,
+        cast_delay_multiplier: 0.9,
+        spread: 0.4,
+        mana_capacity: 800,
+        shuffle: true,
+    },
+    always_cast: &[],
+};
+
+const GUIDING_WAND: WandProps = WandProps {
+    name: Dict {
+        ja: "導きの杖",
+        en: "Guiding Wand",
+    },
+    description: Dict {
+        ja: "放たれた魔法が自ずと敵へ向かうよう、追尾の魔法が刻み込まれた杖。",
+        en: "A wand engraved with a homing spell that guides every bolt toward its target.",
+    },
+    slice: "wand_guiding",
+    icon: "wand_icon_guiding",
+    stats: WandStats {
+        capacity: 6,
+        recharge: 20,
+        cast_delay_multiplier: 1.0,
+        spread: 0.0,
+        mana_capacity: 800,
+        shuffle: false,
+    },
+    min_stats: WandStats {
+        capacity: 4,
+        recharge: 15,
+        cast_delay_multiplier: 0.9,
+        spread: 0.0,
+        mana_capacity: 600,
+        shuffle: false,
+    },
+    max_stats: WandStats {
+        capacity: 7,
+        recharge: 30,
+        cast_delay_multiplier: 1.1,
+        spread: 0.1,
+        mana_capacity: 1000,
+        shuffle: false,
+    },
+    always_cast: &[SpellType::Homing],
 };
 
 impl WandType {
@@ -41,6 +172,80 @@ impl WandType {
         match self {
             WandType::CypressWand => CYPRESS_WAND,
             WandType::KeyWand => KEY_WAND,
+            WandType::GuidingWand => GUIDING_WAND,
         }
     }
 }
+
+const CAPACITY: Dict = Dict {
+    ja: "容量",
+    en: "Capacity",
+};
+
+const RECHARGE: Dict = Dict {
+    ja: "再装填",
+    en: "Recharge",
+};
+
+const CAST_DELAY: Dict = Dict {
+    ja: "詠唱遅延",
+    en: "Cast Delay",
+};
+
+const SPREAD: Dict = Dict {
+    ja: "拡散",
+    en: "Spread",
+};
+
+const MANA_CAPACITY: Dict = Dict {
+    ja: "マナ上限",
+    en: "Mana Capacity",
+};
+
+const ALWAYS_CAST: Dict = Dict {
+    ja: "常時詠唱",
+    en: "Always Cast",
+};
+
+const SHUFFLE: Dict = Dict {
+    ja: "順番が無作為に入れ替わる",
+    en: "Shuffles spell order",
+};
+
+/// 杖の説明文に付け加える、杖の性能の一覧です
+/// 生成された杖は種類ごとの基本の性能と異なる場合があるため、性能と常時詠唱の呪文は杖ごとに指定します
+pub fn get_wand_appendix(
+    registry: &SpellRegistry,
+    stats: &WandStats,
+    always_cast: &[SpellType],
+    language: Languages,
+) -> String {
+    let mut appendix = format!(
+        "{}:{}  {}:{}\n{}:x{}  {}:{:+}\n{}:{}",
+        CAPACITY.get(language),
+        stats.capacity,
+        RECHARGE.get(language),
+        stats.recharge,
+        CAST_DELAY.get(language),
+        stats.cast_delay_multiplier,
+        SPREAD.get(language),
+        stats.spread,
+        MANA_CAPACITY.get(language),
+        stats.mana_capacity as f32 / 10.0,
+    );
+    if stats.shuffle {
+        appendix += &format!("\n{}", SHUFFLE.get(language));
+    }
+    if !always_cast.is_empty() {
+        appendix += &format!(
+            "\n{}:{}",
+            ALWAYS_CAST.get(language),
+            always_cast
+                .iter()
+                .map(|s| s.to_props(registry).name.get(language))
+                .collect::<Vec<&str>>()
+                .join(", ")
+        );
+    }
+    appendix
+}
@@ -0,0 +1,426 @@
+use crate::{
+    cast::{draw_cast, exceeds_mana_capacity, DrawnSpell},
+    language::{Dict, Languages},
+    spell::SpellType,
+    spell_props::{get_modifier_text, BulletModifier, BulletProps, SpellCast},
+    spell_registry::SpellRegistry,
+    wand::{Wand, SEQUENTIAL_ORDER},
+};
+
+/// シミュレーションで発射される弾丸ひとつぶんです
+#[derive(Clone, Debug)]
+pub struct SimulatedProjectile {
+    pub spell_type: SpellType,
+
+    /// この弾丸に適用される修飾
+    /// 常時詠唱の修飾と、同じ詠唱の中でこの弾丸より前に唱えられた修飾です
+    pub modifiers: Vec<BulletModifier>,
+
+    /// 修飾と杖の拡散を適用したあとの弾丸の性能
+    pub bullet: BulletProps,
+
+    /// トリガー付きの弾丸が保持するペイロード
+    pub payload: Vec<SpellType>,
+}
+
+/// 1回の詠唱で唱えられる呪文のまとまりです
+#[derive(Clone, Debug)]
+pub struct SimulatedCast {
+    /// 発射される弾丸の一覧
+    pub projectiles: Vec<SimulatedProjectile>,
+
+    /// 弾丸と修飾以外の呪文
+    pub others: Vec<SpellType>,
+
+    /// この詠唱で発生する詠唱遅延のフレーム数
+    pub cast_delay: u32,
+
+    /// この詠唱で消費するマナ
+    pub mana_cost: u32,
+
+    /// 消費するマナが杖の扱えるマナの上限を超えているため、詠唱全体が不発になるかどうか
+    pub fizzled: bool,
+}
+
+/// 杖を最初の呪文から最後の呪文まで唱えたときの結果です
+#[derive(Clone, Debug)]
+pub struct WandSimulation {
+    /// 唱えられる順の詠唱の一覧
+    pub casts: Vec<SimulatedCast>,
+
+    /// すべての詠唱の詠唱遅延の合計
+    pub cast_delay: u32,
+
+    /// 最後の呪文を唱えたあとの再装填時間
+    pub recharge: u32,
+
+    /// すべての詠唱で消費するマナの合計
+    pub mana_cost: u32,
+}
+
+impl WandSimulation {
+    /// 最初の呪文から再装填を終えるまでのフレーム数です
+    pub fn cycle_frames(&self) -> u32 {
+        self.cast_delay + self.recharge
+    }
+}
+
+/// 杖の呪文を1周ぶん唱えた結果を求めます
+/// 実際の詠唱と同じ draw_cast で呪文を取り出しますが、杖の複製に対して行うため副作用はありません
+/// シャッフルする杖でもスロットの並び順どおりに唱えたものとして扱います
+/// 杖の扱えるマナの上限による不発は考慮しますが、アクターのマナの不足による不発は考慮しません
+pub fn simulate_wand(registry: &SpellRegistry, wand: &Wand) -> WandSimulation {
+    let stats = wand.stats;
+
+    let mut wand = wand.clone();
+    wand.index = 0;
+    wand.delay = 0;
+    wand.order = SEQUENTIAL_ORDER;
+
+    let mut always_modifiers: Vec<BulletModifier> = Vec::new();
+    for spell_type in wand.always_cast.iter() {
+        if let SpellCast::Modifier(modifiers) = &spell_type.to_props(registry).cast {
+            always_modifiers.extend(modifiers.iter().copied());
+        }
+    }
+
+    let mut casts = Vec::new();
+
+    while !wand.is_cycle_finished() {
+        let delay_before = wand.delay;
+        let drawn = draw_cast(registry, &mut wand);
+        let cast_delay = wand.delay - delay_before;
+
+        let mut projectiles = Vec::new();
+        let mut others = Vec::new();
+        let mut modifiers = always_modifiers.clone();
+
+        for spell_type in wand.always_cast.iter() {
+            if let SpellCast::Bullet(bullet) = &spell_type.to_props(registry).cast {
+                projectiles.push(simulate_projectile(
+                    *spell_type,
+                    bullet,
+                    &always_modifiers,
+                    stats.spread,
+                    Vec::new(),
+                ));
+            }
+        }
+
+        for DrawnSpell {
+            spell_type,
+            tier,
+            payload,
+            ..
+        } in drawn.iter()
+        {
+            match &spell_type.to_props(registry).cast.upgrade(*tier) {
+                SpellCast::Bullet(bullet) => {
+                    projectiles.push(simulate_projectile(
+                        *spell_type,
+                        bullet,
+                        &modifiers,
+                        stats.spread,
+                        payload.clone(),
+                    ));
+                    modifiers = always_modifiers.clone();
+                }
+                SpellCast::Modifier(m) => {
+                    modifiers.extend(m.iter().copied());
+                }
+                SpellCast::MultipleCast { .. } => {}
+                _ => {
+                    others.push(*spell_type);
+                }
+            }
+        }
+
+        casts.push(SimulatedCast {
+            projectiles,
+            others,
+            cast_delay,
+            mana_cost: drawn.iter().map(|d| d.mana_cost).sum(),
+            fizzled: exceeds_mana_capacity(&stats, &drawn),
+        });
+    }
+
+    WandSimulation {
+        cast_delay: casts.iter().map(|c| c.cast_delay).sum(),
+        recharge: stats.recharge,
+        mana_cost: casts.iter().map(|c| c.mana_cost).sum(),
+        casts,
+    }
+}
+
+fn simulate_projectile(
+    spell_type: SpellType,
+    bullet: &BulletProps,
+    modifiers: &[BulletModifier],
+    spread: f32,
+    payload: Vec<SpellType>,
+) -> SimulatedProjectile {
+    let mut props = bullet.clone();
+    for modifier in modifiers.iter() {
+        modifier.apply(&mut props);
+    }
+    props.scattering = (props.scattering + spread).max(0.0);
+    SimulatedProjectile {
+        spell_type,
+        modifiers: modifiers.to_vec(),
+        bullet: props,
+        payload,
+    }
+}
+
+const EMPTY: Dict = Dict {
+    ja: "呪文がありません",
+    en: "No spells",
+};
+
+const FIZZLE: Dict = Dict {
+    ja: "不発",
+    en: "Fizzle",
+};
+
+const PER_CYCLE: Dict = Dict {
+    ja: "フレーム/周",
+    en: "frames/cycle",
+};
+
+/// 杖の編集画面に表示する、詠唱のプレビューの文字列を返します
+/// 1行に1回の詠唱の内容を並べ、最後の行に1周にかかるフレーム数を表示します
+pub fn get_simulation_text(registry: &SpellRegistry, wand: &Wand, language: Languages) -> String {
+    let simulation = simulate_wand(registry, wand);
+    if simulation.casts.is_empty() {
+        return EMPTY.get(language).to_string();
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for cast in simulation.casts.iter() {
+        // 同じ呪文の弾丸はまとめて「名前 x個数」と表示します
+        let mut counts: Vec<(SpellType, u32)> = Vec::new();
+        for spell_type in cast
+            .projectiles
+            .iter()
+            .map(|p| p.spell_type)
+            .chain(cast.others.iter().copied())
+        {
+            match counts.iter_mut().find(|(s, _)| *s == spell_type) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((spell_type, 1)),
+            }
+        }
+
+        let mut parts: Vec<String> = counts
+            .iter()
+            .map(|(spell_type, count)| {
+                let name = spell_type.to_props(registry).name.get(language);
+                if 1 < *count {
+                    format!("{} x{}", name, count)
+                } else {
+                    name.to_string()
+                }
+            })
+            .collect();
+
+        // 修飾はいずれかの弾丸に適用されているものを重複なく表示します
+        let mut modifier_texts: Vec<String> = Vec::new();
+        for projectile in cast.projectiles.iter() {
+            for modifier in projectile.modifiers.iter() {
+                let text = get_modifier_text(modifier, language);
+                if !modifier_texts.contains(&text) {
+                    modifier_texts.push(text);
+                }
+            }
+        }
+        parts.extend(modifier_texts);
+
+        if cast.fizzled {
+            parts.push(format!("({})", FIZZLE.get(language)));
+        }
+
+        lines.push(parts.join(" "));
+    }
+
+    lines.push(format!(
+        "{} {}",
+        simulation.cycle_frames(),
+        PER_CYCLE.get(language)
+    ));
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant::MAX_SPELLS_IN_WAND,
+        random::GameRng,
+        wand::{WandSpell, WandType},
+    };
+
+    fn registry() -> SpellRegistry {
+        SpellRegistry::from_json(include_str!("../assets/data/registry.spell.json")).unwrap()
+    }
+
+    fn wand(spells: &[Option<SpellType>]) -> Wand {
+        let mut slots = [None; MAX_SPELLS_IN_WAND];
+        for (i, spell) in spells.iter().enumerate() {
+            slots[i] = spell.map(|spell_type| WandSpell {
+                spell_type,
+                price: 0,
+                tier: 0,
+            });
+        }
+        Wand::with_slots(WandType::CypressWand, slots)
+    }
+
+    fn projectile_types(cast: &SimulatedCast) -> Vec<SpellType> {
+        cast.projectiles.iter().map(|p| p.spell_type).collect()
+    }
+
+    #[test]
+    fn dual_cast_fires_two_bullets_at_once() {
+        let registry = registry();
+        let wand = wand(&[
+            Some(SpellType::DualCast),
+            Some(SpellType::MagicBolt),
+            Some(SpellType::PurpleBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        assert_eq!(simulation.casts.len(), 1);
+        assert_eq!(
+            projectile_types(&simulation.casts[0]),
+            vec![SpellType::MagicBolt, SpellType::PurpleBolt]
+        );
+        // DualCast の詠唱遅延は 0 ですが、最低でも 1 フレームになります
+        assert_eq!(simulation.cast_delay, 1 + 20 + 120);
+        assert_eq!(simulation.recharge, 30);
+        assert_eq!(simulation.cycle_frames(), 1 + 20 + 120 + 30);
+    }
+
+    #[test]
+    fn triple_cast_fires_three_bullets_at_once() {
+        let registry = registry();
+        let wand = wand(&[
+            Some(SpellType::TripleCast),
+            Some(SpellType::MagicBolt),
+            Some(SpellType::MagicBolt),
+            Some(SpellType::MagicBolt),
+            Some(SpellType::MagicBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        assert_eq!(simulation.casts.len(), 2);
+        assert_eq!(simulation.casts[0].projectiles.len(), 3);
+        assert_eq!(simulation.casts[1].projectiles.len(), 1);
+    }
+
+    #[test]
+    fn empty_slots_are_skipped() {
+        let registry = registry();
+        let wand = wand(&[
+            None,
+            Some(SpellType::DualCast),
+            None,
+            Some(SpellType::MagicBolt),
+            None,
+            None,
+            Some(SpellType::MagicBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        // 空欄は詠唱回数を消費しないため、DualCast は離れた2つの弾丸をまとめて発射します
+        assert_eq!(simulation.casts.len(), 1);
+        assert_eq!(
+            projectile_types(&simulation.casts[0]),
+            vec![SpellType::MagicBolt, SpellType::MagicBolt]
+        );
+    }
+
+    #[test]
+    fn multicast_does_not_wrap_around() {
+        let registry = registry();
+        let mut wand = wand(&[
+            Some(SpellType::MagicBolt),
+            Some(SpellType::DualCast),
+            Some(SpellType::PurpleBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        // 末尾の DualCast は先頭の呪文に戻らず、残りの1発だけを発射して1周を終えます
+        assert_eq!(simulation.casts.len(), 2);
+        assert_eq!(
+            projectile_types(&simulation.casts[0]),
+            vec![SpellType::MagicBolt]
+        );
+        assert_eq!(
+            projectile_types(&simulation.casts[1]),
+            vec![SpellType::PurpleBolt]
+        );
+
+        // 再装填後は先頭の呪文から唱え直します
+        draw_cast(&registry, &mut wand);
+        draw_cast(&registry, &mut wand);
+        assert!(wand.is_cycle_finished());
+        wand.recharge(&mut GameRng::new(0).combat);
+        let drawn = draw_cast(&registry, &mut wand);
+        assert_eq!(drawn.len(), 1);
+        assert_eq!(drawn[0].spell_type, SpellType::MagicBolt);
+    }
+
+    #[test]
+    fn modifiers_apply_to_following_bullet() {
+        let registry = registry();
+        let wand = wand(&[
+            Some(SpellType::DualCast),
+            Some(SpellType::HeavyShot),
+            Some(SpellType::MagicBolt),
+            Some(SpellType::MagicBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        // HeavyShot は詠唱回数を消費し、直後の弾丸だけに適用されます
+        assert_eq!(simulation.casts.len(), 2);
+        assert_eq!(simulation.casts[0].projectiles[0].bullet.damage, 8 + 5);
+        assert_eq!(simulation.casts[1].projectiles[0].bullet.damage, 8);
+    }
+
+    #[test]
+    fn fizzle_matches_actual_cast() {
+        let registry = registry();
+        let wand = wand(&[
+            Some(SpellType::DualCast),
+            Some(SpellType::SummonFriendWitch),
+            Some(SpellType::SummonFriendSlime),
+            Some(SpellType::MagicBolt),
+        ]);
+        let simulation = simulate_wand(&registry, &wand);
+
+        // 実際の詠唱と同じ順に呪文を取り出し、cast_spell と同じ判定と比べます
+        let mut actual = wand.clone();
+        for cast in simulation.casts.iter() {
+            let drawn = draw_cast(&registry, &mut actual);
+            assert_eq!(cast.fizzled, exceeds_mana_capacity(&wand.stats, &drawn));
+        }
+
+        // 呪文ひとつずつでは上限以内でも、1回の詠唱の合計が上限を超えれば不発になります
+        assert_eq!(simulation.casts.len(), 2);
+        assert!(simulation.casts[0].fizzled);
+        assert!(!simulation.casts[1].fizzled);
+    }
+
+    #[test]
+    fn simulation_does_not_change_wand() {
+        let registry = registry();
+        let mut wand = wand(&[Some(SpellType::MagicBolt), Some(SpellType::MagicBolt)]);
+        wand.index = 1;
+        wand.delay = 7;
+        simulate_wand(&registry, &wand);
+
+        assert_eq!(wand.index, 1);
+        assert_eq!(wand.delay, 7);
+    }
+}